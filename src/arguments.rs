@@ -11,6 +11,6 @@ pub(crate) struct Arguments {
 
 impl Arguments {
   pub(crate) fn run(self) -> Result {
-    self.subcommand.run(self.options)
+    self.subcommand.run(self.options.load_config()?)
   }
 }
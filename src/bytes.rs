@@ -52,6 +52,25 @@ impl FromStr for Bytes {
   }
 }
 
+impl<'de> Deserialize<'de> for Bytes {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+      Number(usize),
+      Text(String),
+    }
+
+    match Repr::deserialize(deserializer)? {
+      Repr::Number(n) => Ok(Bytes(n)),
+      Repr::Text(text) => text.parse().map_err(serde::de::Error::custom),
+    }
+  }
+}
+
 impl Display for Bytes {
   fn fmt(&self, f: &mut Formatter) -> fmt::Result {
     const DISPLAY_SUFFIXES: &[&str] = &["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
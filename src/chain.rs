@@ -37,6 +37,20 @@ impl Chain {
     }
   }
 
+  pub(crate) fn default_max_wallet_balance(self) -> Option<u64> {
+    match self {
+      Self::Mainnet => Some(1_000_000),
+      Self::Regtest | Self::Signet | Self::Testnet => None,
+    }
+  }
+
+  pub(crate) fn default_allowed_wallet_names(self) -> Vec<String> {
+    match self {
+      Self::Mainnet => vec!["ord".into(), "ord-*".into()],
+      Self::Regtest | Self::Signet | Self::Testnet => Vec::new(),
+    }
+  }
+
   pub(crate) fn genesis_block(self) -> Block {
     bitcoin::blockdata::constants::genesis_block(self.network())
   }
@@ -0,0 +1,234 @@
+use {
+  super::*,
+  std::{net::IpAddr, num::NonZeroU32},
+};
+
+/// Settings loaded from `ord.yaml` or `ord.toml`, which are used when the corresponding
+/// option is not given on the command line or in the environment. Keys are
+/// the long names of the corresponding options.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct Config {
+  pub(crate) bitcoin_data_dir: Option<PathBuf>,
+  pub(crate) chain: Option<Chain>,
+  pub(crate) cookie_file: Option<PathBuf>,
+  pub(crate) data_dir: Option<PathBuf>,
+  pub(crate) format: Option<Format>,
+  pub(crate) height_limit: Option<u64>,
+  pub(crate) index_fee_ranges: bool,
+  pub(crate) max_index_size: Option<Bytes>,
+  pub(crate) rpc_pass: Option<String>,
+  pub(crate) rpc_url: Option<String>,
+  pub(crate) rpc_user: Option<String>,
+  pub(crate) server: ServerConfig,
+  pub(crate) wallet_policy: WalletPolicyConfig,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct ServerConfig {
+  pub(crate) acme_cache: Option<PathBuf>,
+  pub(crate) acme_contact: Vec<String>,
+  pub(crate) acme_domain: Vec<String>,
+  pub(crate) address: Option<String>,
+  pub(crate) base_url: Option<String>,
+  pub(crate) http: Option<bool>,
+  pub(crate) http_port: Option<u16>,
+  pub(crate) https: Option<bool>,
+  pub(crate) https_port: Option<u16>,
+  pub(crate) max_expensive_queries: Option<usize>,
+  pub(crate) rate_limit: Option<NonZeroU32>,
+  pub(crate) read_only: bool,
+  pub(crate) trusted_proxy: Vec<IpAddr>,
+}
+
+/// Wallet policy settings for each chain, so that a single config file may
+/// be shared by instances running on different chains.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct WalletPolicyConfig {
+  pub(crate) mainnet: ChainWalletPolicyConfig,
+  pub(crate) regtest: ChainWalletPolicyConfig,
+  pub(crate) signet: ChainWalletPolicyConfig,
+  pub(crate) testnet: ChainWalletPolicyConfig,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct ChainWalletPolicyConfig {
+  pub(crate) allowed_wallet_name: Vec<String>,
+  pub(crate) max_transaction_value: Option<u64>,
+  pub(crate) max_wallet_balance: Option<u64>,
+}
+
+impl WalletPolicyConfig {
+  pub(crate) fn for_chain(&self, chain: Chain) -> &ChainWalletPolicyConfig {
+    match chain {
+      Chain::Mainnet => &self.mainnet,
+      Chain::Regtest => &self.regtest,
+      Chain::Signet => &self.signet,
+      Chain::Testnet => &self.testnet,
+    }
+  }
+}
+
+impl Config {
+  /// Load a config file, parsed as TOML if its extension is `.toml`, and as
+  /// YAML otherwise.
+  pub(crate) fn load(path: &Path) -> Result<Self> {
+    let config = fs::read_to_string(path)
+      .with_context(|| format!("failed to open config file `{}`", path.display()))?;
+
+    if path.extension() == Some("toml".as_ref()) {
+      toml::from_str(&config).map_err(Error::from)
+    } else {
+      serde_yaml::from_str(&config).map_err(Error::from)
+    }
+    .with_context(|| format!("failed to parse config file `{}`", path.display()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rate_limit_must_be_positive() {
+    assert!(serde_yaml::from_str::<Config>("server:\n  rate-limit: 0").is_err());
+  }
+
+  #[test]
+  fn empty_config_is_default() {
+    assert_eq!(
+      serde_yaml::from_str::<Config>("{}").unwrap(),
+      Config::default()
+    );
+  }
+
+  #[test]
+  fn all_fields() {
+    assert_eq!(
+      serde_yaml::from_str::<Config>(
+        "
+bitcoin-data-dir: /bitcoin
+chain: signet
+cookie-file: /cookie
+data-dir: /ord
+format: json
+height-limit: 100
+index-fee-ranges: true
+max-index-size: 1MiB
+rpc-pass: bar
+rpc-url: 127.0.0.1:1234
+rpc-user: foo
+server:
+  acme-cache: /acme
+  acme-contact: [mailto:foo@example.com]
+  acme-domain: [example.com]
+  address: 127.0.0.1
+  base-url: https://ordinals.com
+  http: false
+  http-port: 8080
+  https: true
+  https-port: 8443
+  max-expensive-queries: 2
+  rate-limit: 10
+  read-only: true
+  trusted-proxy: [127.0.0.1]
+wallet-policy:
+  mainnet:
+    allowed-wallet-name: [foo, bar-*]
+    max-transaction-value: 10
+    max-wallet-balance: 20
+  signet:
+    max-wallet-balance: 30
+"
+      )
+      .unwrap(),
+      Config {
+        bitcoin_data_dir: Some("/bitcoin".into()),
+        chain: Some(Chain::Signet),
+        cookie_file: Some("/cookie".into()),
+        data_dir: Some("/ord".into()),
+        format: Some(Format::Json),
+        height_limit: Some(100),
+        index_fee_ranges: true,
+        max_index_size: Some(Bytes::MIB),
+        rpc_pass: Some("bar".into()),
+        rpc_url: Some("127.0.0.1:1234".into()),
+        rpc_user: Some("foo".into()),
+        server: ServerConfig {
+          acme_cache: Some("/acme".into()),
+          acme_contact: vec!["mailto:foo@example.com".into()],
+          acme_domain: vec!["example.com".into()],
+          address: Some("127.0.0.1".into()),
+          base_url: Some("https://ordinals.com".into()),
+          http: Some(false),
+          http_port: Some(8080),
+          https: Some(true),
+          https_port: Some(8443),
+          max_expensive_queries: Some(2),
+          rate_limit: NonZeroU32::new(10),
+          read_only: true,
+          trusted_proxy: vec![[127, 0, 0, 1].into()],
+        },
+        wallet_policy: WalletPolicyConfig {
+          mainnet: ChainWalletPolicyConfig {
+            allowed_wallet_name: vec!["foo".into(), "bar-*".into()],
+            max_transaction_value: Some(10),
+            max_wallet_balance: Some(20),
+          },
+          signet: ChainWalletPolicyConfig {
+            max_wallet_balance: Some(30),
+            ..Default::default()
+          },
+          ..Default::default()
+        },
+      }
+    );
+  }
+
+  #[test]
+  fn toml() {
+    let tempdir = TempDir::new().unwrap();
+    let path = tempdir.path().join("ord.toml");
+    fs::write(
+      &path,
+      "
+chain = \"signet\"
+max-index-size = \"1MiB\"
+
+[server]
+acme-domain = [\"example.com\"]
+https = true
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+      Config::load(&path).unwrap(),
+      Config {
+        chain: Some(Chain::Signet),
+        max_index_size: Some(Bytes::MIB),
+        server: ServerConfig {
+          acme_domain: vec!["example.com".into()],
+          https: Some(true),
+          ..Default::default()
+        },
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn unknown_fields_are_rejected() {
+    assert!(serde_yaml::from_str::<Config>("foo: bar").is_err());
+  }
+
+  #[test]
+  fn unknown_wallet_policy_chains_are_rejected() {
+    assert!(
+      serde_yaml::from_str::<Config>("wallet-policy:\n  main:\n    max-wallet-balance: 1").is_err()
+    );
+  }
+}
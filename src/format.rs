@@ -0,0 +1,127 @@
+use {super::*, clap::ValueEnum};
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Format {
+  Json,
+  Tsv,
+  Text,
+}
+
+/// Output of a subcommand. JSON output is its serialization. TSV output is
+/// lines of tab-separated fields, using the same names and raw values as the
+/// JSON output. Text output is meant for humans, and is the same as TSV
+/// output unless a subcommand's text output predates `--format`.
+pub(crate) trait Output: Serialize {
+  fn tsv(&self) -> String;
+
+  fn text(&self) -> String {
+    self.tsv()
+  }
+}
+
+impl<T: Output> Output for Vec<T> {
+  fn tsv(&self) -> String {
+    self.iter().map(Output::tsv).collect()
+  }
+
+  fn text(&self) -> String {
+    self.iter().map(Output::text).collect()
+  }
+}
+
+impl Output for Ordinal {
+  fn tsv(&self) -> String {
+    format!("{self}\n")
+  }
+}
+
+impl Format {
+  pub(crate) fn render(self, output: &impl Output) -> Result<String> {
+    Ok(match self {
+      Self::Json => serde_json::to_string_pretty(output)? + "\n",
+      Self::Tsv => output.tsv(),
+      Self::Text => output.text(),
+    })
+  }
+
+  pub(crate) fn print(self, output: &impl Output) -> Result {
+    print!("{}", self.render(output)?);
+    Ok(())
+  }
+}
+
+/// Render the fields of a single record one per line as `key\tvalue`.
+pub(crate) fn fields_tsv(fields: &[(&str, String)]) -> String {
+  fields
+    .iter()
+    .map(|(key, value)| format!("{key}\t{value}\n"))
+    .collect()
+}
+
+/// Render the fields of a single record one per line as `key: value`.
+pub(crate) fn fields_text(fields: &[(&str, String)]) -> String {
+  fields
+    .iter()
+    .map(|(key, value)| format!("{key}: {value}\n"))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Serialize)]
+  struct Record {
+    foo: u64,
+    bar: String,
+  }
+
+  impl Output for Record {
+    fn tsv(&self) -> String {
+      format!("{}\t{}\n", self.foo, self.bar)
+    }
+
+    fn text(&self) -> String {
+      format!("{} {}\n", self.foo, self.bar)
+    }
+  }
+
+  fn records() -> Vec<Record> {
+    vec![
+      Record {
+        foo: 1,
+        bar: "a".into(),
+      },
+      Record {
+        foo: 2,
+        bar: "b".into(),
+      },
+    ]
+  }
+
+  #[test]
+  fn json() {
+    assert_eq!(
+      Format::Json.render(&records()).unwrap(),
+      "[\n  {\n    \"foo\": 1,\n    \"bar\": \"a\"\n  },\n  {\n    \"foo\": 2,\n    \"bar\": \"b\"\n  }\n]\n"
+    );
+  }
+
+  #[test]
+  fn tsv() {
+    assert_eq!(Format::Tsv.render(&records()).unwrap(), "1\ta\n2\tb\n");
+  }
+
+  #[test]
+  fn text() {
+    assert_eq!(Format::Text.render(&records()).unwrap(), "1 a\n2 b\n");
+  }
+
+  #[test]
+  fn fields() {
+    let fields = [("foo", "1".to_string()), ("bar baz", "a".to_string())];
+    assert_eq!(fields_tsv(&fields), "foo\t1\nbar baz\ta\n");
+    assert_eq!(fields_text(&fields), "foo: 1\nbar baz: a\n");
+  }
+}
@@ -1,27 +1,45 @@
+pub(crate) use self::block_stats::BlockStats;
+
 use {
   self::updater::Updater,
   super::*,
   bitcoin::consensus::encode::deserialize,
+  bitcoin::hashes::sha256,
   bitcoin::BlockHeader,
   bitcoincore_rpc::{json::GetBlockHeaderResult, Auth, Client},
   indicatif::{ProgressBar, ProgressStyle},
   log::log_enabled,
   redb::{Database, ReadableTable, Table, TableDefinition, WriteStrategy, WriteTransaction},
-  std::collections::HashMap,
+  std::collections::{HashMap, HashSet},
+  std::io::Read,
+  std::ops::Range,
   std::sync::atomic::{AtomicBool, Ordering},
 };
 
+mod block_stats;
 mod rtx;
 mod updater;
 
+const HEIGHT_TO_BLOCK_STATS: TableDefinition<u64, [u8]> =
+  TableDefinition::new("HEIGHT_TO_BLOCK_STATS");
+const HEIGHT_TO_RARE_ORDINAL_MOVES: TableDefinition<u64, [u8]> =
+  TableDefinition::new("HEIGHT_TO_RARE_ORDINAL_MOVES");
 const HEIGHT_TO_BLOCK_HASH: TableDefinition<u64, [u8; 32]> =
   TableDefinition::new("HEIGHT_TO_BLOCK_HASH");
+const HEIGHT_TO_WATCHED_TXIDS: TableDefinition<u64, [u8]> =
+  TableDefinition::new("HEIGHT_TO_WATCHED_TXIDS");
+const ORDINAL_RANGE_START_TO_OUTPOINT: TableDefinition<u64, [u8; 36]> =
+  TableDefinition::new("ORDINAL_RANGE_START_TO_OUTPOINT");
 const ORDINAL_TO_SATPOINT: TableDefinition<u64, [u8; 44]> =
   TableDefinition::new("ORDINAL_TO_SATPOINT");
 const OUTPOINT_TO_ORDINAL_RANGES: TableDefinition<[u8; 36], [u8]> =
   TableDefinition::new("OUTPOINT_TO_ORDINAL_RANGES");
 const RUNE_HASH_TO_RUNE: TableDefinition<[u8; 32], str> = TableDefinition::new("RUNE_HASH_TO_RUNE");
 const STATISTIC_TO_COUNT: TableDefinition<u64, u64> = TableDefinition::new("STATISTIC_TO_COUNT");
+const WATCHED_OUTPOINT_TO_SCRIPT_PUBKEY: TableDefinition<[u8; 36], [u8]> =
+  TableDefinition::new("WATCHED_OUTPOINT_TO_SCRIPT_PUBKEY");
+const WATCHED_SCRIPT_PUBKEY_HASH_TO_SCRIPT_PUBKEY: TableDefinition<[u8; 32], [u8]> =
+  TableDefinition::new("WATCHED_SCRIPT_PUBKEY_HASH_TO_SCRIPT_PUBKEY");
 
 fn encode_outpoint(outpoint: OutPoint) -> [u8; 36] {
   let mut array = [0; 36];
@@ -31,6 +49,44 @@ fn encode_outpoint(outpoint: OutPoint) -> [u8; 36] {
   array
 }
 
+/// Assign `input_ordinal_ranges` to outputs with `values` first-in-first-out,
+/// returning the ranges assigned to each output and the ranges left over. The
+/// updater and everything that predicts where ordinals go use this, so that
+/// they always agree.
+pub(crate) fn assign_ordinal_ranges(
+  mut input_ordinal_ranges: VecDeque<(u64, u64)>,
+  values: impl IntoIterator<Item = u64>,
+) -> (Vec<Vec<(u64, u64)>>, Vec<(u64, u64)>) {
+  let mut outputs = Vec::new();
+
+  for value in values {
+    let mut ranges = Vec::new();
+
+    let mut remaining = value;
+    while remaining > 0 {
+      let (start, end) = match input_ordinal_ranges.pop_front() {
+        Some(range) => range,
+        None => break,
+      };
+
+      let assigned = if end - start > remaining {
+        input_ordinal_ranges.push_front((start + remaining, end));
+        (start, start + remaining)
+      } else {
+        (start, end)
+      };
+
+      remaining -= assigned.1 - assigned.0;
+
+      ranges.push(assigned);
+    }
+
+    outputs.push(ranges);
+  }
+
+  (outputs, input_ordinal_ranges.into())
+}
+
 fn encode_satpoint(satpoint: SatPoint) -> [u8; 44] {
   let mut array = [0; 44];
   satpoint
@@ -42,15 +98,39 @@ fn encode_satpoint(satpoint: SatPoint) -> [u8; 44] {
 pub(crate) struct Index {
   auth: Auth,
   client: Client,
-  database: Database,
+  database: Mutex<OpenDatabase>,
   database_path: PathBuf,
   genesis_block_coinbase_transaction: Transaction,
   genesis_block_coinbase_txid: Txid,
   height_limit: Option<u64>,
+  index_fee_ranges: bool,
+  read_only: bool,
   reorged: AtomicBool,
   rpc_url: String,
 }
 
+/// The database, and the header of its file when it was opened, which a
+/// read-only index compares against to notice commits by other processes.
+struct OpenDatabase {
+  database: Arc<Database>,
+  header: Vec<u8>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Info {
+  pub(crate) blocks_indexed: u64,
+  pub(crate) utxos_indexed: usize,
+  pub(crate) outputs_traversed: u64,
+  pub(crate) ordinal_ranges: u64,
+  pub(crate) lost_ordinals: u64,
+  pub(crate) tree_height: usize,
+  pub(crate) free_pages: usize,
+  pub(crate) stored_bytes: usize,
+  pub(crate) overhead_bytes: usize,
+  pub(crate) fragmented_bytes: usize,
+  pub(crate) index_size_bytes: usize,
+}
+
 #[derive(Debug, PartialEq)]
 pub(crate) enum List {
   Spent,
@@ -63,6 +143,7 @@ pub(crate) enum Statistic {
   OutputsTraversed = 0,
   Commits = 1,
   OrdinalRanges = 2,
+  LostOrdinals = 3,
 }
 
 impl From<Statistic> for u64 {
@@ -96,22 +177,28 @@ impl<T> BitcoinCoreRpcResultExt<T> for Result<T, bitcoincore_rpc::Error> {
 
 impl Index {
   pub(crate) fn open(options: &Options) -> Result<Self> {
+    Self::open_with(options, false)
+  }
+
+  /// Open an existing index without ever writing to it, for serving an
+  /// index kept up to date by another process running `ord index --watch`.
+  pub(crate) fn open_read_only(options: &Options) -> Result<Self> {
+    Self::open_with(options, true)
+  }
+
+  fn open_with(options: &Options, read_only: bool) -> Result<Self> {
     let rpc_url = options.rpc_url();
-    let cookie_file = options.cookie_file()?;
 
     if cfg!(test) {
       // The default max database size is 10 MiB for Regtest and 1 TiB
       // for all other networks. A larger database takes longer to
       // initialize, so unit tests should use the regtest network.
-      assert_eq!(options.chain, Chain::Regtest);
+      assert_eq!(options.chain(), Chain::Regtest);
     }
 
-    log::info!(
-      "Connecting to Bitcoin Core RPC server at {rpc_url} using credentials from `{}`",
-      cookie_file.display()
-    );
+    log::info!("Connecting to Bitcoin Core RPC server at {rpc_url}");
 
-    let auth = Auth::CookieFile(cookie_file);
+    let auth = options.bitcoin_rpc_auth()?;
 
     let client = Client::new(&rpc_url, auth.clone()).context("failed to connect to RPC URL")?;
 
@@ -123,8 +210,18 @@ impl Index {
 
     let database_path = data_dir.join("index.redb");
 
+    // Read before opening, so that a commit in between makes a read-only
+    // index reopen the database rather than miss it
+    let header = Self::read_header(&database_path).unwrap_or_default();
+
     let database = match unsafe { redb::Database::open(&database_path) } {
       Ok(database) => database,
+      Err(redb::Error::Io(error)) if error.kind() == io::ErrorKind::NotFound && read_only => {
+        bail!(
+          "index `{}` does not exist, run `ord index` to create it",
+          database_path.display()
+        )
+      }
       Err(redb::Error::Io(error)) if error.kind() == io::ErrorKind::NotFound => unsafe {
         Database::builder()
           .set_write_strategy(if cfg!(test) {
@@ -137,41 +234,55 @@ impl Index {
       Err(error) => return Err(error.into()),
     };
 
-    let tx = database.begin_write()?;
-
-    #[cfg(test)]
-    let tx = {
-      let mut tx = tx;
-      tx.set_durability(redb::Durability::None);
-      tx
-    };
-
-    tx.open_table(RUNE_HASH_TO_RUNE)?;
-    tx.open_table(HEIGHT_TO_BLOCK_HASH)?;
-    tx.open_table(ORDINAL_TO_SATPOINT)?;
-    tx.open_table(OUTPOINT_TO_ORDINAL_RANGES)?;
-    tx.open_table(STATISTIC_TO_COUNT)?;
-
-    tx.commit()?;
+    if !read_only {
+      let tx = database.begin_write()?;
+
+      #[cfg(test)]
+      let tx = {
+        let mut tx = tx;
+        tx.set_durability(redb::Durability::None);
+        tx
+      };
+
+      tx.open_table(RUNE_HASH_TO_RUNE)?;
+      tx.open_table(HEIGHT_TO_BLOCK_HASH)?;
+      tx.open_table(HEIGHT_TO_WATCHED_TXIDS)?;
+      tx.open_table(HEIGHT_TO_BLOCK_STATS)?;
+      tx.open_table(HEIGHT_TO_RARE_ORDINAL_MOVES)?;
+      tx.open_table(ORDINAL_RANGE_START_TO_OUTPOINT)?;
+      tx.open_table(ORDINAL_TO_SATPOINT)?;
+      tx.open_table(OUTPOINT_TO_ORDINAL_RANGES)?;
+      tx.open_table(STATISTIC_TO_COUNT)?;
+      tx.open_table(WATCHED_OUTPOINT_TO_SCRIPT_PUBKEY)?;
+      tx.open_table(WATCHED_SCRIPT_PUBKEY_HASH_TO_SCRIPT_PUBKEY)?;
+
+      tx.commit()?;
+    }
 
     let genesis_block_coinbase_transaction =
-      options.chain.genesis_block().coinbase().unwrap().clone();
+      options.chain().genesis_block().coinbase().unwrap().clone();
 
     Ok(Self {
       genesis_block_coinbase_txid: genesis_block_coinbase_transaction.txid(),
       auth,
       client,
-      database,
+      database: Mutex::new(OpenDatabase {
+        database: Arc::new(database),
+        header,
+      }),
       database_path,
       genesis_block_coinbase_transaction,
-      height_limit: options.height_limit,
+      height_limit: options.height_limit(),
+      index_fee_ranges: options.index_fee_ranges(),
+      read_only,
       reorged: AtomicBool::new(false),
       rpc_url,
     })
   }
 
-  pub(crate) fn print_info(&self) -> Result {
-    let wtx = self.begin_write()?;
+  pub(crate) fn info(&self) -> Result<Info> {
+    let database = self.database()?;
+    let wtx = self.begin_write(&database)?;
 
     let blocks_indexed = wtx
       .open_table(HEIGHT_TO_BLOCK_HASH)?
@@ -181,7 +292,19 @@ impl Index {
       .map(|(height, _hash)| height + 1)
       .unwrap_or(0);
 
-    let utxos_indexed = wtx.open_table(OUTPOINT_TO_ORDINAL_RANGES)?.len()?;
+    let utxos_indexed = {
+      let outpoint_to_ordinal_ranges = wtx.open_table(OUTPOINT_TO_ORDINAL_RANGES)?;
+
+      // Sentinel outpoints holding lost ordinals aren't UTXOs
+      let lost = outpoint_to_ordinal_ranges
+        .range(
+          encode_outpoint(Self::lost_outpoint(0))
+            ..=encode_outpoint(Self::lost_outpoint(u32::MAX.into())),
+        )?
+        .count();
+
+      outpoint_to_ordinal_ranges.len()? - lost
+    };
 
     let ordinal_ranges = wtx
       .open_table(STATISTIC_TO_COUNT)?
@@ -193,25 +316,59 @@ impl Index {
       .get(&Statistic::OutputsTraversed.into())?
       .unwrap_or(0);
 
+    let lost_ordinals = wtx
+      .open_table(STATISTIC_TO_COUNT)?
+      .get(&Statistic::LostOrdinals.into())?
+      .unwrap_or(0);
+
     let stats = wtx.stats()?;
 
-    println!("blocks indexed\t{}", blocks_indexed);
-    println!("utxos indexed\t{}", utxos_indexed);
-    println!("outputs traversed\t{}", outputs_traversed);
-    println!("ordinal ranges\t{}", ordinal_ranges);
-    println!("tree height\t{}", stats.tree_height());
-    println!("free pages\t{}", stats.free_pages());
-    println!("stored\t{}", Bytes(stats.stored_bytes()));
-    println!("overhead\t{}", Bytes(stats.metadata_bytes()));
-    println!("fragmented\t{}", Bytes(stats.fragmented_bytes()));
-    println!(
-      "index size\t{}",
-      Bytes(std::fs::metadata(&self.database_path)?.len().try_into()?)
-    );
+    let info = Info {
+      blocks_indexed,
+      utxos_indexed,
+      outputs_traversed,
+      ordinal_ranges,
+      lost_ordinals,
+      tree_height: stats.tree_height(),
+      free_pages: stats.free_pages(),
+      stored_bytes: stats.stored_bytes(),
+      overhead_bytes: stats.metadata_bytes(),
+      fragmented_bytes: stats.fragmented_bytes(),
+      index_size_bytes: std::fs::metadata(&self.database_path)?.len().try_into()?,
+    };
 
     wtx.abort()?;
 
-    Ok(())
+    Ok(info)
+  }
+
+  /// Ordinals lost in a block, to an underpaid coinbase or an OP_RETURN
+  /// output, are recorded under a sentinel outpoint with an all-zero txid and
+  /// the block height as its vout.
+  pub(crate) fn lost_outpoint(height: u64) -> OutPoint {
+    OutPoint::new(Txid::all_zeros(), height as u32)
+  }
+
+  /// The height of the block in which ordinals recorded under `outpoint` were
+  /// lost, if it is a sentinel outpoint.
+  pub(crate) fn lost_in_block(outpoint: OutPoint) -> Option<u64> {
+    (outpoint.txid == Txid::all_zeros()).then(|| outpoint.vout.into())
+  }
+
+  /// Rare ordinals that moved in a block, and where to, as concatenated
+  /// 8-byte little-endian ordinals and 44-byte satpoints.
+  pub(crate) fn encode_rare_ordinal_moves(moves: &[(u64, SatPoint)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (ordinal, satpoint) in moves {
+      bytes.extend_from_slice(&ordinal.to_le_bytes());
+      bytes.extend_from_slice(&encode_satpoint(*satpoint));
+    }
+    bytes
+  }
+
+  pub(crate) fn encode_ordinal_range((start, end): (u64, u64)) -> [u8; 11] {
+    let n = start as u128 | ((end - start) as u128) << 51;
+    n.to_le_bytes()[0..11].try_into().unwrap()
   }
 
   pub(crate) fn decode_ordinal_range(bytes: [u8; 11]) -> (u64, u64) {
@@ -229,24 +386,76 @@ impl Index {
   }
 
   pub(crate) fn update(&self) -> Result {
+    if self.read_only {
+      bail!("cannot update read-only index");
+    }
+
     Updater::update(self)
   }
 
+  pub(crate) fn is_read_only(&self) -> bool {
+    self.read_only
+  }
+
   pub(crate) fn is_reorged(&self) -> bool {
     self.reorged.load(Ordering::Relaxed)
   }
 
-  fn begin_read(&self) -> Result<rtx::Rtx> {
-    Ok(rtx::Rtx(self.database.begin_read()?))
+  /// Every commit rewrites the header, which is in the first page of the
+  /// file.
+  fn read_header(database_path: &Path) -> Result<Vec<u8>> {
+    let mut header = Vec::new();
+    fs::File::open(database_path)?
+      .take(4096)
+      .read_to_end(&mut header)?;
+    Ok(header)
   }
 
-  fn begin_write(&self) -> Result<WriteTransaction> {
-    if cfg!(test) {
-      let mut tx = self.database.begin_write()?;
+  /// The database, reopened first if this index is read-only and another
+  /// process has committed since it was last opened, since an open redb
+  /// database never sees commits made through another one.
+  ///
+  /// Reopening while `ord index --watch` keeps writing is safe because redb
+  /// never overwrites pages reachable from the last commit: a commit writes
+  /// new pages and then switches the header to them, and pages freed by a
+  /// commit are only reused after the commit that follows it. A read
+  /// transaction begun just after reopening therefore sees a consistent
+  /// snapshot until the writer has committed twice more. Read transactions
+  /// only last as long as a request, which is cut off after at most
+  /// `Throttle::EXPENSIVE_TIMEOUT`, and once caught up `ord index --watch`
+  /// commits once per block, so this holds. While the writer is still
+  /// syncing it may commit more often than that, so read-only servers
+  /// should only be started against an index that has caught up.
+  ///
+  /// Callers must hold the returned handle for as long as their
+  /// transaction, so that a reopen can't close the database under them.
+  fn database(&self) -> Result<Arc<Database>> {
+    let mut open = self.database.lock().unwrap();
+
+    if self.read_only {
+      let header = Self::read_header(&self.database_path)?;
+
+      if header != open.header {
+        log::info!("Reopening index after commit by another process");
+        *open = OpenDatabase {
+          database: Arc::new(unsafe { Database::open(&self.database_path)? }),
+          header,
+        };
+      }
+    }
+
+    Ok(open.database.clone())
+  }
+
+  fn begin_write<'a>(&self, database: &'a Database) -> Result<WriteTransaction<'a>> {
+    if self.read_only {
+      bail!("cannot write to read-only index");
+    } else if cfg!(test) {
+      let mut tx = database.begin_write()?;
       tx.set_durability(redb::Durability::None);
       Ok(tx)
     } else {
-      Ok(self.database.begin_write()?)
+      Ok(database.begin_write()?)
     }
   }
 
@@ -263,7 +472,7 @@ impl Index {
   pub(crate) fn statistic(&self, statistic: Statistic) -> Result<u64> {
     Ok(
       self
-        .database
+        .database()?
         .begin_read()?
         .open_table(STATISTIC_TO_COUNT)?
         .get(&(statistic.into()))?
@@ -272,20 +481,25 @@ impl Index {
   }
 
   pub(crate) fn height(&self) -> Result<Height> {
-    Ok(Height(self.begin_read()?.height()?))
+    Ok(Height(rtx::Rtx(self.database()?.begin_read()?).height()?))
   }
 
   pub(crate) fn blocks(&self, take: u64) -> Result<Vec<(u64, BlockHash)>> {
-    let mut blocks = Vec::new();
+    let height = rtx::Rtx(self.database()?.begin_read()?).height()?;
+    self.blocks_before(height + 1, take)
+  }
 
-    let rtx = self.begin_read()?;
+  /// Up to `take` of the blocks below height `before`, highest first.
+  pub(crate) fn blocks_before(&self, before: u64, take: u64) -> Result<Vec<(u64, BlockHash)>> {
+    let mut blocks = Vec::new();
 
-    let height = rtx.height()?;
+    let database = self.database()?;
+    let rtx = database.begin_read()?;
 
-    let height_to_block_hash = rtx.0.open_table(HEIGHT_TO_BLOCK_HASH)?;
+    let height_to_block_hash = rtx.open_table(HEIGHT_TO_BLOCK_HASH)?;
 
     for next in height_to_block_hash
-      .range(height.saturating_sub(take.saturating_sub(1))..=height)?
+      .range(before.saturating_sub(take)..before)?
       .rev()
     {
       blocks.push((next.0, BlockHash::from_slice(next.1)?));
@@ -294,20 +508,80 @@ impl Index {
     Ok(blocks)
   }
 
-  pub(crate) fn rare_ordinal_satpoints(&self) -> Result<Vec<(Ordinal, SatPoint)>> {
-    let mut result = Vec::new();
+  /// Statistics for the block at `height`, which are missing for blocks
+  /// indexed before block statistics were recorded.
+  pub(crate) fn block_stats(&self, height: u64) -> Result<Option<BlockStats>> {
+    self
+      .database()?
+      .begin_read()?
+      .open_table(HEIGHT_TO_BLOCK_STATS)?
+      .get(&height)?
+      .map(BlockStats::decode)
+      .transpose()
+  }
 
-    let rtx = self.database.begin_read()?;
+  pub(crate) fn rare_ordinal_satpoints(
+    &self,
+    ordinals: Range<u64>,
+  ) -> Result<Vec<(Ordinal, SatPoint)>> {
+    self.rare_ordinal_satpoints_matching(ordinals, |_| true, 0, usize::MAX)
+  }
+
+  /// Rare ordinals in `ordinals` that match `filter`, skipping the first
+  /// `skip` matches and stopping the scan once `take` more have been found.
+  pub(crate) fn rare_ordinal_satpoints_matching(
+    &self,
+    ordinals: Range<u64>,
+    filter: impl Fn(Ordinal) -> bool,
+    skip: usize,
+    take: usize,
+  ) -> Result<Vec<(Ordinal, SatPoint)>> {
+    let database = self.database()?;
+    let rtx = database.begin_read()?;
 
     let ordinal_to_satpoint = rtx.open_table(ORDINAL_TO_SATPOINT)?;
 
-    for (ordinal, satpoint) in ordinal_to_satpoint.range(0..)? {
+    let mut result = Vec::new();
+
+    for (ordinal, satpoint) in ordinal_to_satpoint
+      .range(ordinals)?
+      .filter(|(ordinal, _satpoint)| filter(Ordinal(*ordinal)))
+      .skip(skip)
+      .take(take)
+    {
       result.push((Ordinal(ordinal), deserialize(satpoint)?));
     }
 
     Ok(result)
   }
 
+  /// Up to `take` of the latest moves of rare ordinals, including their
+  /// creation in coinbase transactions, newest block first.
+  pub(crate) fn rare_ordinal_moves(&self, take: usize) -> Result<Vec<(u64, Ordinal, SatPoint)>> {
+    let mut moves = Vec::new();
+
+    let database = self.database()?;
+    let rtx = database.begin_read()?;
+
+    let height_to_rare_ordinal_moves = rtx.open_table(HEIGHT_TO_RARE_ORDINAL_MOVES)?;
+
+    for (height, bytes) in height_to_rare_ordinal_moves.range(0..)?.rev() {
+      for chunk in bytes.chunks_exact(52) {
+        if moves.len() == take {
+          return Ok(moves);
+        }
+
+        moves.push((
+          height,
+          Ordinal(u64::from_le_bytes(chunk[..8].try_into().unwrap())),
+          deserialize(&chunk[8..])?,
+        ));
+      }
+    }
+
+    Ok(moves)
+  }
+
   pub(crate) fn block(&self, height: u64) -> Result<Option<Block>> {
     Ok(
       self
@@ -323,6 +597,18 @@ impl Index {
     self.client.get_block_header(&hash).into_option()
   }
 
+  pub(crate) fn block_hash(&self, height: u64) -> Result<Option<BlockHash>> {
+    Ok(
+      self
+        .database()?
+        .begin_read()?
+        .open_table(HEIGHT_TO_BLOCK_HASH)?
+        .get(&height)?
+        .map(|hash| BlockHash::from_slice(hash))
+        .transpose()?,
+    )
+  }
+
   pub(crate) fn block_header_info(&self, hash: BlockHash) -> Result<Option<GetBlockHeaderResult>> {
     self.client.get_block_header_info(&hash).into_option()
   }
@@ -339,6 +625,62 @@ impl Index {
     }
   }
 
+  /// The flow of ordinal ranges through `transaction`, built from the ranges
+  /// of whichever of its previous outputs and outputs are unspent.
+  pub(crate) fn transaction_flow(&self, transaction: &Transaction) -> Result<TransactionFlow> {
+    let database = self.database()?;
+    let rtx = database.begin_read()?;
+
+    let outpoint_to_ordinal_ranges = rtx.open_table(OUTPOINT_TO_ORDINAL_RANGES)?;
+
+    let ordinal_ranges = |outpoint: OutPoint| -> Result<Option<Vec<(u64, u64)>>> {
+      Ok(
+        outpoint_to_ordinal_ranges
+          .get(&encode_outpoint(outpoint))?
+          .map(|ordinal_ranges| {
+            ordinal_ranges
+              .chunks_exact(11)
+              .map(|chunk| Self::decode_ordinal_range(chunk.try_into().unwrap()))
+              .collect()
+          }),
+      )
+    };
+
+    let input_ranges = transaction
+      .input
+      .iter()
+      .map(|input| ordinal_ranges(input.previous_output))
+      .collect::<Result<Vec<_>>>()?;
+
+    let txid = transaction.txid();
+
+    let output_ranges = (0..transaction.output.len())
+      .map(|vout| ordinal_ranges(OutPoint::new(txid, vout as u32)))
+      .collect::<Result<Vec<_>>>()?;
+
+    Ok(TransactionFlow::new(
+      transaction,
+      input_ranges,
+      output_ranges,
+    ))
+  }
+
+  /// Outpoints spent by transactions in the mempool. Bitcoin Core may be
+  /// ahead of the index, but outpoints spent in blocks not yet indexed are
+  /// not included, since their spending transactions have left the mempool.
+  /// Transactions that leave the mempool while this runs are skipped.
+  pub(crate) fn mempool_spent_outpoints(&self) -> Result<HashSet<OutPoint>> {
+    let mut outpoints = HashSet::new();
+
+    for txid in self.client.get_raw_mempool()? {
+      if let Some(transaction) = self.client.get_raw_transaction(&txid, None).into_option()? {
+        outpoints.extend(transaction.input.iter().map(|tx_in| tx_in.previous_output));
+      }
+    }
+
+    Ok(outpoints)
+  }
+
   pub(crate) fn is_transaction_in_active_chain(&self, txid: Txid) -> Result<bool> {
     Ok(
       self
@@ -354,12 +696,34 @@ impl Index {
     )
   }
 
+  /// Height of the block that confirmed `txid`, or `None` if it is
+  /// unconfirmed or unknown.
+  pub(crate) fn transaction_height(&self, txid: Txid) -> Result<Option<u64>> {
+    let blockhash = match self
+      .client
+      .get_raw_transaction_info(&txid, None)
+      .into_option()?
+      .and_then(|transaction_info| transaction_info.blockhash)
+    {
+      Some(blockhash) => blockhash,
+      None => return Ok(None),
+    };
+
+    Ok(
+      self
+        .block_header_info(blockhash)?
+        .filter(|info| info.confirmations >= 0)
+        .map(|info| info.height as u64),
+    )
+  }
+
   pub(crate) fn find(&self, ordinal: u64) -> Result<Option<SatPoint>> {
     if self.height()? < Ordinal(ordinal).height() {
       return Ok(None);
     }
 
-    let rtx = self.database.begin_read()?;
+    let database = self.database()?;
+    let rtx = database.begin_read()?;
 
     let outpoint_to_ordinal_ranges = rtx.open_table(OUTPOINT_TO_ORDINAL_RANGES)?;
 
@@ -381,10 +745,63 @@ impl Index {
     Ok(None)
   }
 
+  /// The `take` outputs holding the lowest pieces of the ordinal range
+  /// `ordinals`, along with those pieces, ordered by the first piece.
+  pub(crate) fn range_outputs(
+    &self,
+    ordinals: Range<u64>,
+    take: usize,
+  ) -> Result<Vec<(OutPoint, Vec<(u64, u64)>)>> {
+    let database = self.database()?;
+    let rtx = database.begin_read()?;
+
+    let ordinal_range_start_to_outpoint = rtx.open_table(ORDINAL_RANGE_START_TO_OUTPOINT)?;
+    let outpoint_to_ordinal_ranges = rtx.open_table(OUTPOINT_TO_ORDINAL_RANGES)?;
+
+    // The range holding the first ordinal may start before it
+    let holding_first = ordinal_range_start_to_outpoint
+      .range(0..ordinals.start)?
+      .rev()
+      .next();
+
+    let mut seen = HashSet::new();
+    let mut outputs = Vec::new();
+
+    for (_start, outpoint) in holding_first
+      .into_iter()
+      .chain(ordinal_range_start_to_outpoint.range(ordinals.clone())?)
+    {
+      if outputs.len() == take {
+        break;
+      }
+
+      let outpoint: [u8; 36] = outpoint.as_slice().try_into().unwrap();
+
+      if !seen.insert(outpoint) {
+        continue;
+      }
+
+      let pieces = outpoint_to_ordinal_ranges
+        .get(&outpoint)?
+        .ok_or_else(|| anyhow!("ordinal range start index refers to missing output"))?
+        .chunks_exact(11)
+        .map(|chunk| Index::decode_ordinal_range(chunk.try_into().unwrap()))
+        .filter(|(start, end)| *start < ordinals.end && ordinals.start < *end)
+        .map(|(start, end)| (start.max(ordinals.start), end.min(ordinals.end)))
+        .collect::<Vec<(u64, u64)>>();
+
+      if !pieces.is_empty() {
+        outputs.push((deserialize(outpoint.as_slice())?, pieces));
+      }
+    }
+
+    Ok(outputs)
+  }
+
   pub(crate) fn list_inner(&self, outpoint: &[u8]) -> Result<Option<Vec<u8>>> {
     Ok(
       self
-        .database
+        .database()?
         .begin_read()?
         .open_table(OUTPOINT_TO_ORDINAL_RANGES)?
         .get(outpoint.try_into().unwrap())?
@@ -414,13 +831,118 @@ impl Index {
     }
   }
 
+  /// Track unspent outputs paying to `script_pubkey`. Only outputs in blocks
+  /// indexed after this call are tracked, so scripts must be watched before
+  /// they receive funds.
+  pub(crate) fn watch(&self, script_pubkey: &Script) -> Result {
+    let database = self.database()?;
+    let wtx = self.begin_write(&database)?;
+
+    wtx
+      .open_table(WATCHED_SCRIPT_PUBKEY_HASH_TO_SCRIPT_PUBKEY)?
+      .insert(
+        &sha256::Hash::hash(script_pubkey.as_bytes()).into_inner(),
+        script_pubkey.as_bytes(),
+      )?;
+
+    wtx.commit()?;
+
+    Ok(())
+  }
+
+  /// Track `outpoint`, an existing output paying to a watched script, which
+  /// was indexed before the script was watched, in the block at `height`.
+  pub(crate) fn watch_output(
+    &self,
+    outpoint: OutPoint,
+    script_pubkey: &Script,
+    height: u64,
+  ) -> Result {
+    let database = self.database()?;
+    let wtx = self.begin_write(&database)?;
+
+    wtx
+      .open_table(WATCHED_OUTPOINT_TO_SCRIPT_PUBKEY)?
+      .insert(&encode_outpoint(outpoint), script_pubkey.as_bytes())?;
+
+    {
+      let mut height_to_watched_txids = wtx.open_table(HEIGHT_TO_WATCHED_TXIDS)?;
+
+      let mut txids = height_to_watched_txids
+        .get(&height)?
+        .map(|txids| txids.to_vec())
+        .unwrap_or_default();
+
+      if !txids
+        .chunks_exact(32)
+        .any(|txid| txid == outpoint.txid.as_inner())
+      {
+        txids.extend_from_slice(outpoint.txid.as_inner());
+        height_to_watched_txids.insert(&height, &txids)?;
+      }
+    }
+
+    wtx.commit()?;
+
+    Ok(())
+  }
+
+  pub(crate) fn watched_outputs(&self) -> Result<Vec<(OutPoint, Script)>> {
+    let database = self.database()?;
+    let rtx = database.begin_read()?;
+
+    let watched_outpoint_to_script_pubkey = rtx.open_table(WATCHED_OUTPOINT_TO_SCRIPT_PUBKEY)?;
+
+    let mut outputs = Vec::new();
+
+    for (outpoint, script_pubkey) in watched_outpoint_to_script_pubkey.range([0; 36]..)? {
+      outputs.push((
+        deserialize(outpoint.as_slice())?,
+        Script::from(script_pubkey.to_vec()),
+      ));
+    }
+
+    Ok(outputs)
+  }
+
+  /// Transactions that paid to or spent outputs paying to watched scripts,
+  /// oldest first, along with the heights of the blocks they are in.
+  pub(crate) fn watched_transactions(&self) -> Result<Vec<(u64, Txid)>> {
+    let database = self.database()?;
+    let rtx = database.begin_read()?;
+
+    let height_to_watched_txids = rtx.open_table(HEIGHT_TO_WATCHED_TXIDS)?;
+
+    let mut transactions = Vec::new();
+
+    for (height, txids) in height_to_watched_txids.range(0..)? {
+      for txid in txids.chunks_exact(32) {
+        transactions.push((height, Txid::from_slice(txid)?));
+      }
+    }
+
+    Ok(transactions)
+  }
+
+  pub(crate) fn is_watched(&self, script_pubkey: &Script) -> Result<bool> {
+    Ok(
+      self
+        .database()?
+        .begin_read()?
+        .open_table(WATCHED_SCRIPT_PUBKEY_HASH_TO_SCRIPT_PUBKEY)?
+        .get(&sha256::Hash::hash(script_pubkey.as_bytes()).into_inner())?
+        .is_some(),
+    )
+  }
+
   pub(crate) fn blocktime(&self, height: Height) -> Result<Blocktime> {
     let height = height.n();
 
     match self.block(height)? {
       Some(block) => Ok(Blocktime::Confirmed(block.header.time.into())),
       None => {
-        let tx = self.database.begin_read()?;
+        let database = self.database()?;
+        let tx = database.begin_read()?;
 
         let current = tx
           .open_table(HEIGHT_TO_BLOCK_HASH)?
@@ -492,6 +1014,113 @@ mod tests {
     }
   }
 
+  #[test]
+  fn ordinal_ranges_are_assigned_first_in_first_out() {
+    assert_eq!(
+      assign_ordinal_ranges(VecDeque::from([(0, 10), (20, 30)]), [5, 10]),
+      (vec![vec![(0, 5)], vec![(5, 10), (20, 25)]], vec![(25, 30)])
+    );
+  }
+
+  #[test]
+  fn ordinal_ranges_may_all_go_to_one_output() {
+    assert_eq!(
+      assign_ordinal_ranges(VecDeque::from([(0, 10), (50, 60), (100, 110)]), [30]),
+      (vec![vec![(0, 10), (50, 60), (100, 110)]], Vec::new())
+    );
+  }
+
+  #[test]
+  fn outputs_exceeding_inputs_are_left_short() {
+    assert_eq!(
+      assign_ordinal_ranges(VecDeque::from([(0, 10)]), [5, 10, 5]),
+      (vec![vec![(0, 5)], vec![(5, 10)], vec![]], Vec::new())
+    );
+  }
+
+  #[test]
+  fn block_stats_are_recorded() {
+    let context = Context::with_args("--index-fee-ranges");
+
+    context.rpc_server.mine_blocks(1);
+    context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 100,
+    });
+    context.rpc_server.mine_blocks(1);
+    context.index.update().unwrap();
+
+    assert_eq!(
+      context.index.block_stats(1).unwrap().unwrap(),
+      BlockStats {
+        subsidy: 50 * COIN_VALUE,
+        rare_ordinals_mined: 1,
+        ordinal_ranges_created: 1,
+        ..Default::default()
+      }
+    );
+
+    assert_eq!(
+      context.index.block_stats(2).unwrap().unwrap(),
+      BlockStats {
+        subsidy: 50 * COIN_VALUE,
+        fees: 100,
+        rare_ordinals_mined: 1,
+        rare_ordinals_moved: 1,
+        ordinal_ranges_created: 3,
+        fee_ranges: vec![(100 * COIN_VALUE - 100, 100 * COIN_VALUE)],
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn fee_ranges_are_only_recorded_if_requested() {
+    let context = Context::new();
+
+    context.rpc_server.mine_blocks(1);
+    context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 100,
+    });
+    context.rpc_server.mine_blocks(1);
+    context.index.update().unwrap();
+
+    let stats = context.index.block_stats(2).unwrap().unwrap();
+    assert_eq!(stats.fees, 100);
+    assert_eq!(stats.fee_ranges, Vec::new());
+  }
+
+  #[test]
+  fn outputs_spent_in_blocks_not_yet_indexed_are_not_spent_in_mempool() {
+    let context = Context::with_args("--height-limit 1");
+
+    let outpoint = OutPoint::new(context.rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+    context.index.update().unwrap();
+
+    context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 0,
+    });
+    assert!(context
+      .index
+      .mempool_spent_outpoints()
+      .unwrap()
+      .contains(&outpoint));
+
+    context.rpc_server.mine_blocks(1);
+    context.index.update().unwrap();
+    assert_eq!(context.index.height().unwrap(), 1);
+    assert!(!context
+      .index
+      .mempool_spent_outpoints()
+      .unwrap()
+      .contains(&outpoint));
+  }
+
   #[test]
   fn height_limit() {
     {
@@ -722,6 +1351,67 @@ mod tests {
     );
   }
 
+  #[test]
+  fn is_watched() {
+    let context = Context::new();
+    assert!(!context.index.is_watched(&Script::new()).unwrap());
+    context.index.watch(&Script::new()).unwrap();
+    assert!(context.index.is_watched(&Script::new()).unwrap());
+  }
+
+  #[test]
+  fn watched_outputs_are_tracked_until_spent() {
+    let context = Context::new();
+    context.index.watch(&Script::new()).unwrap();
+    let coinbase = context.rpc_server.mine_blocks(1)[0].txdata[0].txid();
+    context.index.update().unwrap();
+
+    assert_eq!(
+      context.index.watched_outputs().unwrap(),
+      vec![(OutPoint::new(coinbase, 0), Script::new())]
+    );
+
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 0,
+    });
+    let coinbase = context.rpc_server.mine_blocks(1)[0].txdata[0].txid();
+    context.index.update().unwrap();
+
+    let mut expected = vec![
+      (OutPoint::new(coinbase, 0), Script::new()),
+      (OutPoint::new(txid, 0), Script::new()),
+    ];
+    expected.sort();
+
+    let mut actual = context.index.watched_outputs().unwrap();
+    actual.sort();
+
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn transactions_paying_to_or_spending_watched_outputs_are_recorded() {
+    let context = Context::new();
+    context.index.watch(&Script::new()).unwrap();
+    let first = context.rpc_server.mine_blocks(1)[0].txdata[0].txid();
+    context.index.update().unwrap();
+
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 0,
+    });
+    let second = context.rpc_server.mine_blocks(1)[0].txdata[0].txid();
+    context.index.update().unwrap();
+
+    assert_eq!(
+      context.index.watched_transactions().unwrap(),
+      vec![(1, first), (2, second), (2, txid)]
+    );
+  }
+
   #[test]
   fn list_unknown_output() {
     let context = Context::new();
@@ -808,4 +1498,201 @@ mod tests {
       }
     )
   }
+
+  #[test]
+  fn find_ordinal_lost_to_underpaid_coinbase() {
+    let context = Context::new();
+    context
+      .rpc_server
+      .mine_blocks_with_subsidy(1, 25 * COIN_VALUE);
+    context.index.update().unwrap();
+
+    assert_eq!(
+      context.index.find(80 * COIN_VALUE).unwrap().unwrap(),
+      SatPoint {
+        outpoint: Index::lost_outpoint(1),
+        offset: 5 * COIN_VALUE,
+      }
+    );
+
+    assert_eq!(Index::lost_in_block(Index::lost_outpoint(1)), Some(1));
+
+    assert_eq!(
+      context.index.block_stats(1).unwrap().unwrap().lost,
+      25 * COIN_VALUE
+    );
+
+    assert_eq!(
+      context.index.statistic(Statistic::LostOrdinals).unwrap(),
+      25 * COIN_VALUE
+    );
+  }
+
+  #[test]
+  fn lost_ordinals_are_not_counted_as_utxos() {
+    let context = Context::new();
+    let utxos_indexed = context.index.info().unwrap().utxos_indexed;
+
+    context
+      .rpc_server
+      .mine_blocks_with_subsidy(1, 25 * COIN_VALUE);
+    context.index.update().unwrap();
+
+    assert_eq!(
+      context.index.info().unwrap().utxos_indexed,
+      utxos_indexed + 1
+    );
+  }
+
+  #[test]
+  fn find_ordinal_lost_to_op_return() {
+    let context = Context::new();
+    context.rpc_server.mine_blocks_with_script_pubkey(
+      1,
+      bitcoin::blockdata::script::Builder::new()
+        .push_opcode(bitcoin::blockdata::opcodes::all::OP_RETURN)
+        .into_script(),
+    );
+    context.index.update().unwrap();
+
+    assert_eq!(
+      context.index.find(50 * COIN_VALUE).unwrap().unwrap(),
+      SatPoint {
+        outpoint: Index::lost_outpoint(1),
+        offset: 0,
+      }
+    );
+
+    assert_eq!(
+      context
+        .index
+        .rare_ordinal_satpoints(0..Ordinal::SUPPLY)
+        .unwrap()[1],
+      (
+        Ordinal(50 * COIN_VALUE),
+        SatPoint {
+          outpoint: Index::lost_outpoint(1),
+          offset: 0,
+        }
+      )
+    );
+
+    assert_eq!(
+      context.index.statistic(Statistic::LostOrdinals).unwrap(),
+      50 * COIN_VALUE
+    );
+  }
+
+  #[test]
+  fn ordinary_outputs_are_not_lost() {
+    assert_eq!(
+      Index::lost_in_block(
+        "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0"
+          .parse()
+          .unwrap()
+      ),
+      None
+    );
+  }
+
+  #[test]
+  fn rare_ordinal_moves_are_recorded_newest_first() {
+    let context = Context::new();
+    let coinbase = context.rpc_server.mine_blocks(1)[0].txdata[0].txid();
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 0,
+    });
+    let block = context.rpc_server.mine_blocks(1)[0].txdata[0].txid();
+    context.index.update().unwrap();
+
+    let satpoint = |txid| SatPoint {
+      outpoint: OutPoint::new(txid, 0),
+      offset: 0,
+    };
+
+    assert_eq!(
+      context.index.rare_ordinal_moves(3).unwrap(),
+      vec![
+        (2, Ordinal(50 * COIN_VALUE), satpoint(txid)),
+        (2, Ordinal(100 * COIN_VALUE), satpoint(block)),
+        (1, Ordinal(50 * COIN_VALUE), satpoint(coinbase)),
+      ]
+    );
+  }
+
+  #[test]
+  fn range_outputs_are_clipped_to_range() {
+    let context = Context::new();
+    context.rpc_server.mine_blocks(1);
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 2,
+      fee: 0,
+    });
+    context.rpc_server.mine_blocks(1);
+    context.index.update().unwrap();
+
+    assert_eq!(
+      context
+        .index
+        .range_outputs(50 * COIN_VALUE - 1..75 * COIN_VALUE + 1, 10)
+        .unwrap(),
+      vec![
+        (
+          OutPoint::new(context.index.genesis_block_coinbase_txid, 0),
+          vec![(50 * COIN_VALUE - 1, 50 * COIN_VALUE)]
+        ),
+        (
+          OutPoint::new(txid, 0),
+          vec![(50 * COIN_VALUE, 75 * COIN_VALUE)]
+        ),
+        (
+          OutPoint::new(txid, 1),
+          vec![(75 * COIN_VALUE, 75 * COIN_VALUE + 1)]
+        ),
+      ]
+    );
+
+    assert_eq!(
+      context
+        .index
+        .range_outputs(0..100 * COIN_VALUE, 2)
+        .unwrap()
+        .into_iter()
+        .map(|(outpoint, _pieces)| outpoint)
+        .collect::<Vec<OutPoint>>(),
+      vec![
+        OutPoint::new(context.index.genesis_block_coinbase_txid, 0),
+        OutPoint::new(txid, 0),
+      ]
+    );
+  }
+
+  #[test]
+  fn range_outputs_forget_spent_outputs() {
+    let context = Context::new();
+    context.rpc_server.mine_blocks(1);
+    context.index.update().unwrap();
+
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 0,
+    });
+    context.rpc_server.mine_blocks(1);
+    context.index.update().unwrap();
+
+    assert_eq!(
+      context
+        .index
+        .range_outputs(50 * COIN_VALUE..100 * COIN_VALUE, 10)
+        .unwrap(),
+      vec![(
+        OutPoint::new(txid, 0),
+        vec![(50 * COIN_VALUE, 100 * COIN_VALUE)]
+      )]
+    );
+  }
 }
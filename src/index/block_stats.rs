@@ -0,0 +1,94 @@
+use super::*;
+
+/// Statistics recorded by the updater for each block. The fee ranges
+/// collected by its coinbase can't be recovered from the index once the
+/// outputs they were spent from are gone, but take up space for every
+/// transaction, so they are only recorded with `--index-fee-ranges`.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub(crate) struct BlockStats {
+  pub(crate) subsidy: u64,
+  pub(crate) fees: u64,
+  pub(crate) lost: u64,
+  pub(crate) rare_ordinals_mined: u64,
+  pub(crate) rare_ordinals_moved: u64,
+  pub(crate) ordinal_ranges_created: u64,
+  pub(crate) fee_ranges: Vec<(u64, u64)>,
+}
+
+const HEADER_LEN: usize = 6 * 8;
+
+impl BlockStats {
+  pub(crate) fn encode(&self) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + self.fee_ranges.len() * 11);
+
+    for n in [
+      self.subsidy,
+      self.fees,
+      self.lost,
+      self.rare_ordinals_mined,
+      self.rare_ordinals_moved,
+      self.ordinal_ranges_created,
+    ] {
+      bytes.extend_from_slice(&n.to_le_bytes());
+    }
+
+    for range in &self.fee_ranges {
+      bytes.extend_from_slice(&Index::encode_ordinal_range(*range));
+    }
+
+    bytes
+  }
+
+  pub(crate) fn decode(bytes: &[u8]) -> Result<Self> {
+    if bytes.len() < HEADER_LEN || (bytes.len() - HEADER_LEN) % 11 != 0 {
+      bail!("invalid block stats length: {}", bytes.len());
+    }
+
+    let field = |i: usize| u64::from_le_bytes(bytes[i * 8..(i + 1) * 8].try_into().unwrap());
+
+    Ok(Self {
+      subsidy: field(0),
+      fees: field(1),
+      lost: field(2),
+      rare_ordinals_mined: field(3),
+      rare_ordinals_moved: field(4),
+      ordinal_ranges_created: field(5),
+      fee_ranges: bytes[HEADER_LEN..]
+        .chunks_exact(11)
+        .map(|chunk| Index::decode_ordinal_range(chunk.try_into().unwrap()))
+        .collect(),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn round_trip() {
+    let stats = BlockStats {
+      subsidy: 1,
+      fees: 2,
+      lost: 3,
+      rare_ordinals_mined: 4,
+      rare_ordinals_moved: 5,
+      ordinal_ranges_created: 6,
+      fee_ranges: vec![(10, 20), (2099999997689999, 2099999997690000)],
+    };
+
+    assert_eq!(BlockStats::decode(&stats.encode()).unwrap(), stats);
+  }
+
+  #[test]
+  fn invalid_length() {
+    assert_eq!(
+      BlockStats::decode(&[0; 47]).unwrap_err().to_string(),
+      "invalid block stats length: 47"
+    );
+    assert_eq!(
+      BlockStats::decode(&[0; 50]).unwrap_err().to_string(),
+      "invalid block stats length: 50"
+    );
+  }
+}
@@ -1,4 +1,7 @@
-use {super::*, std::sync::mpsc};
+use {
+  super::*,
+  std::{collections::HashSet, mem, sync::mpsc},
+};
 
 pub struct Updater {
   cache: HashMap<[u8; 36], Vec<u8>>,
@@ -7,11 +10,15 @@ pub struct Updater {
   ordinal_ranges_since_flush: u64,
   outputs_inserted_since_flush: u64,
   height: u64,
+  lost_ordinals_since_flush: u64,
+  rare_ordinal_moves: Vec<(u64, SatPoint)>,
+  watched: HashSet<Script>,
 }
 
 impl Updater {
   pub(crate) fn update(index: &Index) -> Result {
-    let wtx = index.begin_write()?;
+    let database = index.database()?;
+    let wtx = index.begin_write(&database)?;
 
     let height = wtx
       .open_table(HEIGHT_TO_BLOCK_HASH)?
@@ -21,6 +28,12 @@ impl Updater {
       .map(|(height, _hash)| height + 1)
       .unwrap_or(0);
 
+    let watched = wtx
+      .open_table(WATCHED_SCRIPT_PUBKEY_HASH_TO_SCRIPT_PUBKEY)?
+      .range([0; 32]..)?
+      .map(|(_hash, script_pubkey)| Script::from(script_pubkey.to_vec()))
+      .collect();
+
     let mut updater = Self {
       cache: HashMap::new(),
       outputs_traversed: 0,
@@ -28,15 +41,19 @@ impl Updater {
       outputs_inserted_since_flush: 0,
       ordinal_ranges_since_flush: 0,
       height,
+      lost_ordinals_since_flush: 0,
+      rare_ordinal_moves: Vec::new(),
+      watched,
     };
 
-    updater.update_index(index, wtx)
+    updater.update_index(index, &database, wtx)
   }
 
-  pub(crate) fn update_index<'index>(
+  pub(crate) fn update_index<'database>(
     &mut self,
-    index: &'index Index,
-    mut wtx: WriteTransaction<'index>,
+    index: &Index,
+    database: &'database Database,
+    mut wtx: WriteTransaction<'database>,
   ) -> Result {
     let starting_height = index.client.get_block_count()? + 1;
 
@@ -84,7 +101,7 @@ impl Updater {
 
       if i % 5000 == 0 {
         self.commit(wtx)?;
-        wtx = index.begin_write()?;
+        wtx = index.begin_write(database)?;
         uncomitted = 0;
       }
 
@@ -177,6 +194,7 @@ impl Updater {
     let mut height_to_block_hash = wtx.open_table(HEIGHT_TO_BLOCK_HASH)?;
     let mut ordinal_to_satpoint = wtx.open_table(ORDINAL_TO_SATPOINT)?;
     let mut outpoint_to_ordinal_ranges = wtx.open_table(OUTPOINT_TO_ORDINAL_RANGES)?;
+    let mut ordinal_range_start_to_outpoint = wtx.open_table(ORDINAL_RANGE_START_TO_OUTPOINT)?;
 
     let start = Instant::now();
     let mut ordinal_ranges_written = 0;
@@ -201,12 +219,23 @@ impl Updater {
     }
 
     let mut coinbase_inputs = VecDeque::new();
+    let mut lost_ordinal_ranges = Vec::new();
 
     let h = Height(self.height);
+
+    let mut stats = BlockStats {
+      subsidy: h.subsidy(),
+      ..Default::default()
+    };
+
     if h.subsidy() > 0 {
       let start = h.starting_ordinal();
       coinbase_inputs.push_front((start.n(), (start + h.subsidy()).n()));
       self.ordinal_ranges_since_flush += 1;
+
+      if !start.is_common() {
+        stats.rare_ordinals_mined += 1;
+      }
     }
 
     for (tx_offset, tx) in block.txdata.iter().enumerate().skip(1) {
@@ -224,11 +253,22 @@ impl Updater {
             self.outputs_cached += 1;
             ordinal_ranges
           }
-          None => outpoint_to_ordinal_ranges
-            .remove(&key)?
-            .ok_or_else(|| anyhow!("Could not find outpoint {} in index", input.previous_output))?
-            .to_value()
-            .to_vec(),
+          None => {
+            let ordinal_ranges = outpoint_to_ordinal_ranges
+              .remove(&key)?
+              .ok_or_else(|| anyhow!("Could not find outpoint {} in index", input.previous_output))?
+              .to_value()
+              .to_vec();
+
+            // Cached outputs haven't been flushed, so only flushed ones have
+            // range starts to forget
+            for chunk in ordinal_ranges.chunks_exact(11) {
+              let (start, _end) = Index::decode_ordinal_range(chunk.try_into().unwrap());
+              ordinal_range_start_to_outpoint.remove(&start)?;
+            }
+
+            ordinal_ranges
+          }
         };
 
         for chunk in ordinal_ranges.chunks_exact(11) {
@@ -236,15 +276,29 @@ impl Updater {
         }
       }
 
+      stats.rare_ordinals_moved += input_ordinal_ranges
+        .iter()
+        .filter(|(start, _end)| !Ordinal(*start).is_common())
+        .count() as u64;
+
       self.index_transaction(
         txid,
         tx,
         &mut ordinal_to_satpoint,
         &mut input_ordinal_ranges,
+        &mut lost_ordinal_ranges,
         &mut ordinal_ranges_written,
         &mut outputs_in_block,
       )?;
 
+      for (start, end) in &input_ordinal_ranges {
+        stats.fees += end - start;
+      }
+
+      if index.index_fee_ranges {
+        stats.fee_ranges.extend(&input_ordinal_ranges);
+      }
+
       coinbase_inputs.extend(input_ordinal_ranges);
     }
 
@@ -254,11 +308,95 @@ impl Updater {
         tx,
         &mut ordinal_to_satpoint,
         &mut coinbase_inputs,
+        &mut lost_ordinal_ranges,
         &mut ordinal_ranges_written,
         &mut outputs_in_block,
       )?;
     }
 
+    lost_ordinal_ranges.extend(coinbase_inputs);
+
+    if !lost_ordinal_ranges.is_empty() {
+      let outpoint = Index::lost_outpoint(self.height);
+      let mut ordinals = Vec::new();
+
+      for (start, end) in lost_ordinal_ranges {
+        if !Ordinal(start).is_common() {
+          self.move_rare_ordinal(
+            &mut ordinal_to_satpoint,
+            start,
+            SatPoint {
+              outpoint,
+              offset: stats.lost,
+            },
+          )?;
+        }
+
+        ordinals.extend_from_slice(&Index::encode_ordinal_range((start, end)));
+
+        stats.lost += end - start;
+      }
+
+      self.cache.insert(encode_outpoint(outpoint), ordinals);
+      self.outputs_inserted_since_flush += 1;
+      self.lost_ordinals_since_flush += stats.lost;
+    }
+
+    stats.ordinal_ranges_created = ordinal_ranges_written;
+
+    wtx
+      .open_table(HEIGHT_TO_BLOCK_STATS)?
+      .insert(&self.height, &stats.encode())?;
+
+    if !self.rare_ordinal_moves.is_empty() {
+      wtx.open_table(HEIGHT_TO_RARE_ORDINAL_MOVES)?.insert(
+        &self.height,
+        &Index::encode_rare_ordinal_moves(&self.rare_ordinal_moves),
+      )?;
+      self.rare_ordinal_moves.clear();
+    }
+
+    if !self.watched.is_empty() {
+      let mut watched_outpoint_to_script_pubkey =
+        wtx.open_table(WATCHED_OUTPOINT_TO_SCRIPT_PUBKEY)?;
+
+      let mut watched_txids = Vec::new();
+
+      for tx in &block.txdata {
+        let mut watched_transaction = false;
+
+        if !tx.is_coin_base() {
+          for input in &tx.input {
+            watched_transaction |= watched_outpoint_to_script_pubkey
+              .remove(&encode_outpoint(input.previous_output))?
+              .is_some();
+          }
+        }
+
+        let txid = tx.txid();
+
+        for (vout, output) in tx.output.iter().enumerate() {
+          if self.watched.contains(&output.script_pubkey) {
+            watched_outpoint_to_script_pubkey.insert(
+              &encode_outpoint(OutPoint::new(txid, vout as u32)),
+              output.script_pubkey.as_bytes(),
+            )?;
+            watched_transaction = true;
+          }
+        }
+
+        if watched_transaction {
+          watched_txids.extend_from_slice(txid.as_inner());
+        }
+      }
+
+      if !watched_txids.is_empty() {
+        wtx
+          .open_table(HEIGHT_TO_WATCHED_TXIDS)?
+          .insert(&self.height, &watched_txids)?;
+      }
+    }
+
     height_to_block_hash.insert(&self.height, &block.block_hash().as_hash().into_inner())?;
 
     self.height += 1;
@@ -272,63 +410,74 @@ impl Updater {
     Ok(())
   }
 
+  /// Record that rare `ordinal` is now at `satpoint`, both as its current
+  /// location and as a move in the block being indexed.
+  fn move_rare_ordinal(
+    &mut self,
+    ordinal_to_satpoint: &mut Table<u64, [u8; 44]>,
+    ordinal: u64,
+    satpoint: SatPoint,
+  ) -> Result {
+    ordinal_to_satpoint.insert(&ordinal, &encode_satpoint(satpoint))?;
+    self.rare_ordinal_moves.push((ordinal, satpoint));
+    Ok(())
+  }
+
   pub(crate) fn index_transaction(
     &mut self,
     txid: Txid,
     tx: &Transaction,
     ordinal_to_satpoint: &mut Table<u64, [u8; 44]>,
     input_ordinal_ranges: &mut VecDeque<(u64, u64)>,
+    lost_ordinal_ranges: &mut Vec<(u64, u64)>,
     ordinal_ranges_written: &mut u64,
     outputs_traversed: &mut u64,
   ) -> Result {
-    for (vout, output) in tx.output.iter().enumerate() {
+    let input_range_count = input_ordinal_ranges.len();
+
+    let (output_ordinal_ranges, remaining_ordinal_ranges) = assign_ordinal_ranges(
+      mem::take(input_ordinal_ranges),
+      tx.output.iter().map(|output| output.value),
+    );
+
+    // Every range split in two adds one to the total
+    self.ordinal_ranges_since_flush += (output_ordinal_ranges.iter().map(Vec::len).sum::<usize>()
+      + remaining_ordinal_ranges.len()
+      - input_range_count) as u64;
+
+    *input_ordinal_ranges = remaining_ordinal_ranges.into();
+
+    for (vout, (output, ranges)) in tx.output.iter().zip(output_ordinal_ranges).enumerate() {
+      if ranges.iter().map(|(start, end)| end - start).sum::<u64>() < output.value {
+        bail!("insufficient inputs for transaction outputs");
+      }
+
       let outpoint = OutPoint {
         vout: vout as u32,
         txid,
       };
-      let mut ordinals = Vec::new();
-
-      let mut remaining = output.value;
-      while remaining > 0 {
-        let range = input_ordinal_ranges
-          .pop_front()
-          .ok_or_else(|| anyhow!("insufficient inputs for transaction outputs"))?;
-
-        if !Ordinal(range.0).is_common() {
-          ordinal_to_satpoint.insert(
-            &range.0,
-            &encode_satpoint(SatPoint {
-              outpoint,
-              offset: output.value - remaining,
-            }),
-          )?;
-        }
 
-        let count = range.1 - range.0;
-
-        let assigned = if count > remaining {
-          self.ordinal_ranges_since_flush += 1;
-          let middle = range.0 + remaining;
-          input_ordinal_ranges.push_front((middle, range.1));
-          (range.0, middle)
-        } else {
-          range
-        };
+      *ordinal_ranges_written += ranges.len() as u64;
+      *outputs_traversed += 1;
 
-        let base = assigned.0;
-        let delta = assigned.1 - assigned.0;
+      if output.script_pubkey.is_op_return() {
+        lost_ordinal_ranges.extend(ranges);
+        continue;
+      }
 
-        let n = base as u128 | (delta as u128) << 51;
+      let mut ordinals = Vec::with_capacity(ranges.len() * 11);
+      let mut offset = 0;
 
-        ordinals.extend_from_slice(&n.to_le_bytes()[0..11]);
+      for (start, end) in ranges {
+        if !Ordinal(start).is_common() {
+          self.move_rare_ordinal(ordinal_to_satpoint, start, SatPoint { outpoint, offset })?;
+        }
 
-        remaining -= assigned.1 - assigned.0;
+        ordinals.extend_from_slice(&Index::encode_ordinal_range((start, end)));
 
-        *ordinal_ranges_written += 1;
+        offset += end - start;
       }
 
-      *outputs_traversed += 1;
-
       self.cache.insert(encode_outpoint(outpoint), ordinals);
       self.outputs_inserted_since_flush += 1;
     }
@@ -354,9 +503,15 @@ impl Updater {
       );
 
       let mut outpoint_to_ordinal_ranges = wtx.open_table(OUTPOINT_TO_ORDINAL_RANGES)?;
+      let mut ordinal_range_start_to_outpoint = wtx.open_table(ORDINAL_RANGE_START_TO_OUTPOINT)?;
 
       for (k, v) in &self.cache {
         outpoint_to_ordinal_ranges.insert(k, v)?;
+
+        for chunk in v.chunks_exact(11) {
+          let (start, _end) = Index::decode_ordinal_range(chunk.try_into().unwrap());
+          ordinal_range_start_to_outpoint.insert(&start, k)?;
+        }
       }
 
       self.cache.clear();
@@ -371,6 +526,12 @@ impl Updater {
       self.ordinal_ranges_since_flush,
     )?;
     self.ordinal_ranges_since_flush = 0;
+    Index::increment_statistic(
+      &wtx,
+      Statistic::LostOrdinals,
+      self.lost_ordinals_since_flush,
+    )?;
+    self.lost_ordinals_since_flush = 0;
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
     wtx.commit()?;
     Ok(())
@@ -5,9 +5,11 @@ use {
     arguments::Arguments,
     blocktime::Blocktime,
     bytes::Bytes,
+    config::Config,
     decimal::Decimal,
     degree::Degree,
     epoch::Epoch,
+    format::{fields_text, fields_tsv, Format, Output},
     height::Height,
     index::{Index, List},
     options::Options,
@@ -16,6 +18,8 @@ use {
     sat_point::SatPoint,
     subcommand::Subcommand,
     tally::Tally,
+    transaction_flow::TransactionFlow,
+    wallet_policy::WalletPolicy,
   },
   anyhow::{anyhow, bail, Context, Error},
   bitcoin::{
@@ -65,9 +69,11 @@ mod arguments;
 mod blocktime;
 mod bytes;
 mod chain;
+mod config;
 mod decimal;
 mod degree;
 mod epoch;
+mod format;
 mod height;
 mod index;
 mod options;
@@ -76,6 +82,8 @@ mod rarity;
 mod sat_point;
 mod subcommand;
 mod tally;
+mod transaction_flow;
+mod wallet_policy;
 
 type Result<T = (), E = Error> = std::result::Result<T, E>;
 
@@ -7,44 +7,212 @@ use {
 pub(crate) struct Options {
   #[clap(
     long,
+    env = "ORD_CONFIG",
+    help = "Load configuration from <CONFIG>, parsed as TOML if it ends in `.toml` and as YAML otherwise. [default: <DATA_DIR>/ord.yaml or <DATA_DIR>/ord.toml]"
+  )]
+  config: Option<PathBuf>,
+  #[clap(skip)]
+  loaded_config: Config,
+  #[clap(
+    long,
+    env = "ORD_MAX_INDEX_SIZE",
     help = "Limit the ordinal index to <MAX_INDEX_SIZE> bytes. This cannot be changed later. [mainnet, testnet, and signet default: 1 TiB, regtest default: 10 MiB]"
   )]
   max_index_size: Option<Bytes>,
-  #[clap(long, help = "Load Bitcoin Core RPC cookie file from <COOKIE_FILE>.")]
+  #[clap(
+    long,
+    env = "ORD_COOKIE_FILE",
+    help = "Load Bitcoin Core RPC cookie file from <COOKIE_FILE>."
+  )]
   cookie_file: Option<PathBuf>,
-  #[clap(long, help = "Connect to Bitcoin Core RPC at <RPC_URL>.")]
+  #[clap(
+    long,
+    env = "ORD_RPC_URL",
+    help = "Connect to Bitcoin Core RPC at <RPC_URL>."
+  )]
   rpc_url: Option<String>,
-  #[clap(long, arg_enum, default_value = "mainnet", help = "Index <CHAIN>.")]
-  pub(crate) chain: Chain,
-  #[clap(long, help = "Store index in <DATA_DIR>.")]
+  #[clap(
+    long,
+    env = "ORD_RPC_USER",
+    help = "Authenticate to Bitcoin Core RPC as <RPC_USER> instead of using the cookie file."
+  )]
+  rpc_user: Option<String>,
+  #[clap(
+    long,
+    env = "ORD_RPC_PASS",
+    hide_env_values = true,
+    help = "Authenticate to Bitcoin Core RPC with <RPC_PASS>."
+  )]
+  rpc_pass: Option<String>,
+  #[clap(
+    long = "chain",
+    arg_enum,
+    env = "ORD_CHAIN",
+    help = "Index <CHAIN>. [default: mainnet]"
+  )]
+  chain_argument: Option<Chain>,
+  #[clap(
+    long,
+    arg_enum,
+    env = "ORD_FORMAT",
+    help = "Print output as <FORMAT>. [default: text]"
+  )]
+  format: Option<Format>,
+  #[clap(long, env = "ORD_DATA_DIR", help = "Store index in <DATA_DIR>.")]
   data_dir: Option<PathBuf>,
-  #[clap(long, help = "Load Bitcoin Core data dir from <BITCOIN_DATA_DIR>.")]
+  #[clap(
+    long,
+    env = "ORD_BITCOIN_DATA_DIR",
+    help = "Load Bitcoin Core data dir from <BITCOIN_DATA_DIR>."
+  )]
   bitcoin_data_dir: Option<PathBuf>,
-  #[clap(long, help = "Limit index to <HEIGHT_LIMIT> blocks.")]
-  pub(crate) height_limit: Option<u64>,
+  #[clap(
+    long,
+    env = "ORD_HEIGHT_LIMIT",
+    help = "Limit index to <HEIGHT_LIMIT> blocks."
+  )]
+  height_limit: Option<u64>,
+  #[clap(
+    long,
+    env = "ORD_INDEX_FEE_RANGES",
+    help = "Record the ordinal ranges collected as fees by each block, so block pages can show them and where they went in the coinbase."
+  )]
+  index_fee_ranges: bool,
+  #[clap(
+    long,
+    env = "ORD_MAX_WALLET_BALANCE",
+    help = "Refuse to use wallets containing more than <MAX_WALLET_BALANCE> sats. [mainnet default: 1000000]"
+  )]
+  max_wallet_balance: Option<u64>,
+  #[clap(
+    long,
+    env = "ORD_MAX_TRANSACTION_VALUE",
+    help = "Refuse to sign transactions spending more than <MAX_TRANSACTION_VALUE> sats."
+  )]
+  max_transaction_value: Option<u64>,
+  #[clap(
+    long,
+    env = "ORD_ALLOWED_WALLET_NAME",
+    value_delimiter = ',',
+    help = "Only use Bitcoin Core wallets named <ALLOWED_WALLET_NAME>, where a trailing `*` matches any suffix. May be given more than once. [mainnet default: `ord` and `ord-*`]"
+  )]
+  allowed_wallet_name: Vec<String>,
+  #[clap(
+    long,
+    env = "ORD_I_UNDERSTAND_THE_RISKS",
+    help = "Disable all wallet safety limits."
+  )]
+  i_understand_the_risks: bool,
 }
 
 impl Options {
+  /// Load the config file given with `--config`, or `ord.yaml` or `ord.toml`
+  /// in the data dir if one exists. Since the data dir depends on the chain, the chain
+  /// and data dir must be given on the command line or in the environment
+  /// for a config file in a non-default data dir to be found.
+  pub(crate) fn load_config(mut self) -> Result<Self> {
+    let path = match &self.config {
+      Some(path) => path.clone(),
+      None => {
+        let data_dir = self.data_dir()?;
+        match ["ord.yaml", "ord.toml"]
+          .iter()
+          .map(|name| data_dir.join(name))
+          .find(|path| path.exists())
+        {
+          Some(path) => path,
+          None => return Ok(self),
+        }
+      }
+    };
+
+    self.loaded_config = Config::load(&path)?;
+
+    Ok(self)
+  }
+
+  pub(crate) fn config(&self) -> &Config {
+    &self.loaded_config
+  }
+
+  pub(crate) fn chain(&self) -> Chain {
+    self
+      .chain_argument
+      .or(self.loaded_config.chain)
+      .unwrap_or(Chain::Mainnet)
+  }
+
+  pub(crate) fn format(&self) -> Format {
+    self
+      .format
+      .or(self.loaded_config.format)
+      .unwrap_or(Format::Text)
+  }
+
+  pub(crate) fn height_limit(&self) -> Option<u64> {
+    self.height_limit.or(self.loaded_config.height_limit)
+  }
+
+  pub(crate) fn index_fee_ranges(&self) -> bool {
+    self.index_fee_ranges || self.loaded_config.index_fee_ranges
+  }
+
   pub(crate) fn max_index_size(&self) -> Bytes {
     self
       .max_index_size
-      .unwrap_or_else(|| self.chain.default_max_index_size())
+      .or(self.loaded_config.max_index_size)
+      .unwrap_or_else(|| self.chain().default_max_index_size())
+  }
+
+  pub(crate) fn wallet_policy(&self) -> WalletPolicy {
+    let chain = self.chain();
+
+    if self.i_understand_the_risks {
+      return WalletPolicy::permissive(chain);
+    }
+
+    let config = self.loaded_config.wallet_policy.for_chain(chain);
+
+    WalletPolicy {
+      allowed_wallet_names: if !self.allowed_wallet_name.is_empty() {
+        self.allowed_wallet_name.clone()
+      } else if !config.allowed_wallet_name.is_empty() {
+        config.allowed_wallet_name.clone()
+      } else {
+        chain.default_allowed_wallet_names()
+      },
+      chain,
+      max_balance: self
+        .max_wallet_balance
+        .or(config.max_wallet_balance)
+        .or_else(|| chain.default_max_wallet_balance()),
+      max_transaction_value: self.max_transaction_value.or(config.max_transaction_value),
+    }
   }
 
   pub(crate) fn rpc_url(&self) -> String {
     self
       .rpc_url
       .as_ref()
-      .unwrap_or(&format!("127.0.0.1:{}", self.chain.default_rpc_port(),))
+      .or(self.loaded_config.rpc_url.as_ref())
+      .unwrap_or(&format!("127.0.0.1:{}", self.chain().default_rpc_port(),))
       .into()
   }
 
   pub(crate) fn cookie_file(&self) -> Result<PathBuf> {
-    if let Some(cookie_file) = &self.cookie_file {
+    if let Some(cookie_file) = self
+      .cookie_file
+      .as_ref()
+      .or(self.loaded_config.cookie_file.as_ref())
+    {
       return Ok(cookie_file.clone());
     }
 
-    let path = if let Some(bitcoin_data_dir) = &self.bitcoin_data_dir {
+    let path = if let Some(bitcoin_data_dir) = self
+      .bitcoin_data_dir
+      .as_ref()
+      .or(self.loaded_config.bitcoin_data_dir.as_ref())
+    {
       bitcoin_data_dir.clone()
     } else if cfg!(target_os = "linux") {
       dirs::home_dir()
@@ -56,31 +224,63 @@ impl Options {
         .join("Bitcoin")
     };
 
-    let path = self.chain.join_with_data_dir(&path);
+    let path = self.chain().join_with_data_dir(&path);
 
     Ok(path.join(".cookie"))
   }
 
   pub(crate) fn data_dir(&self) -> Result<PathBuf> {
-    let base = match &self.data_dir {
+    let base = match self
+      .data_dir
+      .as_ref()
+      .or(self.loaded_config.data_dir.as_ref())
+    {
       Some(base) => base.clone(),
       None => dirs::data_dir()
         .ok_or_else(|| anyhow!("failed to retrieve data dir"))?
         .join("ord"),
     };
 
-    Ok(self.chain.join_with_data_dir(&base))
+    Ok(self.chain().join_with_data_dir(&base))
+  }
+
+  /// Credentials for Bitcoin Core RPC, which are the username and password
+  /// if given and otherwise the cookie file.
+  pub(crate) fn bitcoin_rpc_auth(&self) -> Result<Auth> {
+    let rpc_user = self
+      .rpc_user
+      .as_ref()
+      .or(self.loaded_config.rpc_user.as_ref());
+
+    let rpc_pass = self
+      .rpc_pass
+      .as_ref()
+      .or(self.loaded_config.rpc_pass.as_ref());
+
+    match (rpc_user, rpc_pass) {
+      (Some(rpc_user), Some(rpc_pass)) => {
+        log::info!("Using Bitcoin Core RPC credentials for user `{rpc_user}`");
+        Ok(Auth::UserPass(rpc_user.clone(), rpc_pass.clone()))
+      }
+      (None, None) => {
+        let cookie_file = self.cookie_file()?;
+        log::info!(
+          "Using Bitcoin Core RPC credentials from `{}`",
+          cookie_file.display()
+        );
+        Ok(Auth::CookieFile(cookie_file))
+      }
+      (Some(_), None) => bail!("RPC user given without RPC password"),
+      (None, Some(_)) => bail!("RPC password given without RPC user"),
+    }
   }
 
   pub(crate) fn bitcoin_rpc_client(&self) -> Result<Client> {
-    let cookie_file = self.cookie_file()?;
     let rpc_url = self.rpc_url();
-    log::info!(
-      "Connecting to Bitcoin Core RPC server at {rpc_url} using credentials from `{}`",
-      cookie_file.display()
-    );
 
-    let client = Client::new(&rpc_url, Auth::CookieFile(cookie_file))
+    log::info!("Connecting to Bitcoin Core RPC server at {rpc_url}");
+
+    let client = Client::new(&rpc_url, self.bitcoin_rpc_auth()?)
       .with_context(|| format!("failed to connect to Bitcoin Core RPC at {rpc_url}"))?;
 
     let rpc_chain = match client.get_blockchain_info()?.chain.as_str() {
@@ -91,7 +291,7 @@ impl Options {
       other => bail!("Bitcoin RPC server on unknown chain: {other}"),
     };
 
-    let ord_chain = self.chain;
+    let ord_chain = self.chain();
 
     if rpc_chain != ord_chain {
       bail!("Bitcoin RPC server is on {rpc_chain} but ord is on {ord_chain}");
@@ -103,21 +303,20 @@ impl Options {
   pub(crate) fn bitcoin_rpc_client_for_wallet_command(&self, command: &str) -> Result<Client> {
     let client = self.bitcoin_rpc_client()?;
 
-    if self.chain == Chain::Mainnet {
-      let wallet_info = client.get_wallet_info()?;
+    let policy = self.wallet_policy();
 
-      if !(wallet_info.wallet_name == "ord" || wallet_info.wallet_name.starts_with("ord-")) {
-        bail!("`{command}` may only be used on mainnet with a wallet named `ord` or whose name starts with `ord-`");
-      }
+    if !policy.allowed_wallet_names.is_empty() {
+      policy.check_wallet_name(command, &client.get_wallet_info()?.wallet_name)?;
+    }
 
+    if policy.max_balance.is_some() {
       let balances = client.get_balances()?;
 
       let total = balances.mine.trusted + balances.mine.untrusted_pending + balances.mine.immature;
 
-      if total > Amount::from_sat(1_000_000) {
-        bail!("`ord wallet send` may not be used on mainnet with wallets containing more than 1,000,000 sats");
-      }
+      policy.check_balance(command, total.to_sat())?;
     }
+
     Ok(client)
   }
 }
@@ -203,6 +402,59 @@ mod tests {
     );
   }
 
+  #[test]
+  fn bitcoin_rpc_auth_defaults_to_cookie_file() {
+    assert_eq!(
+      Arguments::try_parse_from(&["ord", "--cookie-file=/foo/bar", "index"])
+        .unwrap()
+        .options
+        .bitcoin_rpc_auth()
+        .unwrap(),
+      Auth::CookieFile("/foo/bar".into())
+    );
+  }
+
+  #[test]
+  fn bitcoin_rpc_auth_uses_user_and_pass() {
+    assert_eq!(
+      Arguments::try_parse_from(&[
+        "ord",
+        "--cookie-file=/foo/bar",
+        "--rpc-user=foo",
+        "--rpc-pass=bar",
+        "index"
+      ])
+      .unwrap()
+      .options
+      .bitcoin_rpc_auth()
+      .unwrap(),
+      Auth::UserPass("foo".into(), "bar".into())
+    );
+  }
+
+  #[test]
+  fn bitcoin_rpc_auth_requires_user_and_pass_together() {
+    assert_eq!(
+      Arguments::try_parse_from(&["ord", "--rpc-user=foo", "index"])
+        .unwrap()
+        .options
+        .bitcoin_rpc_auth()
+        .unwrap_err()
+        .to_string(),
+      "RPC user given without RPC password"
+    );
+
+    assert_eq!(
+      Arguments::try_parse_from(&["ord", "--rpc-pass=bar", "index"])
+        .unwrap()
+        .options
+        .bitcoin_rpc_auth()
+        .unwrap_err()
+        .to_string(),
+      "RPC password given without RPC user"
+    );
+  }
+
   #[test]
   fn use_default_network() {
     let arguments = Arguments::try_parse_from(&["ord", "index"]).unwrap();
@@ -341,6 +593,163 @@ mod tests {
     check_network_alias("testnet", "ord/testnet3");
   }
 
+  #[test]
+  fn wallet_policy_defaults() {
+    assert_eq!(
+      Arguments::try_parse_from(&["ord", "index"])
+        .unwrap()
+        .options
+        .wallet_policy(),
+      WalletPolicy {
+        allowed_wallet_names: vec!["ord".into(), "ord-*".into()],
+        chain: Chain::Mainnet,
+        max_balance: Some(1_000_000),
+        max_transaction_value: None,
+      }
+    );
+
+    assert_eq!(
+      Arguments::try_parse_from(&["ord", "--chain=signet", "index"])
+        .unwrap()
+        .options
+        .wallet_policy(),
+      WalletPolicy::permissive(Chain::Signet)
+    );
+  }
+
+  #[test]
+  fn wallet_policy_overrides() {
+    assert_eq!(
+      Arguments::try_parse_from(&[
+        "ord",
+        "--max-wallet-balance=10",
+        "--max-transaction-value=5",
+        "--allowed-wallet-name=foo",
+        "--allowed-wallet-name=bar-*",
+        "index"
+      ])
+      .unwrap()
+      .options
+      .wallet_policy(),
+      WalletPolicy {
+        allowed_wallet_names: vec!["foo".into(), "bar-*".into()],
+        chain: Chain::Mainnet,
+        max_balance: Some(10),
+        max_transaction_value: Some(5),
+      }
+    );
+  }
+
+  #[test]
+  fn i_understand_the_risks_disables_wallet_policy() {
+    assert_eq!(
+      Arguments::try_parse_from(&[
+        "ord",
+        "--i-understand-the-risks",
+        "--max-wallet-balance=10",
+        "index"
+      ])
+      .unwrap()
+      .options
+      .wallet_policy(),
+      WalletPolicy::permissive(Chain::Mainnet)
+    );
+  }
+
+  #[test]
+  fn config_file_fills_in_options() {
+    let tempdir = TempDir::new().unwrap();
+    let config = tempdir.path().join("ord.yaml");
+    fs::write(
+      &config,
+      "
+chain: signet
+rpc-url: 127.0.0.1:1000
+wallet-policy:
+  mainnet:
+    max-wallet-balance: 10
+  signet:
+    max-wallet-balance: 5
+",
+    )
+    .unwrap();
+
+    let options =
+      Arguments::try_parse_from(&["ord", "--config", config.to_str().unwrap(), "index"])
+        .unwrap()
+        .options
+        .load_config()
+        .unwrap();
+
+    assert_eq!(options.chain(), Chain::Signet);
+    assert_eq!(options.rpc_url(), "127.0.0.1:1000");
+    assert_eq!(options.wallet_policy().max_balance, Some(5));
+  }
+
+  #[test]
+  fn command_line_overrides_config_file() {
+    let tempdir = TempDir::new().unwrap();
+    let config = tempdir.path().join("ord.yaml");
+    fs::write(&config, "chain: signet\nrpc-url: 127.0.0.1:1000\n").unwrap();
+
+    let options = Arguments::try_parse_from(&[
+      "ord",
+      "--config",
+      config.to_str().unwrap(),
+      "--chain=regtest",
+      "--rpc-url=127.0.0.1:2000",
+      "index",
+    ])
+    .unwrap()
+    .options
+    .load_config()
+    .unwrap();
+
+    assert_eq!(options.chain(), Chain::Regtest);
+    assert_eq!(options.rpc_url(), "127.0.0.1:2000");
+  }
+
+  #[test]
+  fn config_file_in_data_dir_is_optional() {
+    let tempdir = TempDir::new().unwrap();
+
+    let options = Arguments::try_parse_from(&[
+      "ord",
+      "--data-dir",
+      tempdir.path().to_str().unwrap(),
+      "index",
+    ])
+    .unwrap()
+    .options
+    .load_config()
+    .unwrap();
+
+    assert_eq!(options.config(), &Config::default());
+  }
+
+  #[test]
+  fn config_file_in_data_dir_may_be_toml() {
+    let tempdir = TempDir::new().unwrap();
+    fs::write(
+      tempdir.path().join("ord.toml"),
+      "rpc-url = \"127.0.0.1:1000\"\n",
+    )
+    .unwrap();
+
+    let options = Arguments::try_parse_from(&[
+      "ord",
+      "--data-dir",
+      tempdir.path().to_str().unwrap(),
+      "index",
+    ])
+    .unwrap()
+    .options
+    .load_config()
+    .unwrap();
+
+    assert_eq!(options.rpc_url(), "127.0.0.1:1000");
+  }
+
   #[test]
   fn rpc_server_chain_must_match() {
     let rpc_server = test_bitcoincore_rpc::spawn_with(bitcoin::Network::Testnet, "ord");
@@ -1,6 +1,7 @@
 use super::*;
 
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub(crate) enum Rarity {
   Common,
   Uncommon,
@@ -27,6 +28,22 @@ impl Display for Rarity {
   }
 }
 
+impl FromStr for Rarity {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "common" => Ok(Self::Common),
+      "uncommon" => Ok(Self::Uncommon),
+      "rare" => Ok(Self::Rare),
+      "epic" => Ok(Self::Epic),
+      "legendary" => Ok(Self::Legendary),
+      "mythic" => Ok(Self::Mythic),
+      _ => Err(anyhow!("invalid rarity: {s}")),
+    }
+  }
+}
+
 impl From<Ordinal> for Rarity {
   fn from(ordinal: Ordinal) -> Self {
     let Degree {
@@ -56,6 +73,22 @@ impl From<Ordinal> for Rarity {
 mod tests {
   use super::*;
 
+  #[test]
+  fn from_str() {
+    for rarity in [
+      Rarity::Common,
+      Rarity::Uncommon,
+      Rarity::Rare,
+      Rarity::Epic,
+      Rarity::Legendary,
+      Rarity::Mythic,
+    ] {
+      assert_eq!(rarity.to_string().parse::<Rarity>().unwrap(), rarity);
+    }
+
+    assert!("foo".parse::<Rarity>().is_err());
+  }
+
   #[test]
   fn rarity() {
     assert_eq!(Ordinal(0).rarity(), Rarity::Mythic);
@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 pub(crate) struct SatPoint {
   pub(crate) outpoint: OutPoint,
   pub(crate) offset: u64,
@@ -12,6 +12,15 @@ impl Display for SatPoint {
   }
 }
 
+impl Serialize for SatPoint {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    serializer.collect_str(self)
+  }
+}
+
 impl Encodable for SatPoint {
   fn consensus_encode<S: io::Write + ?Sized>(&self, s: &mut S) -> Result<usize, io::Error> {
     let len = self.outpoint.consensus_encode(s)?;
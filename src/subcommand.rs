@@ -11,12 +11,13 @@ mod server;
 mod supply;
 mod traits;
 mod wallet;
+mod watch;
 
 #[derive(Debug, Parser)]
 pub(crate) enum Subcommand {
   Epochs,
   Find(find::Find),
-  Index,
+  Index(index::Index),
   Info,
   List(list::List),
   Parse(parse::Parse),
@@ -26,27 +27,30 @@ pub(crate) enum Subcommand {
   Traits(traits::Traits),
   #[clap(subcommand)]
   Wallet(wallet::Wallet),
+  #[clap(subcommand)]
+  Watch(watch::Watch),
 }
 
 impl Subcommand {
   pub(crate) fn run(self, options: Options) -> Result {
     match self {
-      Self::Epochs => epochs::run(),
+      Self::Epochs => epochs::run(options),
       Self::Find(find) => find.run(options),
-      Self::Index => index::run(options),
+      Self::Index(index) => index.run(options),
       Self::Info => info::run(options),
       Self::List(list) => list.run(options),
       Self::Parse(parse) => parse.run(),
-      Self::Range(range) => range.run(),
+      Self::Range(range) => range.run(options),
       Self::Server(server) => {
-        let index = Arc::new(Index::open(&options)?);
+        let index = Arc::new(server.open_index(&options)?);
         let handle = axum_server::Handle::new();
         LISTENERS.lock().unwrap().push(handle.clone());
         server.run(options, index, handle)
       }
-      Self::Supply => supply::run(),
-      Self::Traits(traits) => traits.run(),
+      Self::Supply => supply::run(options),
+      Self::Traits(traits) => traits.run(options),
       Self::Wallet(wallet) => wallet.run(options),
+      Self::Watch(watch) => watch.run(options),
     }
   }
 }
@@ -1,8 +1,5 @@
 use super::*;
 
-pub(crate) fn run() -> Result {
-  for ordinal in Epoch::STARTING_ORDINALS {
-    println!("{}", ordinal);
-  }
-  Ok(())
+pub(crate) fn run(options: Options) -> Result {
+  options.format().print(&Epoch::STARTING_ORDINALS.to_vec())
 }
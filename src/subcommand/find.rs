@@ -6,6 +6,17 @@ pub(crate) struct Find {
   ordinal: Ordinal,
 }
 
+#[derive(Serialize)]
+struct Found {
+  satpoint: SatPoint,
+}
+
+impl Output for Found {
+  fn tsv(&self) -> String {
+    format!("{}\n", self.satpoint)
+  }
+}
+
 impl Find {
   pub(crate) fn run(self, options: Options) -> Result {
     let index = Index::open(&options)?;
@@ -13,10 +24,10 @@ impl Find {
     index.update()?;
 
     match index.find(self.ordinal.0)? {
-      Some(satpoint) => {
-        println!("{satpoint}");
-        Ok(())
-      }
+      Some(satpoint) => match Index::lost_in_block(satpoint.outpoint) {
+        Some(height) => Err(anyhow!("ordinal was lost in block {height}")),
+        None => options.format().print(&Found { satpoint }),
+      },
       None => Err(anyhow!("ordinal has not been mined as of index height")),
     }
   }
@@ -1,9 +1,28 @@
 use super::*;
 
-pub(crate) fn run(options: Options) -> Result {
-  let index = Index::open(&options)?;
+#[derive(Debug, Parser)]
+pub(crate) struct Index {
+  #[clap(
+    long,
+    help = "Keep updating the index as new blocks arrive, until interrupted."
+  )]
+  watch: bool,
+}
+
+impl Index {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = crate::Index::open(&options)?;
+
+    index.update()?;
+
+    while self.watch && INTERRUPTS.load(atomic::Ordering::Relaxed) == 0 {
+      thread::sleep(Duration::from_millis(100));
 
-  index.update()?;
+      if let Err(error) = index.update() {
+        log::error!("{error}");
+      }
+    }
 
-  Ok(())
+    Ok(())
+  }
 }
@@ -1,8 +1,41 @@
-use super::*;
+use {super::*, crate::index::Info};
+
+impl Output for Info {
+  fn tsv(&self) -> String {
+    fields_tsv(&[
+      ("blocks_indexed", self.blocks_indexed.to_string()),
+      ("utxos_indexed", self.utxos_indexed.to_string()),
+      ("outputs_traversed", self.outputs_traversed.to_string()),
+      ("ordinal_ranges", self.ordinal_ranges.to_string()),
+      ("lost_ordinals", self.lost_ordinals.to_string()),
+      ("tree_height", self.tree_height.to_string()),
+      ("free_pages", self.free_pages.to_string()),
+      ("stored_bytes", self.stored_bytes.to_string()),
+      ("overhead_bytes", self.overhead_bytes.to_string()),
+      ("fragmented_bytes", self.fragmented_bytes.to_string()),
+      ("index_size_bytes", self.index_size_bytes.to_string()),
+    ])
+  }
+
+  fn text(&self) -> String {
+    fields_tsv(&[
+      ("blocks indexed", self.blocks_indexed.to_string()),
+      ("utxos indexed", self.utxos_indexed.to_string()),
+      ("outputs traversed", self.outputs_traversed.to_string()),
+      ("ordinal ranges", self.ordinal_ranges.to_string()),
+      ("lost ordinals", self.lost_ordinals.to_string()),
+      ("tree height", self.tree_height.to_string()),
+      ("free pages", self.free_pages.to_string()),
+      ("stored", Bytes(self.stored_bytes).to_string()),
+      ("overhead", Bytes(self.overhead_bytes).to_string()),
+      ("fragmented", Bytes(self.fragmented_bytes).to_string()),
+      ("index size", Bytes(self.index_size_bytes).to_string()),
+    ])
+  }
+}
 
 pub(crate) fn run(options: Options) -> Result {
   let index = Index::open(&options)?;
   index.update()?;
-  index.print_info()?;
-  Ok(())
+  options.format().print(&index.info()?)
 }
@@ -6,6 +6,22 @@ pub(crate) struct List {
   outpoint: OutPoint,
 }
 
+#[derive(Serialize)]
+struct OrdinalRange {
+  start: u64,
+  end: u64,
+}
+
+impl Output for OrdinalRange {
+  fn tsv(&self) -> String {
+    format!("{}\t{}\n", self.start, self.end)
+  }
+
+  fn text(&self) -> String {
+    format!("[{},{})\n", self.start, self.end)
+  }
+}
+
 impl List {
   pub(crate) fn run(self, options: Options) -> Result {
     let index = Index::open(&options)?;
@@ -13,12 +29,12 @@ impl List {
     index.update()?;
 
     match index.list(self.outpoint)? {
-      Some(crate::index::List::Unspent(ranges)) => {
-        for (start, end) in ranges {
-          println!("[{start},{end})");
-        }
-        Ok(())
-      }
+      Some(crate::index::List::Unspent(ranges)) => options.format().print(
+        &ranges
+          .into_iter()
+          .map(|(start, end)| OrdinalRange { start, end })
+          .collect::<Vec<OrdinalRange>>(),
+      ),
       Some(crate::index::List::Spent) => Err(anyhow!("output spent.")),
       None => Err(anyhow!("output not found")),
     }
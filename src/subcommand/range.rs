@@ -8,8 +8,24 @@ pub(crate) struct Range {
   height: Height,
 }
 
+#[derive(Serialize)]
+struct SubsidyRange<T> {
+  start: T,
+  end: T,
+}
+
+impl<T: Display + Serialize> Output for SubsidyRange<T> {
+  fn tsv(&self) -> String {
+    format!("{}\t{}\n", self.start, self.end)
+  }
+
+  fn text(&self) -> String {
+    format!("[{},{})\n", self.start, self.end)
+  }
+}
+
 impl Range {
-  pub(crate) fn run(self) -> Result {
+  pub(crate) fn run(self, options: Options) -> Result {
     let mut start = 0;
 
     for n in 0..self.height.n() {
@@ -25,11 +41,12 @@ impl Range {
     let end = start + self.height.subsidy();
 
     if self.name {
-      println!("[{},{})", Ordinal(start).name(), Ordinal(end).name());
+      options.format().print(&SubsidyRange {
+        start: Ordinal(start).name(),
+        end: Ordinal(end).name(),
+      })
     } else {
-      println!("[{},{})", start, end);
+      options.format().print(&SubsidyRange { start, end })
     }
-
-    Ok(())
   }
 }
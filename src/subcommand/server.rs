@@ -2,19 +2,24 @@ use super::*;
 
 use {
   self::{
+    block_query::BlockQuery,
     deserialize_from_str::DeserializeFromStr,
+    rare_query::RareQuery,
     templates::{
-      BlockHtml, ClockSvg, Content, HomeHtml, InputHtml, OrdinalHtml, OutputHtml, PageHtml,
-      RangeHtml, RareTxt, TransactionHtml,
+      BlockHtml, BlocksHtml, ClockSvg, Content, FeedXml, HomeHtml, InputHtml, OrdinalHtml,
+      OutputHtml, PageHtml, RangeHtml, RareHtml, RareTxt, SearchHtml, TransactionHtml,
     },
+    throttle::Throttle,
   },
+  crate::config::ServerConfig,
   axum::{
     body,
     extract::{Extension, Path, Query},
     http::{header, StatusCode},
+    middleware,
     response::{IntoResponse, Redirect, Response},
     routing::get,
-    Router,
+    Json, Router,
   },
   axum_server::Handle,
   lazy_static::lazy_static,
@@ -26,21 +31,41 @@ use {
     AcmeConfig,
   },
   serde::{de, Deserializer},
-  std::{cmp::Ordering, str},
+  std::{
+    cmp::Ordering,
+    net::{IpAddr, SocketAddr},
+    num::NonZeroU32,
+    str,
+  },
   tokio_stream::StreamExt,
 };
 
+mod block_query;
+mod cache;
 mod deserialize_from_str;
+mod openapi;
+mod rare_query;
+mod routes;
 mod templates;
+mod throttle;
 
 enum ServerError {
   Internal(Error),
   NotFound(String),
   BadRequest(String),
+  Timeout,
+  TooManyRequests(Duration),
 }
 
 type ServerResult<T> = Result<T, ServerError>;
 
+/// Where links in RSS feeds point, without a trailing slash. Empty if
+/// neither `--base-url` nor `--acme-domain` were given, making links
+/// relative, since the `Host` header is chosen by the client and responses
+/// may be cached by proxies.
+#[derive(Clone)]
+struct BaseUrl(String);
+
 impl IntoResponse for ServerError {
   fn into_response(self) -> Response {
     match self {
@@ -56,6 +81,18 @@ impl IntoResponse for ServerError {
       }
       Self::NotFound(message) => (StatusCode::NOT_FOUND, message).into_response(),
       Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+      Self::Timeout => (StatusCode::SERVICE_UNAVAILABLE, "request timed out").into_response(),
+      Self::TooManyRequests(retry_after) => (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(
+          header::RETRY_AFTER,
+          (retry_after.as_secs_f64().ceil() as u64).max(1).to_string(),
+        )],
+        StatusCode::TOO_MANY_REQUESTS
+          .canonical_reason()
+          .unwrap_or_default(),
+      )
+        .into_response(),
     }
   }
 }
@@ -65,6 +102,11 @@ struct Search {
   query: String,
 }
 
+#[derive(Deserialize)]
+struct BlocksQuery {
+  before: Option<u64>,
+}
+
 #[derive(RustEmbed)]
 #[folder = "static"]
 struct StaticAssets;
@@ -90,88 +132,248 @@ impl Display for StaticHtml {
 pub(crate) struct Server {
   #[clap(
     long,
-    default_value = "0.0.0.0",
-    help = "Listen on <ADDRESS> for incoming requests."
+    env = "ORD_SERVER_ADDRESS",
+    help = "Listen on <ADDRESS> for incoming requests. [default: 0.0.0.0]"
+  )]
+  address: Option<String>,
+  #[clap(
+    long,
+    env = "ORD_SERVER_BASE_URL",
+    help = "Link to this server at <BASE_URL> in RSS feeds. [default: https://<ACME_DOMAIN> for the first <ACME_DOMAIN>, or relative links]"
   )]
-  address: String,
+  base_url: Option<String>,
   #[clap(
     long,
+    env = "ORD_SERVER_ACME_DOMAIN",
+    value_delimiter = ',',
     help = "Request ACME TLS certificate for <ACME_DOMAIN>. This ord instance must be reachable at <ACME_DOMAIN>:443 to respond to Let's Encrypt ACME challenges."
   )]
   acme_domain: Vec<String>,
   #[clap(
     long,
+    env = "ORD_SERVER_HTTP_PORT",
     help = "Listen on <HTTP_PORT> for incoming HTTP requests. [default: 80]."
   )]
   http_port: Option<u16>,
   #[clap(
     long,
     group = "port",
+    env = "ORD_SERVER_HTTPS_PORT",
     help = "Listen on <HTTPS_PORT> for incoming HTTPS requests. [default: 443]."
   )]
   https_port: Option<u16>,
-  #[clap(long, help = "Store ACME TLS certificates in <ACME_CACHE>.")]
+  #[clap(
+    long,
+    env = "ORD_SERVER_ACME_CACHE",
+    help = "Store ACME TLS certificates in <ACME_CACHE>."
+  )]
   acme_cache: Option<PathBuf>,
-  #[clap(long, help = "Provide ACME contact <ACME_CONTACT>.")]
+  #[clap(
+    long,
+    env = "ORD_SERVER_ACME_CONTACT",
+    value_delimiter = ',',
+    help = "Provide ACME contact <ACME_CONTACT>."
+  )]
   acme_contact: Vec<String>,
-  #[clap(long, help = "Serve HTTP traffic on <HTTP_PORT>.")]
+  #[clap(
+    long,
+    env = "ORD_SERVER_HTTP",
+    overrides_with = "no_http",
+    help = "Serve HTTP traffic on <HTTP_PORT>."
+  )]
   http: bool,
-  #[clap(long, help = "Serve HTTPS traffic on <HTTPS_PORT>.")]
+  #[clap(
+    long,
+    env = "ORD_SERVER_NO_HTTP",
+    overrides_with = "http",
+    help = "Don't serve HTTP traffic, even if the config file enables it."
+  )]
+  no_http: bool,
+  #[clap(
+    long,
+    env = "ORD_SERVER_HTTPS",
+    overrides_with = "no_https",
+    help = "Serve HTTPS traffic on <HTTPS_PORT>."
+  )]
   https: bool,
+  #[clap(
+    long,
+    env = "ORD_SERVER_NO_HTTPS",
+    overrides_with = "https",
+    help = "Don't serve HTTPS traffic, even if the config file enables it."
+  )]
+  no_https: bool,
+  #[clap(
+    long,
+    env = "ORD_SERVER_READ_ONLY",
+    help = "Open the index read-only and never update it, reopening it to pick up blocks indexed by `ord index --watch` once that has caught up."
+  )]
+  read_only: bool,
+  #[clap(
+    long,
+    env = "ORD_SERVER_RATE_LIMIT",
+    help = "Allow each IP address at most <RATE_LIMIT> requests per second."
+  )]
+  rate_limit: Option<NonZeroU32>,
+  #[clap(
+    long,
+    env = "ORD_SERVER_TRUSTED_PROXY",
+    value_delimiter = ',',
+    help = "Rate limit requests relayed by the reverse proxy at <TRUSTED_PROXY> by the client address it adds to `X-Forwarded-For`, rather than by the proxy's own address."
+  )]
+  trusted_proxy: Vec<IpAddr>,
+  #[clap(
+    long,
+    env = "ORD_SERVER_MAX_EXPENSIVE_QUERIES",
+    help = "Run at most <MAX_EXPENSIVE_QUERIES> expensive queries, like finding ordinals or scanning ranges, at once. [default: 4]"
+  )]
+  max_expensive_queries: Option<usize>,
 }
 
 impl Server {
+  /// Fill in settings not given on the command line or in the environment
+  /// from the config file.
+  fn with_config(self, config: &ServerConfig) -> Self {
+    let config = config.clone();
+
+    let http = Self::flag(self.http, self.no_http).or(config.http);
+    let https = Self::flag(self.https, self.no_https).or(config.https);
+
+    Self {
+      address: self.address.or(config.address),
+      base_url: self.base_url.or(config.base_url),
+      acme_domain: if self.acme_domain.is_empty() {
+        config.acme_domain
+      } else {
+        self.acme_domain
+      },
+      http_port: self.http_port.or(config.http_port),
+      https_port: self.https_port.or(config.https_port),
+      acme_cache: self.acme_cache.or(config.acme_cache),
+      acme_contact: if self.acme_contact.is_empty() {
+        config.acme_contact
+      } else {
+        self.acme_contact
+      },
+      http: http == Some(true),
+      no_http: http == Some(false),
+      https: https == Some(true),
+      no_https: https == Some(false),
+      read_only: self.read_only || config.read_only,
+      rate_limit: self.rate_limit.or(config.rate_limit),
+      trusted_proxy: if self.trusted_proxy.is_empty() {
+        config.trusted_proxy
+      } else {
+        self.trusted_proxy
+      },
+      max_expensive_queries: self.max_expensive_queries.or(config.max_expensive_queries),
+    }
+  }
+
+  fn base_url(&self) -> BaseUrl {
+    BaseUrl(
+      self
+        .base_url
+        .clone()
+        .or_else(|| {
+          self
+            .acme_domain
+            .first()
+            .map(|domain| format!("https://{domain}"))
+        })
+        .unwrap_or_default()
+        .trim_end_matches('/')
+        .into(),
+    )
+  }
+
+  /// `Some(true)` if a transport was enabled on the command line,
+  /// `Some(false)` if it was disabled, and `None` if neither.
+  fn flag(enable: bool, disable: bool) -> Option<bool> {
+    if enable {
+      Some(true)
+    } else if disable {
+      Some(false)
+    } else {
+      None
+    }
+  }
+
+  pub(crate) fn open_index(&self, options: &Options) -> Result<Index> {
+    if self.read_only || options.config().server.read_only {
+      Index::open_read_only(options)
+    } else {
+      Index::open(options)
+    }
+  }
+
   pub(crate) fn run(self, options: Options, index: Arc<Index>, handle: Handle) -> Result {
+    let server = self.with_config(&options.config().server);
+
     Runtime::new()?.block_on(async {
-      let clone = index.clone();
-      thread::spawn(move || loop {
-        if let Err(error) = clone.update() {
-          log::error!("{error}");
-        }
-        thread::sleep(Duration::from_millis(100));
-      });
+      if !index.is_read_only() {
+        let clone = index.clone();
+        thread::spawn(move || loop {
+          if let Err(error) = clone.update() {
+            log::error!("{error}");
+          }
+          thread::sleep(Duration::from_millis(100));
+        });
+      }
+
+      let throttle = Arc::new(Throttle::new(
+        server.rate_limit,
+        server
+          .max_expensive_queries
+          .unwrap_or(Throttle::DEFAULT_MAX_EXPENSIVE_QUERIES),
+        server.trusted_proxy.clone(),
+      ));
 
-      let router = Router::new()
-        .route("/", get(Self::home))
-        .route("/block/:hash", get(Self::block))
-        .route("/bounties", get(Self::bounties))
-        .route("/clock", get(Self::clock))
-        .route("/faq", get(Self::faq))
-        .route("/favicon.ico", get(Self::favicon))
-        .route("/height", get(Self::height))
-        .route("/input/:block/:transaction/:input", get(Self::input))
-        .route("/ordinal/:ordinal", get(Self::ordinal))
-        .route("/output/:output", get(Self::output))
-        .route("/range/:start/:end", get(Self::range))
-        .route("/rare.txt", get(Self::rare_txt))
-        .route("/search", get(Self::search_by_query))
-        .route("/search/:query", get(Self::search_by_path))
-        .route("/static/*path", get(Self::static_asset))
-        .route("/status", get(Self::status))
-        .route("/tx/:txid", get(Self::transaction))
+      let (expensive, cheap) = routes::routes()
+        .into_iter()
+        .partition::<Vec<_>, _>(|route| route.expensive);
+
+      let expensive = expensive
+        .into_iter()
+        .fold(Router::new(), |router, route| {
+          router.route(route.path, route.handler)
+        })
+        .route_layer(middleware::from_fn(throttle::expensive));
+
+      let router = cheap
+        .into_iter()
+        .fold(Router::new(), |router, route| {
+          router.route(route.path, route.handler)
+        })
+        .route_layer(middleware::from_fn(throttle::timeout))
+        .merge(expensive)
+        .layer(middleware::from_fn(cache::etag))
+        .layer(middleware::from_fn(throttle::rate_limit))
+        .layer(Extension(throttle))
+        .layer(Extension(server.base_url()))
         .layer(Extension(index))
-        .layer(Extension(options.chain))
+        .layer(Extension(options.chain()))
         .layer(
           CorsLayer::new()
             .allow_methods([http::Method::GET])
             .allow_origin(Any),
         );
 
-      match (self.http_port(), self.https_port()) {
-        (Some(http_port), None) => self.spawn(router, handle, http_port, None)?.await??,
+      match (server.http_port(), server.https_port()) {
+        (Some(http_port), None) => server.spawn(router, handle, http_port, None)?.await??,
         (None, Some(https_port)) => {
-          self
-            .spawn(router, handle, https_port, Some(self.acceptor(&options)?))?
+          server
+            .spawn(router, handle, https_port, Some(server.acceptor(&options)?))?
             .await??
         }
         (Some(http_port), Some(https_port)) => {
           let (http_result, https_result) = tokio::join!(
-            self.spawn(router.clone(), handle.clone(), http_port, None)?,
-            self.spawn(router, handle, https_port, Some(self.acceptor(&options)?))?
+            server.spawn(router.clone(), handle.clone(), http_port, None)?,
+            server.spawn(router, handle, https_port, Some(server.acceptor(&options)?))?
           );
           http_result.and(https_result)??;
         }
-        (None, None) => unreachable!(),
+        (None, None) => bail!("HTTP and HTTPS are both disabled"),
       }
 
       Ok(())
@@ -185,7 +387,7 @@ impl Server {
     port: u16,
     https_acceptor: Option<AxumAcceptor>,
   ) -> Result<task::JoinHandle<io::Result<()>>> {
-    let addr = (self.address.as_str(), port)
+    let addr = (self.address.as_deref().unwrap_or("0.0.0.0"), port)
       .to_socket_addrs()?
       .next()
       .ok_or_else(|| anyhow!("failed to get socket addrs"))?;
@@ -195,12 +397,12 @@ impl Server {
         axum_server::Server::bind(addr)
           .handle(handle)
           .acceptor(acceptor)
-          .serve(router.into_make_service())
+          .serve(router.into_make_service_with_connect_info::<SocketAddr>())
           .await
       } else {
         axum_server::Server::bind(addr)
           .handle(handle)
-          .serve(router.into_make_service())
+          .serve(router.into_make_service_with_connect_info::<SocketAddr>())
           .await
       }
     }))
@@ -225,7 +427,9 @@ impl Server {
   }
 
   fn http_port(&self) -> Option<u16> {
-    if self.http || self.http_port.is_some() || (self.https_port.is_none() && !self.https) {
+    if self.no_http {
+      None
+    } else if self.http || self.http_port.is_some() || (self.https_port.is_none() && !self.https) {
       Some(self.http_port.unwrap_or(80))
     } else {
       None
@@ -233,7 +437,9 @@ impl Server {
   }
 
   fn https_port(&self) -> Option<u16> {
-    if self.https || self.https_port.is_some() {
+    if self.no_https {
+      None
+    } else if self.https || self.https_port.is_some() {
       Some(self.https_port.unwrap_or(443))
     } else {
       None
@@ -305,14 +511,20 @@ impl Server {
       .map_err(ServerError::Internal)?
       .ok_or_else(|| ServerError::NotFound(format!("output {outpoint} unknown")))?;
 
-    let output = index
-      .transaction(outpoint.txid)
-      .map_err(ServerError::Internal)?
-      .ok_or_else(|| ServerError::NotFound(format!("output {outpoint} unknown")))?
-      .output
-      .into_iter()
-      .nth(outpoint.vout as usize)
-      .ok_or_else(|| ServerError::NotFound(format!("output {outpoint} unknown")))?;
+    let output = if Index::lost_in_block(outpoint).is_some() {
+      None
+    } else {
+      Some(
+        index
+          .transaction(outpoint.txid)
+          .map_err(ServerError::Internal)?
+          .ok_or_else(|| ServerError::NotFound(format!("output {outpoint} unknown")))?
+          .output
+          .into_iter()
+          .nth(outpoint.vout as usize)
+          .ok_or_else(|| ServerError::NotFound(format!("output {outpoint} unknown")))?,
+      )
+    };
 
     Ok(
       OutputHtml {
@@ -326,24 +538,117 @@ impl Server {
   }
 
   async fn range(
+    Extension(index): Extension<Arc<Index>>,
     Path((DeserializeFromStr(start), DeserializeFromStr(end))): Path<(
       DeserializeFromStr<Ordinal>,
       DeserializeFromStr<Ordinal>,
     )>,
   ) -> ServerResult<PageHtml> {
     match start.cmp(&end) {
-      Ordering::Equal => Err(ServerError::BadRequest("empty range".to_string())),
-      Ordering::Greater => Err(ServerError::BadRequest(
-        "range start greater than range end".to_string(),
-      )),
-      Ordering::Less => Ok(RangeHtml { start, end }.page()),
+      Ordering::Equal => return Err(ServerError::BadRequest("empty range".to_string())),
+      Ordering::Greater => {
+        return Err(ServerError::BadRequest(
+          "range start greater than range end".to_string(),
+        ))
+      }
+      Ordering::Less => {}
     }
+
+    let outputs = index
+      .range_outputs(start.n()..end.n(), RangeHtml::LIMIT + 1)
+      .map_err(|err| {
+        ServerError::Internal(anyhow!(
+          "error serving request for range {start}–{end}: {err}"
+        ))
+      })?;
+
+    let rare_ordinals = index
+      .rare_ordinal_satpoints_matching(start.n()..end.n(), |_| true, 0, RangeHtml::LIMIT + 1)
+      .map_err(|err| {
+        ServerError::Internal(anyhow!(
+          "error serving request for range {start}–{end}: {err}"
+        ))
+      })?;
+
+    Ok(
+      RangeHtml {
+        start,
+        end,
+        outputs,
+        rare_ordinals,
+      }
+      .page(),
+    )
   }
 
-  async fn rare_txt(Extension(index): Extension<Arc<Index>>) -> ServerResult<RareTxt> {
-    Ok(RareTxt(index.rare_ordinal_satpoints().map_err(|err| {
-      ServerError::Internal(anyhow!("error getting rare ordinal satpoints: {err}"))
-    })?))
+  fn rare_ordinal_satpoints(
+    index: &Index,
+    query: &RareQuery,
+    skip: usize,
+    take: usize,
+  ) -> ServerResult<Vec<(Ordinal, SatPoint)>> {
+    index
+      .rare_ordinal_satpoints_matching(
+        query.ordinals(),
+        |ordinal| query.matches(ordinal),
+        skip,
+        take,
+      )
+      .map_err(|err| ServerError::Internal(anyhow!("error getting rare ordinal satpoints: {err}")))
+  }
+
+  async fn rare(
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<RareQuery>,
+  ) -> ServerResult<PageHtml> {
+    let page = query.page();
+
+    let mut ordinals = Self::rare_ordinal_satpoints(
+      &index,
+      &query,
+      page.saturating_mul(RareQuery::PAGE_SIZE),
+      RareQuery::PAGE_SIZE + 1,
+    )?;
+
+    let more = ordinals.len() > RareQuery::PAGE_SIZE;
+
+    ordinals.truncate(RareQuery::PAGE_SIZE);
+
+    let spent_in_mempool = index
+      .mempool_spent_outpoints()
+      .map_err(|err| ServerError::Internal(anyhow!("error checking mempool: {err}")))?;
+
+    let ordinals = ordinals
+      .into_iter()
+      .map(|(ordinal, satpoint)| {
+        (
+          ordinal,
+          satpoint,
+          spent_in_mempool.contains(&satpoint.outpoint),
+        )
+      })
+      .collect();
+
+    Ok(
+      RareHtml {
+        ordinals,
+        prev: page.checked_sub(1).map(|prev| query.href(prev)),
+        next: more.then(|| query.href(page + 1)),
+      }
+      .page(),
+    )
+  }
+
+  async fn rare_txt(
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<RareQuery>,
+  ) -> ServerResult<RareTxt> {
+    Ok(RareTxt(Self::rare_ordinal_satpoints(
+      &index,
+      &query,
+      0,
+      usize::MAX,
+    )?))
   }
 
   async fn home(Extension(index): Extension<Arc<Index>>) -> ServerResult<PageHtml> {
@@ -357,10 +662,114 @@ impl Server {
     )
   }
 
+  async fn blocks(
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<BlocksQuery>,
+  ) -> ServerResult<PageHtml> {
+    let height = index
+      .height()
+      .map_err(|err| ServerError::Internal(anyhow!("error getting height: {err}")))?
+      .n();
+
+    let before = query.before.unwrap_or(height + 1);
+
+    Ok(
+      BlocksHtml::new(
+        index
+          .blocks_before(before, BlocksHtml::PAGE_SIZE)
+          .map_err(|err| ServerError::Internal(anyhow!("error getting blocks: {err}")))?,
+        before,
+        height,
+      )
+      .page(),
+    )
+  }
+
+  fn feed(feed: FeedXml) -> Response {
+    (
+      [(header::CONTENT_TYPE, "application/rss+xml")],
+      feed.to_string(),
+    )
+      .into_response()
+  }
+
+  /// The time of the block with `hash`, from its header.
+  fn block_time(index: &Index, hash: BlockHash) -> ServerResult<u32> {
+    Ok(
+      index
+        .block_header(hash)
+        .map_err(|err| ServerError::Internal(anyhow!("error getting block {hash}: {err}")))?
+        .ok_or_else(|| ServerError::Internal(anyhow!("block {hash} not found")))?
+        .time,
+    )
+  }
+
+  async fn blocks_feed(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(BaseUrl(base_url)): Extension<BaseUrl>,
+  ) -> ServerResult<Response> {
+    let mut blocks = Vec::new();
+
+    for (height, hash) in index
+      .blocks(FeedXml::ITEMS as u64)
+      .map_err(|err| ServerError::Internal(anyhow!("error getting blocks: {err}")))?
+    {
+      blocks.push((height, hash, Self::block_time(&index, hash)?));
+    }
+
+    Ok(Self::feed(FeedXml::blocks(&base_url, blocks)))
+  }
+
+  async fn rare_feed(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(BaseUrl(base_url)): Extension<BaseUrl>,
+  ) -> ServerResult<Response> {
+    let mut moves = Vec::new();
+
+    // Moves are newest block first, so only the last block's time is needed
+    let mut last_block = None;
+
+    for (height, ordinal, satpoint) in index
+      .rare_ordinal_moves(FeedXml::ITEMS)
+      .map_err(|err| ServerError::Internal(anyhow!("error getting rare ordinal moves: {err}")))?
+    {
+      let time = match last_block {
+        Some((last_height, time)) if last_height == height => time,
+        _ => {
+          let hash = index
+            .block_hash(height)
+            .map_err(|err| {
+              ServerError::Internal(anyhow!("error getting block at height {height}: {err}"))
+            })?
+            .ok_or_else(|| ServerError::Internal(anyhow!("block {height} not indexed")))?;
+          let time = Self::block_time(&index, hash)?;
+          last_block = Some((height, time));
+          time
+        }
+      };
+
+      moves.push((height, time, ordinal, satpoint));
+    }
+
+    Ok(Self::feed(FeedXml::rare(&base_url, moves)))
+  }
+
   async fn block(
-    Path(hash): Path<BlockHash>,
+    Path(DeserializeFromStr(query)): Path<DeserializeFromStr<BlockQuery>>,
     index: Extension<Arc<Index>>,
-  ) -> ServerResult<PageHtml> {
+  ) -> ServerResult<Response> {
+    let hash = match query {
+      BlockQuery::Hash(hash) => hash,
+      BlockQuery::Height(height) => index
+        .block_hash(height)
+        .map_err(|err| {
+          ServerError::Internal(anyhow!(
+            "error serving request for block at height {height}: {err}"
+          ))
+        })?
+        .ok_or_else(|| ServerError::NotFound(format!("block {height} unknown")))?,
+    };
+
     let info = index
       .block_header_info(hash)
       .map_err(|err| {
@@ -379,28 +788,93 @@ impl Server {
       })?
       .ok_or_else(|| ServerError::NotFound(format!("block {hash} unknown")))?;
 
-    Ok(BlockHtml::new(block, Height(info.height as u64)).page())
+    let stats = index.block_stats(info.height as u64).map_err(|err| {
+      ServerError::Internal(anyhow!(
+        "error serving request for block with hash {hash}: {err}"
+      ))
+    })?;
+
+    let index_height = index.height().map_err(|err| {
+      ServerError::Internal(anyhow!(
+        "error serving request for block with hash {hash}: {err}"
+      ))
+    })?;
+
+    let immutable = stats.is_some()
+      && info.confirmations >= 0
+      && cache::is_deep(info.height as u64, index_height);
+
+    let page = BlockHtml::new(block, Height(info.height as u64), stats).page();
+
+    Ok(if immutable {
+      ([(header::CACHE_CONTROL, cache::IMMUTABLE)], page).into_response()
+    } else {
+      page.into_response()
+    })
+  }
+
+  fn transaction_flow(index: &Index, txid: Txid) -> ServerResult<(Transaction, TransactionFlow)> {
+    let transaction = index
+      .transaction(txid)
+      .map_err(|err| {
+        ServerError::Internal(anyhow!(
+          "error serving request for transaction {txid}: {err}"
+        ))
+      })?
+      .ok_or_else(|| ServerError::NotFound(format!("transaction {txid} unknown")))?;
+
+    let flow = index.transaction_flow(&transaction).map_err(|err| {
+      ServerError::Internal(anyhow!(
+        "error serving request for transaction {txid}: {err}"
+      ))
+    })?;
+
+    Ok((transaction, flow))
   }
 
   async fn transaction(
     Extension(index): Extension<Arc<Index>>,
     Extension(chain): Extension<Chain>,
     Path(txid): Path<Txid>,
-  ) -> ServerResult<PageHtml> {
-    Ok(
-      TransactionHtml::new(
-        index
-          .transaction(txid)
-          .map_err(|err| {
-            ServerError::Internal(anyhow!(
-              "error serving request for transaction {txid}: {err}"
-            ))
-          })?
-          .ok_or_else(|| ServerError::NotFound(format!("transaction {txid} unknown")))?,
-        chain,
-      )
-      .page(),
-    )
+  ) -> ServerResult<Response> {
+    let (transaction, flow) = Self::transaction_flow(&index, txid)?;
+
+    // Output ranges disappear from the page as outputs are spent, so only
+    // deep transactions whose outputs are all spent are settled.
+    let immutable = flow.outputs.iter().all(|output| output.ranges.is_none()) && {
+      let height = index.transaction_height(txid).map_err(|err| {
+        ServerError::Internal(anyhow!(
+          "error serving request for transaction {txid}: {err}"
+        ))
+      })?;
+
+      let index_height = index.height().map_err(|err| {
+        ServerError::Internal(anyhow!(
+          "error serving request for transaction {txid}: {err}"
+        ))
+      })?;
+
+      height.map_or(false, |height| cache::is_deep(height, index_height))
+    };
+
+    let page = TransactionHtml::new(transaction, flow, chain).page();
+
+    Ok(if immutable {
+      ([(header::CACHE_CONTROL, cache::IMMUTABLE)], page).into_response()
+    } else {
+      page.into_response()
+    })
+  }
+
+  async fn transaction_json(
+    Extension(index): Extension<Arc<Index>>,
+    Path(txid): Path<Txid>,
+  ) -> ServerResult<Json<TransactionFlow>> {
+    Ok(Json(Self::transaction_flow(&index, txid)?.1))
+  }
+
+  async fn openapi() -> Json<serde_json::Value> {
+    Json(openapi::spec(&routes::routes()))
   }
 
   async fn status(Extension(index): Extension<Arc<Index>>) -> (StatusCode, &'static str) {
@@ -419,49 +893,172 @@ impl Server {
 
   async fn search_by_query(
     Extension(index): Extension<Arc<Index>>,
+    Extension(chain): Extension<Chain>,
     Query(search): Query<Search>,
-  ) -> ServerResult<Redirect> {
-    Self::search(&index, &search.query).await
+  ) -> ServerResult<Response> {
+    Self::search(&index, chain, &search.query).await
   }
 
   async fn search_by_path(
     Extension(index): Extension<Arc<Index>>,
+    Extension(chain): Extension<Chain>,
     Path(search): Path<Search>,
-  ) -> ServerResult<Redirect> {
-    Self::search(&index, &search.query).await
+  ) -> ServerResult<Response> {
+    Self::search(&index, chain, &search.query).await
+  }
+
+  async fn search(index: &Index, chain: Chain, query: &str) -> ServerResult<Response> {
+    Self::search_inner(index, chain, query)
   }
 
-  async fn search(index: &Index, query: &str) -> ServerResult<Redirect> {
-    Self::search_inner(index, query)
+  /// Redirect to the only result for `query`, or show every result when the
+  /// query is ambiguous or there is something to say about it.
+  fn search_inner(index: &Index, chain: Chain, query: &str) -> ServerResult<Response> {
+    let query = query.trim();
+
+    let (results, notes) = Self::search_results(index, chain, query)?;
+
+    if results.len() == 1 && notes.is_empty() {
+      return Ok(Redirect::to(&results[0].0).into_response());
+    }
+
+    let status = if results.is_empty() && notes.is_empty() {
+      StatusCode::NOT_FOUND
+    } else {
+      StatusCode::OK
+    };
+
+    Ok(
+      (
+        status,
+        SearchHtml {
+          query: query.into(),
+          results,
+          notes,
+        }
+        .page(),
+      )
+        .into_response(),
+    )
   }
 
-  fn search_inner(index: &Index, query: &str) -> ServerResult<Redirect> {
+  /// Links to whatever `query` might refer to, along with notes about
+  /// queries that were recognized but have nothing to link to.
+  fn search_results(
+    index: &Index,
+    chain: Chain,
+    query: &str,
+  ) -> ServerResult<(Vec<(String, String)>, Vec<String>)> {
     lazy_static! {
       static ref HASH: Regex = Regex::new(r"^[[:xdigit:]]{64}$").unwrap();
       static ref OUTPOINT: Regex = Regex::new(r"^[[:xdigit:]]{64}:\d+$").unwrap();
+      static ref SATPOINT: Regex = Regex::new(r"^([[:xdigit:]]{64}:\d+):(\d+)$").unwrap();
+      static ref BLOCK_HEIGHT: Regex = Regex::new(r"^block\s+(\d+)$").unwrap();
+      static ref NUMBER: Regex = Regex::new(r"^\d+$").unwrap();
     }
 
-    let query = query.trim();
+    let internal =
+      |err: Error| ServerError::Internal(anyhow!("error searching for {query}: {err}"));
+
+    let mut results = Vec::new();
+    let mut notes = Vec::new();
 
     if HASH.is_match(query) {
       if index
         .block_header(query.parse().unwrap())
-        .map_err(|err| {
-          ServerError::Internal(anyhow!(
-            "failed to retrieve block {query} from index: {err}"
-          ))
-        })?
+        .map_err(internal)?
         .is_some()
       {
-        Ok(Redirect::to(&format!("/block/{query}")))
-      } else {
-        Ok(Redirect::to(&format!("/tx/{query}")))
+        results.push((format!("/block/{query}"), format!("block {query}")));
+      }
+
+      if results.is_empty()
+        || index
+          .transaction(query.parse().unwrap())
+          .map_err(internal)?
+          .is_some()
+      {
+        results.push((format!("/tx/{query}"), format!("transaction {query}")));
       }
     } else if OUTPOINT.is_match(query) {
-      Ok(Redirect::to(&format!("/output/{query}")))
+      results.push((format!("/output/{query}"), format!("output {query}")));
+    } else if let Some(captures) = SATPOINT.captures(query) {
+      let outpoint = captures[1]
+        .parse::<OutPoint>()
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+      let mut offset = captures[2]
+        .parse::<u64>()
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+      if let Some(List::Unspent(ranges)) = index.list(outpoint).map_err(internal)? {
+        for (start, end) in ranges {
+          if offset < end - start {
+            let ordinal = start + offset;
+            results.push((format!("/ordinal/{ordinal}"), format!("ordinal {ordinal}")));
+            break;
+          }
+          offset -= end - start;
+        }
+      }
+
+      if results.is_empty() {
+        results.push((format!("/output/{outpoint}"), format!("output {outpoint}")));
+      }
+    } else if let Some(captures) = BLOCK_HEIGHT.captures(query) {
+      results.push((
+        format!("/block/{}", &captures[1]),
+        format!("block {}", &captures[1]),
+      ));
     } else {
-      Ok(Redirect::to(&format!("/ordinal/{query}")))
+      if NUMBER.is_match(query) {
+        if let Ok(height) = query.parse::<u64>() {
+          if height <= index.height().map_err(internal)?.n() {
+            results.push((format!("/block/{height}"), format!("block {height}")));
+          }
+        }
+      }
+
+      if let Ok(address) = query.parse::<Address>() {
+        if address.is_valid_for_network(chain.network()) {
+          let script_pubkey = address.script_pubkey();
+
+          let mut watched_outputs = 0;
+          for (outpoint, watched) in index.watched_outputs().map_err(internal)? {
+            if watched == script_pubkey {
+              results.push((
+                format!("/output/{outpoint}"),
+                format!("watched output {outpoint}"),
+              ));
+              watched_outputs += 1;
+            }
+          }
+
+          if watched_outputs == 0 {
+            notes.push(if index.is_watched(&script_pubkey).map_err(internal)? {
+              format!("Address {address} has no unspent outputs.")
+            } else {
+              format!(
+                "Address {address} is not watched. Run `ord watch add {address}` to track its outputs."
+              )
+            });
+          }
+        }
+      }
+
+      if let Ok(rarity) = query.parse::<Rarity>() {
+        results.push((
+          format!("/rare?rarity={rarity}"),
+          format!("{rarity} ordinals"),
+        ));
+      }
+
+      if let Ok(ordinal) = query.parse::<Ordinal>() {
+        results.push((format!("/ordinal/{ordinal}"), format!("ordinal {ordinal}")));
+      }
     }
+
+    Ok((results, notes))
   }
 
   async fn favicon() -> ServerResult<Response> {
@@ -530,7 +1127,7 @@ impl Server {
 
 #[cfg(test)]
 mod tests {
-  use {super::*, reqwest::Url, std::net::TcpListener, tempfile::TempDir};
+  use {super::*, bitcoin::WPubkeyHash, reqwest::Url, std::net::TcpListener, tempfile::TempDir};
 
   struct TestServer {
     bitcoin_rpc_server: test_bitcoincore_rpc::Handle,
@@ -543,6 +1140,10 @@ mod tests {
 
   impl TestServer {
     fn new() -> Self {
+      Self::new_with_args("")
+    }
+
+    fn new_with_args(args: &str) -> Self {
       let bitcoin_rpc_server = test_bitcoincore_rpc::spawn();
 
       let tempdir = TempDir::new().unwrap();
@@ -560,11 +1161,12 @@ mod tests {
       let url = Url::parse(&format!("http://127.0.0.1:{port}")).unwrap();
 
       let (options, server) = parse_server_args(&format!(
-        "ord --chain regtest --rpc-url {} --cookie-file {} --data-dir {} server --http-port {} --address 127.0.0.1",
+        "ord --chain regtest --rpc-url {} --cookie-file {} --data-dir {} server --http-port {} --address 127.0.0.1 {}",
         bitcoin_rpc_server.url(),
         cookiefile.to_str().unwrap(),
         tempdir.path().to_str().unwrap(),
         port,
+        args,
       ));
 
       let index = Arc::new(Index::open(&options).unwrap());
@@ -775,18 +1377,97 @@ mod tests {
   }
 
   #[test]
-  fn acme_cache_defaults_to_data_dir() {
-    let arguments = Arguments::try_parse_from(&["ord", "--data-dir", "foo", "server"]).unwrap();
-    let acme_cache = Server::acme_cache(None, &arguments.options)
-      .unwrap()
-      .display()
-      .to_string();
-    assert!(acme_cache.contains("foo/acme-cache"), "{acme_cache}")
+  fn config_fills_in_missing_settings() {
+    let server = parse_server_args("ord server --http-port 8080 --acme-domain foo").1;
+
+    let server = server.with_config(&ServerConfig {
+      acme_cache: Some("bar".into()),
+      acme_contact: vec!["baz".into()],
+      acme_domain: vec!["qux".into()],
+      address: Some("127.0.0.1".into()),
+      http_port: Some(1),
+      https: Some(true),
+      ..Default::default()
+    });
+
+    assert_eq!(server.address.as_deref(), Some("127.0.0.1"));
+    assert_eq!(server.acme_domain, vec!["foo".to_string()]);
+    assert_eq!(server.acme_contact, vec!["baz".to_string()]);
+    assert_eq!(server.acme_cache, Some("bar".into()));
+    assert_eq!(server.http_port(), Some(8080));
+    assert_eq!(server.https_port(), Some(443));
   }
 
   #[test]
-  fn acme_cache_flag_is_respected() {
-    let arguments =
+  fn command_line_disables_transports_enabled_in_config() {
+    let config = ServerConfig {
+      http: Some(true),
+      https: Some(true),
+      ..Default::default()
+    };
+
+    let server = parse_server_args("ord server --no-https")
+      .1
+      .with_config(&config);
+    assert_eq!(server.http_port(), Some(80));
+    assert_eq!(server.https_port(), None);
+
+    let server = parse_server_args("ord server --no-http --acme-domain foo")
+      .1
+      .with_config(&config);
+    assert_eq!(server.http_port(), None);
+    assert_eq!(server.https_port(), Some(443));
+  }
+
+  #[test]
+  fn config_disables_default_http() {
+    let config = ServerConfig {
+      http: Some(false),
+      https: Some(true),
+      ..Default::default()
+    };
+
+    let server = parse_server_args("ord server --acme-domain foo")
+      .1
+      .with_config(&config);
+    assert_eq!(server.http_port(), None);
+    assert_eq!(server.https_port(), Some(443));
+
+    let server = parse_server_args("ord server --http --acme-domain foo")
+      .1
+      .with_config(&config);
+    assert_eq!(server.http_port(), Some(80));
+  }
+
+  #[test]
+  fn last_transport_flag_wins() {
+    assert_eq!(
+      parse_server_args("ord server --no-http --http")
+        .1
+        .http_port(),
+      Some(80)
+    );
+    assert_eq!(
+      parse_server_args("ord server --http --no-http --https")
+        .1
+        .http_port(),
+      None
+    );
+  }
+
+  #[test]
+  fn acme_cache_defaults_to_data_dir() {
+    let arguments = Arguments::try_parse_from(&["ord", "--data-dir", "foo", "server"]).unwrap();
+    let acme_cache = Server::acme_cache(None, &arguments.options)
+      .unwrap()
+      .display()
+      .to_string();
+    assert!(acme_cache.contains("foo/acme-cache"), "{acme_cache}")
+  }
+
+  #[test]
+  fn acme_cache_flag_is_respected() {
+    let arguments =
       Arguments::try_parse_from(&["ord", "--data-dir", "foo", "server", "--acme-cache", "bar"])
         .unwrap();
     let acme_cache = Server::acme_cache(Some(&"bar".into()), &arguments.options)
@@ -824,17 +1505,131 @@ mod tests {
 
   #[test]
   fn search_by_query_returns_ordinal() {
-    TestServer::new().assert_redirect("/search?query=0", "/ordinal/0");
+    TestServer::new().assert_redirect("/search?query=5000000000", "/ordinal/5000000000");
   }
 
   #[test]
   fn search_is_whitespace_insensitive() {
-    TestServer::new().assert_redirect("/search/ 0 ", "/ordinal/0");
+    TestServer::new().assert_redirect("/search/ 5000000000 ", "/ordinal/5000000000");
   }
 
   #[test]
   fn search_by_path_returns_ordinal() {
-    TestServer::new().assert_redirect("/search/0", "/ordinal/0");
+    TestServer::new().assert_redirect("/search/5000000000", "/ordinal/5000000000");
+  }
+
+  #[test]
+  fn search_for_number_that_could_be_height_or_ordinal_shows_results() {
+    TestServer::new().assert_response_regex(
+      "/search/0",
+      StatusCode::OK,
+      ".*<title>Search results for 0</title>.*
+<ul>
+  <li><a href=/block/0>block 0</a></li>
+  <li><a href=/ordinal/0>ordinal 0</a></li>
+</ul>
+.*",
+    );
+  }
+
+  #[test]
+  fn search_for_ordinal_name_returns_ordinal() {
+    TestServer::new().assert_redirect("/search/nvtdijuwxlp", "/ordinal/0");
+  }
+
+  #[test]
+  fn search_for_decimal_and_degree_returns_ordinal() {
+    TestServer::new().assert_redirect("/search/1.0", "/ordinal/5000000000");
+    TestServer::new().assert_redirect("/search/0°0′0″0‴", "/ordinal/0");
+  }
+
+  #[test]
+  fn search_for_rarity_shows_rarity_and_ordinal_name() {
+    TestServer::new().assert_response_regex(
+      "/search/rare",
+      StatusCode::OK,
+      ".*<li><a href=/rare\\?rarity=rare>rare ordinals</a></li>
+  <li><a href=/ordinal/[[:digit:]]+>ordinal [[:digit:]]+</a></li>
+.*",
+    );
+  }
+
+  #[test]
+  fn search_for_satpoint_returns_ordinal() {
+    TestServer::new().assert_redirect(
+      "/search/4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:7",
+      "/ordinal/7",
+    );
+  }
+
+  #[test]
+  fn search_for_unknown_satpoint_returns_output() {
+    TestServer::new().assert_redirect(
+      "/search/0000000000000000000000000000000000000000000000000000000000000000:0:7",
+      "/output/0000000000000000000000000000000000000000000000000000000000000000:0",
+    );
+  }
+
+  #[test]
+  fn search_for_watched_address_returns_output() {
+    let test_server = TestServer::new();
+
+    let address = Chain::Regtest
+      .address_from_script(&Script::new_v0_p2wpkh(&WPubkeyHash::all_zeros()))
+      .unwrap();
+
+    test_server.index.watch(&address.script_pubkey()).unwrap();
+
+    let txid = test_server
+      .bitcoin_rpc_server
+      .mine_blocks_with_script_pubkey(1, address.script_pubkey())[0]
+      .txdata[0]
+      .txid();
+
+    test_server.index.update().unwrap();
+
+    test_server.assert_redirect(&format!("/search/{address}"), &format!("/output/{txid}:0"));
+  }
+
+  #[test]
+  fn search_for_unwatched_address_says_so() {
+    let address = Chain::Regtest
+      .address_from_script(&Script::new_v0_p2wpkh(&WPubkeyHash::all_zeros()))
+      .unwrap();
+
+    TestServer::new().assert_response_regex(
+      &format!("/search/{address}"),
+      StatusCode::OK,
+      &format!(
+        ".*<p>Address {address} is not watched. Run `ord watch add {address}` to track its outputs.</p>.*"
+      ),
+    );
+  }
+
+  #[test]
+  fn search_for_watched_address_without_outputs_says_so() {
+    let test_server = TestServer::new();
+
+    let address = Chain::Regtest
+      .address_from_script(&Script::new_v0_p2wpkh(&WPubkeyHash::all_zeros()))
+      .unwrap();
+
+    test_server.index.watch(&address.script_pubkey()).unwrap();
+
+    test_server.assert_response_regex(
+      &format!("/search/{address}"),
+      StatusCode::OK,
+      &format!(".*<p>Address {address} has no unspent outputs.</p>.*"),
+    );
+  }
+
+  #[test]
+  fn search_without_results_returns_404() {
+    TestServer::new().assert_response_regex(
+      "/search/foo:bar",
+      StatusCode::NOT_FOUND,
+      ".*<p>No results.</p>.*",
+    );
   }
 
   #[test]
@@ -861,6 +1656,16 @@ mod tests {
     );
   }
 
+  #[test]
+  fn search_for_block_height_returns_block() {
+    TestServer::new().assert_redirect("/search/block 0", "/block/0");
+  }
+
+  #[test]
+  fn search_for_block_height_is_whitespace_insensitive() {
+    TestServer::new().assert_redirect("/search?query=%20block%20%201%20", "/block/1");
+  }
+
   #[test]
   fn status() {
     TestServer::new().assert_response("/status", StatusCode::OK, "OK");
@@ -929,7 +1734,25 @@ mod tests {
 <dl>
   <dt>value</dt><dd>1</dd>
   <dt>first</dt><dd><a href=/ordinal/0 class=mythic>0</a></dd>
-</dl>.*",
+  <dt>block</dt><dd><a href=/block/0>0</a></dd>
+</dl>
+<h2>1 Output</h2>
+<ul class=monospace>
+  <li>
+    <a href=/output/4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0</a>
+    <ul>
+      <li><a href=/ordinal/0 class=mythic>0</a></li>
+    </ul>
+  </li>
+</ul>
+<h2>1 Rare Ordinal</h2>
+<ul class=monospace>
+  <li>
+    <a href=/ordinal/0 class=mythic>0</a>
+    at <a href=/output/4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0</a>
+  </li>
+</ul>
+.*",
     );
   }
   #[test]
@@ -1018,6 +1841,26 @@ mod tests {
     );
   }
 
+  #[test]
+  fn lost_output() {
+    let test_server = TestServer::new();
+
+    test_server
+      .bitcoin_rpc_server
+      .mine_blocks_with_subsidy(1, 25 * COIN_VALUE);
+
+    test_server.assert_response_regex(
+      "/output/0000000000000000000000000000000000000000000000000000000000000000:1",
+      StatusCode::OK,
+      ".*<h1>Output <span class=monospace>0000000000000000000000000000000000000000000000000000000000000000:1</span></h1>
+<p>Ordinals lost in <a href=/block/1>block 1</a>.</p>
+<h2>1 Ordinal Range</h2>
+<ul class=monospace>
+  <li><a href=/range/7500000000/10000000000 class=common>7500000000–10000000000</a></li>
+</ul>.*",
+    );
+  }
+
   #[test]
   fn invalid_output_returns_400() {
     TestServer::new().assert_response(
@@ -1074,6 +1917,64 @@ mod tests {
     );
   }
 
+  #[test]
+  fn block_by_height() {
+    let test_server = TestServer::new();
+
+    let block_hash = test_server.bitcoin_rpc_server.mine_blocks(1)[0].block_hash();
+
+    test_server.assert_response_regex(
+      "/block/1",
+      StatusCode::OK,
+      &format!(".*<h1>Block <span class=monospace>{block_hash}</span></h1>\n<dl>\n  <dt>height</dt><dd>1</dd>.*"),
+    );
+  }
+
+  #[test]
+  fn block_height_not_found() {
+    TestServer::new().assert_response("/block/1", StatusCode::NOT_FOUND, "block 1 unknown");
+  }
+
+  #[test]
+  fn blocks() {
+    let test_server = TestServer::new();
+
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+
+    test_server.assert_response_regex(
+      "/blocks",
+      StatusCode::OK,
+      ".*<title>Blocks</title>.*
+<h1>Blocks</h1>
+<ol start=1 reversed class=blocks>
+  <li><a href=/block/[[:xdigit:]]{64}>[[:xdigit:]]{64}</a></li>
+  <li><a href=/block/000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f>000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f</a></li>
+</ol>
+<a>newer</a>
+<a>older</a>
+.*",
+    );
+  }
+
+  #[test]
+  fn blocks_before() {
+    let test_server = TestServer::new();
+
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+
+    test_server.assert_response_regex(
+      "/blocks?before=1",
+      StatusCode::OK,
+      ".*<h1>Blocks</h1>
+<ol start=0 reversed class=blocks>
+  <li><a href=/block/000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f>000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f</a></li>
+</ol>
+<a href=/blocks\\?before=2>newer</a>
+<a>older</a>
+.*",
+    );
+  }
+
   #[test]
   fn unmined_ordinal() {
     TestServer::new().assert_response_regex(
@@ -1139,7 +2040,23 @@ mod tests {
   <dt>size</dt><dd>203</dd>
   <dt>weight</dt><dd>812</dd>
   <dt>prev blockhash</dt><dd><a href=/block/659f9b67fbc0b5cba0ef6ebc0aea322e1c246e29e43210bd581f5f3bd36d17bf class=monospace>659f9b67fbc0b5cba0ef6ebc0aea322e1c246e29e43210bd581f5f3bd36d17bf</a></dd>
+  <dt>ordinals</dt><dd><a href=/range/10000000000/15000000000 class=uncommon>10000000000–15000000000</a></dd>
+  <dt>subsidy</dt><dd>5000000000</dd>
+  <dt>fees</dt><dd>0</dd>
+  <dt>lost</dt><dd>0</dd>
+  <dt>rare ordinals mined</dt><dd>1</dd>
+  <dt>rare ordinals moved</dt><dd>1</dd>
+  <dt>ordinal ranges created</dt><dd>2</dd>
 </dl>
+<h2>Coinbase Outputs</h2>
+<ul class=monospace>
+  <li>
+    <a href=/output/[[:xdigit:]]{64}:0>[[:xdigit:]]{64}:0</a>
+    <ul>
+      <li><a href=/range/10000000000/15000000000 class=uncommon>10000000000–15000000000</a></li>
+    </ul>
+  </li>
+</ul>
 <h2>2 Transactions</h2>
 <ul class=monospace>
   <li><a href=/tx/[[:xdigit:]]{64}>[[:xdigit:]]{64}</a></li>
@@ -1160,6 +2077,12 @@ mod tests {
       StatusCode::OK,
       &format!(
         ".*<title>Transaction {txid}</title>.*<h1>Transaction <span class=monospace>{txid}</span></h1>
+<h2>1 Input</h2>
+<ul class=monospace>
+  <li>
+    coinbase
+  </li>
+</ul>
 <h2>1 Output</h2>
 <ul class=monospace>
   <li>
@@ -1170,12 +2093,95 @@ mod tests {
       <dt>value</dt><dd>5000000000</dd>
       <dt>script pubkey</dt><dd class=data></dd>
     </dl>
+    <ul>
+      <li><a href=/range/5000000000/10000000000 class=uncommon>5000000000–10000000000</a></li>
+    </ul>
   </li>
+</ul>
+<p>The index only keeps the ordinal ranges of unspent outputs, so where ordinals went is only shown in full while every input is unspent, as for unconfirmed transactions.</p>
+<h2>1 Rare Ordinal</h2>
+<ul class=monospace>
+  <li><a href=/ordinal/5000000000 class=uncommon>5000000000</a></li>
 </ul>.*"
       ),
     );
   }
 
+  #[test]
+  fn unconfirmed_transaction_json_assigns_ranges() {
+    let test_server = TestServer::new();
+
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+    let txid = test_server
+      .bitcoin_rpc_server
+      .broadcast_tx(TransactionTemplate {
+        input_slots: &[(1, 0, 0)],
+        output_count: 2,
+        fee: 10,
+      });
+
+    let response = test_server.get(&format!("/api/tx/{txid}"));
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let flow: serde_json::Value = serde_json::from_str(&response.text().unwrap()).unwrap();
+
+    assert_eq!(flow["complete"], true);
+    assert_eq!(
+      flow["inputs"][0]["ranges"],
+      serde_json::json!([[5000000000u64, 10000000000u64]])
+    );
+    assert_eq!(
+      flow["outputs"][0]["ranges"],
+      serde_json::json!([[5000000000u64, 7499999995u64]])
+    );
+    assert_eq!(
+      flow["outputs"][1]["ranges"],
+      serde_json::json!([[7499999995u64, 9999999990u64]])
+    );
+    assert_eq!(
+      flow["fee_ranges"],
+      serde_json::json!([[9999999990u64, 10000000000u64]])
+    );
+    assert_eq!(flow["rare_ordinals"], serde_json::json!([5000000000u64]));
+  }
+
+  #[test]
+  fn confirmed_transaction_json_is_incomplete() {
+    let test_server = TestServer::new();
+
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+    let txid = test_server
+      .bitcoin_rpc_server
+      .broadcast_tx(TransactionTemplate {
+        input_slots: &[(1, 0, 0)],
+        output_count: 1,
+        fee: 10,
+      });
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+
+    let response = test_server.get(&format!("/api/tx/{txid}"));
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let flow: serde_json::Value = serde_json::from_str(&response.text().unwrap()).unwrap();
+
+    assert_eq!(flow["complete"], false);
+    assert_eq!(flow["inputs"][0]["ranges"], serde_json::Value::Null);
+    assert_eq!(
+      flow["outputs"][0]["ranges"],
+      serde_json::json!([[5000000000u64, 9999999990u64]])
+    );
+    assert_eq!(flow["fee_ranges"], serde_json::Value::Null);
+  }
+
+  #[test]
+  fn unknown_transaction_json_returns_404() {
+    TestServer::new().assert_response(
+      "/api/tx/0000000000000000000000000000000000000000000000000000000000000000",
+      StatusCode::NOT_FOUND,
+      "transaction 0000000000000000000000000000000000000000000000000000000000000000 unknown",
+    );
+  }
+
   #[test]
   fn detect_reorg() {
     let test_server = TestServer::new();
@@ -1201,6 +2207,83 @@ mod tests {
     );
   }
 
+  #[test]
+  fn rare_txt_filters() {
+    let test_server = TestServer::new();
+
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+
+    test_server.assert_response(
+      "/rare.txt?rarity=uncommon",
+      StatusCode::OK,
+      "ordinal\tsatpoint
+5000000000\t0c4eb1fa83a7d6ce0e21e5e616a96e83a7b1658170fb544acf6f5c6a2d4b3f90:0:0
+",
+    );
+
+    test_server.assert_response(
+      "/rare.txt?min_height=2",
+      StatusCode::OK,
+      "ordinal\tsatpoint\n",
+    );
+  }
+
+  #[test]
+  fn rare_page_shows_ordinals_spent_in_mempool() {
+    let test_server = TestServer::new();
+
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+    test_server
+      .bitcoin_rpc_server
+      .broadcast_tx(TransactionTemplate {
+        input_slots: &[(1, 0, 0)],
+        output_count: 1,
+        fee: 0,
+      });
+
+    test_server.assert_response_regex(
+      "/rare?epoch=0",
+      StatusCode::OK,
+      ".*<title>Rare Ordinals</title>.*
+<ul class=monospace>
+  <li>
+    <a href=/ordinal/0 class=mythic>0</a>
+    at <a href=/output/4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0</a>
+  </li>
+  <li>
+    <a href=/ordinal/5000000000 class=uncommon>5000000000</a>
+    at <a href=/output/0c4eb1fa83a7d6ce0e21e5e616a96e83a7b1658170fb544acf6f5c6a2d4b3f90:0>0c4eb1fa83a7d6ce0e21e5e616a96e83a7b1658170fb544acf6f5c6a2d4b3f90:0:0</a>
+    \\(spent in mempool\\)
+  </li>
+</ul>
+<a>prev</a>
+<a>next</a>
+.*",
+    );
+  }
+
+  #[test]
+  fn rare_page_links_to_previous_page() {
+    TestServer::new().assert_response_regex(
+      "/rare?rarity=mythic&page=1",
+      StatusCode::OK,
+      ".*<ul class=monospace>
+</ul>
+<a href=/rare\\?rarity=mythic>prev</a>
+<a>next</a>
+.*",
+    );
+  }
+
+  #[test]
+  fn rare_page_rejects_unknown_rarity() {
+    TestServer::new().assert_response_regex(
+      "/rare?rarity=foo",
+      StatusCode::BAD_REQUEST,
+      ".*unknown variant.*",
+    );
+  }
+
   #[test]
   fn input() {
     TestServer::new().assert_response_regex(
@@ -1384,4 +2467,267 @@ mod tests {
       5,
     );
   }
+
+  #[test]
+  fn responses_have_etag_and_revalidate() {
+    let test_server = TestServer::new();
+
+    let response = test_server.get("/");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CACHE_CONTROL).unwrap(),
+      "public, no-cache"
+    );
+
+    let etag = response.headers().get(header::ETAG).unwrap().clone();
+    assert!(etag.to_str().unwrap().starts_with("\"0-"));
+
+    let response = reqwest::blocking::Client::new()
+      .get(test_server.join_url("/"))
+      .header(header::IF_NONE_MATCH, etag.clone())
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers().get(header::ETAG).unwrap(), etag);
+    assert_eq!(response.text().unwrap(), "");
+
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+    test_server.index.update().unwrap();
+
+    let response = reqwest::blocking::Client::new()
+      .get(test_server.join_url("/"))
+      .header(header::IF_NONE_MATCH, etag)
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+  }
+
+  #[test]
+  fn errors_have_no_etag() {
+    let test_server = TestServer::new();
+    let response = test_server.get("/block/1");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().get(header::ETAG).is_none());
+  }
+
+  #[test]
+  fn deep_blocks_are_immutable() {
+    let test_server = TestServer::new();
+
+    test_server.bitcoin_rpc_server.mine_blocks(5);
+
+    let response = test_server.get("/block/0");
+    assert_eq!(
+      response.headers().get(header::CACHE_CONTROL).unwrap(),
+      "public, max-age=31536000, immutable"
+    );
+    let etag = response.headers().get(header::ETAG).unwrap().clone();
+    assert!(!etag.to_str().unwrap().contains('-'));
+
+    let response = test_server.get("/block/1");
+    assert_eq!(
+      response.headers().get(header::CACHE_CONTROL).unwrap(),
+      "public, no-cache"
+    );
+
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+
+    let response = test_server.get("/block/0");
+    assert_eq!(response.headers().get(header::ETAG).unwrap(), etag);
+  }
+
+  #[test]
+  fn deep_transactions_with_spent_outputs_are_immutable() {
+    let test_server = TestServer::new();
+
+    let coinbase = test_server.bitcoin_rpc_server.mine_blocks(1)[0].txdata[0].txid();
+    let txid = test_server
+      .bitcoin_rpc_server
+      .broadcast_tx(TransactionTemplate {
+        input_slots: &[(1, 0, 0)],
+        output_count: 1,
+        fee: 0,
+      });
+    test_server.bitcoin_rpc_server.mine_blocks(5);
+
+    assert_eq!(
+      test_server
+        .get(&format!("/tx/{coinbase}"))
+        .headers()
+        .get(header::CACHE_CONTROL)
+        .unwrap(),
+      "public, max-age=31536000, immutable"
+    );
+
+    assert_eq!(
+      test_server
+        .get(&format!("/tx/{txid}"))
+        .headers()
+        .get(header::CACHE_CONTROL)
+        .unwrap(),
+      "public, no-cache"
+    );
+  }
+
+  #[test]
+  fn rate_limited_requests_get_retry_after() {
+    let test_server = TestServer::new_with_args("--rate-limit 1");
+
+    let response = (0..3)
+      .map(|_| reqwest::blocking::get(test_server.join_url("/status")).unwrap())
+      .find(|response| response.status() == StatusCode::TOO_MANY_REQUESTS)
+      .unwrap();
+
+    assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "1");
+  }
+
+  #[test]
+  fn rate_limit_must_be_positive() {
+    assert!(Arguments::try_parse_from(["ord", "server", "--rate-limit", "0"]).is_err());
+  }
+
+  #[test]
+  fn requests_from_trusted_proxies_are_limited_by_forwarded_address() {
+    let test_server = TestServer::new_with_args("--rate-limit 1 --trusted-proxy 127.0.0.1");
+
+    let client = reqwest::blocking::Client::new();
+
+    for i in 0..3 {
+      let response = client
+        .get(test_server.join_url("/status"))
+        .header("X-Forwarded-For", format!("10.0.0.{i}"))
+        .send()
+        .unwrap();
+
+      assert_eq!(response.status(), StatusCode::OK);
+    }
+  }
+
+  #[test]
+  fn expensive_queries_are_limited() {
+    let test_server = TestServer::new_with_args("--max-expensive-queries 0");
+
+    let response = test_server.get("/rare.txt");
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "1");
+
+    test_server.assert_response("/height", StatusCode::OK, "0");
+  }
+
+  #[test]
+  fn openapi_json() {
+    let test_server = TestServer::new();
+
+    let response = test_server.get("/api/openapi.json");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "application/json"
+    );
+
+    let spec: serde_json::Value = serde_json::from_str(&response.text().unwrap()).unwrap();
+
+    assert_eq!(spec["openapi"], "3.0.3");
+    assert_eq!(
+      spec["paths"]["/output/{output}"]["get"]["parameters"][0]["schema"]["pattern"],
+      "^[0-9a-f]{64}:[0-9]+$"
+    );
+    assert_eq!(
+      spec["paths"]["/api/tx/{txid}"]["get"]["responses"]["200"]["content"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .collect::<Vec<&String>>(),
+      ["application/json"]
+    );
+  }
+
+  #[test]
+  fn blocks_feed() {
+    let test_server = TestServer::new();
+
+    let block_hash = test_server.bitcoin_rpc_server.mine_blocks(1)[0].block_hash();
+
+    let response = test_server.get("/feed/blocks.xml");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "application/rss+xml"
+    );
+
+    let body = response.text().unwrap();
+    assert_regex_match!(
+      body,
+      &format!(
+        r#".*<link>/</link>.*<title>Block 1</title>
+      <link>/block/{block_hash}</link>
+      <guid isPermaLink="false">{block_hash}</guid>
+      <pubDate>[A-Z][a-z]{{2}}, \d{{2}} [A-Z][a-z]{{2}} \d{{4}} \d{{2}}:\d{{2}}:\d{{2}} \+0000</pubDate>.*<title>Block 0</title>.*"#
+      )
+    );
+  }
+
+  #[test]
+  fn base_url_defaults_to_first_acme_domain() {
+    assert_eq!(
+      parse_server_args("ord server --acme-domain foo.com --acme-domain bar.com")
+        .1
+        .base_url()
+        .0,
+      "https://foo.com"
+    );
+    assert_eq!(
+      parse_server_args("ord server --base-url https://baz.com/ --acme-domain foo.com")
+        .1
+        .base_url()
+        .0,
+      "https://baz.com"
+    );
+    assert_eq!(parse_server_args("ord server").1.base_url().0, "");
+  }
+
+  #[test]
+  fn feed_links_use_base_url() {
+    let test_server = TestServer::new_with_args("--base-url https://ordinals.com/");
+
+    let body = test_server.get("/feed/rare.xml").text().unwrap();
+
+    assert_regex_match!(
+      body,
+      r#".*<link>https://ordinals.com/</link>.*<link>https://ordinals.com/ordinal/0</link>.*"#
+    );
+  }
+
+  #[test]
+  fn rare_feed_item_ids_are_stable() {
+    let test_server = TestServer::new();
+
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+
+    let guid = Regex::new(r#"<guid isPermaLink="false">([^<]*)</guid>"#).unwrap();
+
+    let guids = |body: &str| {
+      guid
+        .captures_iter(body)
+        .map(|captures| captures[1].to_string())
+        .collect::<Vec<String>>()
+    };
+
+    let before = guids(&test_server.get("/feed/rare.xml").text().unwrap());
+
+    assert_eq!(
+      before,
+      [
+        "5000000000:0c4eb1fa83a7d6ce0e21e5e616a96e83a7b1658170fb544acf6f5c6a2d4b3f90:0:0",
+        "0:4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0",
+      ]
+    );
+
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+
+    let after = guids(&test_server.get("/feed/rare.xml").text().unwrap());
+
+    assert_eq!(after.len(), 3);
+    assert_eq!(after[1..], before);
+  }
 }
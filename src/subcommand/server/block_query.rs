@@ -0,0 +1,41 @@
+use super::*;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum BlockQuery {
+  Hash(BlockHash),
+  Height(u64),
+}
+
+impl FromStr for BlockQuery {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(if s.len() == 64 {
+      Self::Hash(s.parse()?)
+    } else {
+      Self::Height(s.parse()?)
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn from_str() {
+    assert_eq!(
+      "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+        .parse::<BlockQuery>()
+        .unwrap(),
+      BlockQuery::Hash(
+        "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+          .parse()
+          .unwrap()
+      )
+    );
+    assert_eq!("0".parse::<BlockQuery>().unwrap(), BlockQuery::Height(0));
+    assert!("foo".parse::<BlockQuery>().is_err());
+    assert!("-1".parse::<BlockQuery>().is_err());
+  }
+}
@@ -0,0 +1,176 @@
+use {
+  super::*,
+  axum::{
+    body::HttpBody,
+    http::{HeaderValue, Request},
+    middleware::Next,
+  },
+  bitcoin::hashes::sha256,
+};
+
+/// `Cache-Control` for pages whose content can no longer change.
+pub(crate) const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// `Cache-Control` for everything else. Clients may cache, but must
+/// revalidate with the ETag before reuse.
+const REVALIDATE: &str = "public, no-cache";
+
+/// Blocks, and transactions confirmed in blocks, with at least this many
+/// confirmations in the index are considered settled.
+pub(crate) const IMMUTABLE_DEPTH: u64 = 6;
+
+/// Whether the block at `height` has at least `IMMUTABLE_DEPTH`
+/// confirmations in an index whose tip is at `index_height`.
+pub(crate) fn is_deep(height: u64, index_height: Height) -> bool {
+  index_height.n() >= height && index_height.n() - height + 1 >= IMMUTABLE_DEPTH
+}
+
+/// Responses whose bodies may be larger than this, like an unfiltered
+/// `/rare.txt`, are not buffered to compute a digest.
+const MAX_DIGEST_LEN: u64 = 1 << 20;
+
+/// Adds an ETag and `Cache-Control` to successful responses, and answers
+/// requests whose `If-None-Match` matches with `304 Not Modified`. ETags of
+/// immutable responses depend only on their content, those of all other
+/// responses also on the index height. Responses too large to buffer get a
+/// weak ETag of only the index height, or none if they are immutable.
+pub(crate) async fn etag<B>(request: Request<B>, next: Next<B>) -> ServerResult<Response> {
+  let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+
+  let index = request.extensions().get::<Arc<Index>>().cloned();
+
+  let response = next.run(request).await;
+
+  if response.status() != StatusCode::OK {
+    return Ok(response);
+  }
+
+  let (mut parts, mut body) = response.into_parts();
+
+  let immutable = parts
+    .headers
+    .get(header::CACHE_CONTROL)
+    .map_or(false, |cache_control| cache_control == IMMUTABLE);
+
+  let content = if body
+    .size_hint()
+    .upper()
+    .map_or(false, |upper| upper <= MAX_DIGEST_LEN)
+  {
+    let mut content = Vec::new();
+    while let Some(chunk) = body.data().await {
+      content.extend_from_slice(
+        &chunk.map_err(|err| ServerError::Internal(anyhow!("error buffering response: {err}")))?,
+      );
+    }
+    Some(content)
+  } else {
+    None
+  };
+
+  let height = match index {
+    Some(index) if !immutable => Some(
+      index
+        .height()
+        .map_err(|err| ServerError::Internal(anyhow!("error getting index height: {err}")))?,
+    ),
+    _ => None,
+  };
+
+  let etag = tag(height, content.as_deref());
+
+  let body = match content {
+    Some(content) => body::boxed(body::Full::from(content)),
+    None => body,
+  };
+
+  parts
+    .headers
+    .entry(header::CACHE_CONTROL)
+    .or_insert(HeaderValue::from_static(REVALIDATE));
+
+  let etag = match etag {
+    Some(etag) => etag,
+    None => return Ok(Response::from_parts(parts, body)),
+  };
+
+  parts.headers.insert(
+    header::ETAG,
+    HeaderValue::from_str(&etag).map_err(|err| ServerError::Internal(err.into()))?,
+  );
+
+  if if_none_match.map_or(false, |if_none_match| matches(&if_none_match, &etag)) {
+    let mut response = StatusCode::NOT_MODIFIED.into_response();
+    for name in [header::ETAG, header::CACHE_CONTROL] {
+      if let Some(value) = parts.headers.remove(&name) {
+        response.headers_mut().insert(name, value);
+      }
+    }
+    return Ok(response);
+  }
+
+  Ok(Response::from_parts(parts, body))
+}
+
+/// The ETag of a response with buffered `content`, if it wasn't too large,
+/// rendered at index `height`, if it isn't immutable.
+fn tag(height: Option<Height>, content: Option<&[u8]>) -> Option<String> {
+  match (height, content.map(sha256::Hash::hash)) {
+    (Some(height), Some(digest)) => Some(format!("\"{height}-{digest}\"")),
+    (None, Some(digest)) => Some(format!("\"{digest}\"")),
+    (Some(height), None) => Some(format!("W/\"{height}\"")),
+    (None, None) => None,
+  }
+}
+
+/// Whether an `If-None-Match` header matches `etag`, using weak comparison.
+fn matches(if_none_match: &HeaderValue, etag: &str) -> bool {
+  let etag = etag.trim_start_matches("W/");
+  if_none_match.to_str().map_or(false, |if_none_match| {
+    if_none_match
+      .split(',')
+      .map(str::trim)
+      .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn depth() {
+    assert!(!is_deep(0, Height(4)));
+    assert!(is_deep(0, Height(5)));
+    assert!(!is_deep(6, Height(5)));
+  }
+
+  #[test]
+  fn if_none_match() {
+    let etag = "\"abc\"";
+    assert!(matches(&HeaderValue::from_static("\"abc\""), etag));
+    assert!(matches(&HeaderValue::from_static("W/\"abc\""), etag));
+    assert!(matches(&HeaderValue::from_static("\"x\", \"abc\""), etag));
+    assert!(matches(&HeaderValue::from_static("*"), etag));
+    assert!(!matches(&HeaderValue::from_static("\"abcd\""), etag));
+  }
+
+  #[test]
+  fn weak_etag_matches() {
+    assert!(matches(&HeaderValue::from_static("W/\"5\""), "W/\"5\""));
+    assert!(matches(&HeaderValue::from_static("\"5\""), "W/\"5\""));
+    assert!(!matches(&HeaderValue::from_static("W/\"6\""), "W/\"5\""));
+  }
+
+  #[test]
+  fn tags() {
+    let digest = sha256::Hash::hash(b"foo");
+    assert_eq!(
+      tag(Some(Height(5)), Some(b"foo")),
+      Some(format!("\"5-{digest}\""))
+    );
+    assert_eq!(tag(None, Some(b"foo")), Some(format!("\"{digest}\"")));
+    assert_eq!(tag(Some(Height(5)), None), Some("W/\"5\"".into()));
+    assert_eq!(tag(None, None), None);
+  }
+}
@@ -0,0 +1,213 @@
+use {super::*, axum::routing::MethodRouter, serde_json::json};
+
+/// A route served by `Server::run`, with its path in axum syntax, the
+/// handler serving it, whether it is an expensive query, and its
+/// description.
+pub(crate) struct Route {
+  pub(crate) path: &'static str,
+  pub(crate) handler: MethodRouter,
+  pub(crate) expensive: bool,
+  pub(crate) summary: &'static str,
+  pub(crate) parameters: &'static [Parameter],
+  pub(crate) returns: Returns,
+}
+
+pub(crate) struct Parameter {
+  name: &'static str,
+  location: Location,
+  kind: Kind,
+}
+
+#[derive(Copy, Clone)]
+enum Location {
+  Path,
+  Query,
+}
+
+#[derive(Copy, Clone)]
+pub(crate) enum Kind {
+  BlockQuery,
+  Integer,
+  Ordinal,
+  OutPoint,
+  Rarity,
+  String,
+  Txid,
+}
+
+pub(crate) enum Returns {
+  Content(&'static str),
+  Redirect,
+  Search,
+}
+
+impl Parameter {
+  pub(crate) const fn path(name: &'static str, kind: Kind) -> Self {
+    Self {
+      name,
+      location: Location::Path,
+      kind,
+    }
+  }
+
+  pub(crate) const fn query(name: &'static str, kind: Kind) -> Self {
+    Self {
+      name,
+      location: Location::Query,
+      kind,
+    }
+  }
+
+  fn json(&self) -> serde_json::Value {
+    let (location, required) = match self.location {
+      Location::Path => ("path", true),
+      Location::Query => ("query", false),
+    };
+
+    json!({
+      "name": self.name,
+      "in": location,
+      "required": required,
+      "schema": self.kind.schema(),
+    })
+  }
+}
+
+impl Kind {
+  fn schema(self) -> serde_json::Value {
+    match self {
+      Self::BlockQuery => json!({
+        "description": "Block hash or height",
+        "oneOf": [
+          { "type": "string", "pattern": "^[0-9a-f]{64}$" },
+          { "type": "integer", "minimum": 0 },
+        ],
+      }),
+      Self::Integer => json!({ "type": "integer", "minimum": 0 }),
+      Self::Ordinal => json!({
+        "description": "Ordinal as an integer, in decimal notation like `1.0`, in degree notation like `0°1′1″0‴`, as a percentile like `50%`, or by name",
+        "type": "string",
+      }),
+      Self::OutPoint => json!({
+        "description": "Outpoint as `TXID:VOUT`",
+        "type": "string",
+        "pattern": "^[0-9a-f]{64}:[0-9]+$",
+      }),
+      Self::Rarity => json!({
+        "type": "string",
+        "enum": ["common", "uncommon", "rare", "epic", "legendary", "mythic"],
+      }),
+      Self::String => json!({ "type": "string" }),
+      Self::Txid => json!({ "type": "string", "pattern": "^[0-9a-f]{64}$" }),
+    }
+  }
+}
+
+impl Route {
+  /// The path in OpenAPI syntax, with `{name}` for each parameter.
+  fn openapi_path(&self) -> String {
+    self
+      .path
+      .split('/')
+      .map(|segment| match segment.strip_prefix([':', '*']) {
+        Some(name) => format!("{{{name}}}"),
+        None => segment.into(),
+      })
+      .collect::<Vec<String>>()
+      .join("/")
+  }
+
+  fn json(&self) -> serde_json::Value {
+    let mut responses = match self.returns {
+      Returns::Content(content_type) => json!({
+        "200": {
+          "description": "OK",
+          "content": { content_type: {} },
+        },
+      }),
+      Returns::Redirect => json!({
+        "303": { "description": "Redirect" },
+      }),
+      Returns::Search => json!({
+        "200": {
+          "description": "Every result, when there are several",
+          "content": { "text/html": {} },
+        },
+        "303": { "description": "Redirect to the only result" },
+      }),
+    };
+
+    responses["default"] = json!({
+      "description": "Error",
+      "content": { "text/plain": {} },
+    });
+
+    json!({
+      "get": {
+        "summary": self.summary,
+        "parameters": self
+          .parameters
+          .iter()
+          .map(Parameter::json)
+          .collect::<Vec<serde_json::Value>>(),
+        "responses": responses,
+      },
+    })
+  }
+}
+
+/// OpenAPI description of `routes`.
+pub(crate) fn spec(routes: &[Route]) -> serde_json::Value {
+  json!({
+    "openapi": "3.0.3",
+    "info": {
+      "title": "ord",
+      "version": env!("CARGO_PKG_VERSION"),
+    },
+    "paths": routes
+      .iter()
+      .map(|route| (route.openapi_path(), route.json()))
+      .collect::<serde_json::Map<String, serde_json::Value>>(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn every_path_parameter_is_described() {
+    for route in routes::routes() {
+      let names = route
+        .path
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix([':', '*']))
+        .collect::<Vec<&str>>();
+
+      let parameters = route
+        .parameters
+        .iter()
+        .filter(|parameter| matches!(parameter.location, Location::Path))
+        .map(|parameter| parameter.name)
+        .collect::<Vec<&str>>();
+
+      assert_eq!(names, parameters, "{}", route.path);
+    }
+  }
+
+  #[test]
+  fn openapi_path() {
+    assert_eq!(
+      Route {
+        path: "/range/:start/:end",
+        handler: MethodRouter::new(),
+        expensive: false,
+        summary: "",
+        parameters: &[],
+        returns: Returns::Redirect,
+      }
+      .openapi_path(),
+      "/range/{start}/{end}"
+    );
+  }
+}
@@ -0,0 +1,150 @@
+use {super::*, std::ops::Range};
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub(crate) struct RareQuery {
+  pub(crate) rarity: Option<Rarity>,
+  pub(crate) epoch: Option<u64>,
+  pub(crate) min_height: Option<u64>,
+  pub(crate) max_height: Option<u64>,
+  pub(crate) page: Option<usize>,
+}
+
+impl RareQuery {
+  pub(crate) const PAGE_SIZE: usize = 100;
+
+  /// The range of ordinals that can match the epoch and height filters.
+  pub(crate) fn ordinals(&self) -> Range<u64> {
+    let mut start = 0;
+    let mut end = Ordinal::SUPPLY;
+
+    if let Some(epoch) = self.epoch {
+      start = start.max(Epoch(epoch).starting_ordinal().n());
+      end = end.min(Epoch(epoch.saturating_add(1)).starting_ordinal().n());
+    }
+
+    if let Some(min_height) = self.min_height {
+      start = start.max(Height(min_height).starting_ordinal().n());
+    }
+
+    if let Some(max_height) = self.max_height {
+      end = end.min(Height(max_height.saturating_add(1)).starting_ordinal().n());
+    }
+
+    start..end.max(start)
+  }
+
+  pub(crate) fn matches(&self, ordinal: Ordinal) -> bool {
+    self
+      .rarity
+      .as_ref()
+      .map_or(true, |rarity| ordinal.rarity() == *rarity)
+  }
+
+  pub(crate) fn page(&self) -> usize {
+    self.page.unwrap_or(0)
+  }
+
+  /// Link to `page` of the `/rare` listing with the same filters.
+  pub(crate) fn href(&self, page: usize) -> String {
+    let mut parameters = Vec::new();
+
+    if let Some(rarity) = &self.rarity {
+      parameters.push(format!("rarity={rarity}"));
+    }
+
+    if let Some(epoch) = self.epoch {
+      parameters.push(format!("epoch={epoch}"));
+    }
+
+    if let Some(min_height) = self.min_height {
+      parameters.push(format!("min_height={min_height}"));
+    }
+
+    if let Some(max_height) = self.max_height {
+      parameters.push(format!("max_height={max_height}"));
+    }
+
+    if page > 0 {
+      parameters.push(format!("page={page}"));
+    }
+
+    if parameters.is_empty() {
+      "/rare".into()
+    } else {
+      format!("/rare?{}", parameters.join("&"))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unfiltered_query_covers_every_ordinal() {
+    assert_eq!(RareQuery::default().ordinals(), 0..Ordinal::SUPPLY);
+  }
+
+  #[test]
+  fn epoch_restricts_ordinals() {
+    assert_eq!(
+      RareQuery {
+        epoch: Some(1),
+        ..Default::default()
+      }
+      .ordinals(),
+      Epoch(1).starting_ordinal().n()..Epoch(2).starting_ordinal().n()
+    );
+  }
+
+  #[test]
+  fn heights_restrict_ordinals() {
+    assert_eq!(
+      RareQuery {
+        min_height: Some(1),
+        max_height: Some(2),
+        ..Default::default()
+      }
+      .ordinals(),
+      50 * COIN_VALUE..150 * COIN_VALUE
+    );
+  }
+
+  #[test]
+  fn disjoint_filters_match_no_ordinals() {
+    assert!(RareQuery {
+      epoch: Some(0),
+      min_height: Some(SUBSIDY_HALVING_INTERVAL),
+      ..Default::default()
+    }
+    .ordinals()
+    .is_empty());
+  }
+
+  #[test]
+  fn rarity_filter() {
+    let query = RareQuery {
+      rarity: Some(Rarity::Uncommon),
+      ..Default::default()
+    };
+    assert!(query.matches(Ordinal(50 * COIN_VALUE)));
+    assert!(!query.matches(Ordinal(0)));
+    assert!(RareQuery::default().matches(Ordinal(0)));
+  }
+
+  #[test]
+  fn href() {
+    assert_eq!(RareQuery::default().href(0), "/rare");
+    assert_eq!(
+      RareQuery {
+        rarity: Some(Rarity::Epic),
+        epoch: Some(2),
+        min_height: Some(3),
+        max_height: Some(4),
+        page: Some(7),
+      }
+      .href(1),
+      "/rare?rarity=epic&epoch=2&min_height=3&max_height=4&page=1"
+    );
+  }
+}
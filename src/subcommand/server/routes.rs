@@ -0,0 +1,210 @@
+use {
+  super::*,
+  openapi::{Kind, Parameter, Returns, Route},
+};
+
+const RARE_PARAMETERS: &[Parameter] = &[
+  Parameter::query("rarity", Kind::Rarity),
+  Parameter::query("epoch", Kind::Integer),
+  Parameter::query("min_height", Kind::Integer),
+  Parameter::query("max_height", Kind::Integer),
+  Parameter::query("page", Kind::Integer),
+];
+
+/// Every route served by `Server::run`, from which both the router and the
+/// OpenAPI spec are built.
+pub(crate) fn routes() -> Vec<Route> {
+  vec![
+    Route {
+      path: "/",
+      handler: get(Server::home),
+      expensive: false,
+      summary: "Home page, with the latest blocks",
+      parameters: &[],
+      returns: Returns::Content("text/html"),
+    },
+    Route {
+      path: "/api/openapi.json",
+      handler: get(Server::openapi),
+      expensive: false,
+      summary: "This specification",
+      parameters: &[],
+      returns: Returns::Content("application/json"),
+    },
+    Route {
+      path: "/api/tx/:txid",
+      handler: get(Server::transaction_json),
+      expensive: false,
+      summary: "Ordinal ranges flowing through a transaction",
+      parameters: &[Parameter::path("txid", Kind::Txid)],
+      returns: Returns::Content("application/json"),
+    },
+    Route {
+      path: "/block/:query",
+      handler: get(Server::block),
+      expensive: false,
+      summary: "Block by hash or height",
+      parameters: &[Parameter::path("query", Kind::BlockQuery)],
+      returns: Returns::Content("text/html"),
+    },
+    Route {
+      path: "/blocks",
+      handler: get(Server::blocks),
+      expensive: false,
+      summary: "Blocks below a height, highest first",
+      parameters: &[Parameter::query("before", Kind::Integer)],
+      returns: Returns::Content("text/html"),
+    },
+    Route {
+      path: "/bounties",
+      handler: get(Server::bounties),
+      expensive: false,
+      summary: "Redirect to the bounty documentation",
+      parameters: &[],
+      returns: Returns::Redirect,
+    },
+    Route {
+      path: "/clock",
+      handler: get(Server::clock),
+      expensive: false,
+      summary: "Clock showing the current height",
+      parameters: &[],
+      returns: Returns::Content("image/svg+xml"),
+    },
+    Route {
+      path: "/faq",
+      handler: get(Server::faq),
+      expensive: false,
+      summary: "Redirect to the FAQ",
+      parameters: &[],
+      returns: Returns::Redirect,
+    },
+    Route {
+      path: "/favicon.ico",
+      handler: get(Server::favicon),
+      expensive: false,
+      summary: "Favicon",
+      parameters: &[],
+      returns: Returns::Content("image/png"),
+    },
+    Route {
+      path: "/feed/blocks.xml",
+      handler: get(Server::blocks_feed),
+      expensive: false,
+      summary: "RSS feed of the latest blocks",
+      parameters: &[],
+      returns: Returns::Content("application/rss+xml"),
+    },
+    Route {
+      path: "/feed/rare.xml",
+      handler: get(Server::rare_feed),
+      expensive: false,
+      summary: "RSS feed of the latest moves of rare ordinals",
+      parameters: &[],
+      returns: Returns::Content("application/rss+xml"),
+    },
+    Route {
+      path: "/height",
+      handler: get(Server::height),
+      expensive: false,
+      summary: "Height of the index",
+      parameters: &[],
+      returns: Returns::Content("text/plain"),
+    },
+    Route {
+      path: "/input/:block/:transaction/:input",
+      handler: get(Server::input),
+      expensive: false,
+      summary: "Transaction input by block height, transaction index and input index",
+      parameters: &[
+        Parameter::path("block", Kind::Integer),
+        Parameter::path("transaction", Kind::Integer),
+        Parameter::path("input", Kind::Integer),
+      ],
+      returns: Returns::Content("text/html"),
+    },
+    Route {
+      path: "/ordinal/:ordinal",
+      handler: get(Server::ordinal),
+      expensive: true,
+      summary: "Ordinal",
+      parameters: &[Parameter::path("ordinal", Kind::Ordinal)],
+      returns: Returns::Content("text/html"),
+    },
+    Route {
+      path: "/output/:output",
+      handler: get(Server::output),
+      expensive: false,
+      summary: "Transaction output and the ordinal ranges it holds",
+      parameters: &[Parameter::path("output", Kind::OutPoint)],
+      returns: Returns::Content("text/html"),
+    },
+    Route {
+      path: "/range/:start/:end",
+      handler: get(Server::range),
+      expensive: true,
+      summary: "Range of ordinals, from start inclusive to end exclusive",
+      parameters: &[
+        Parameter::path("start", Kind::Ordinal),
+        Parameter::path("end", Kind::Ordinal),
+      ],
+      returns: Returns::Content("text/html"),
+    },
+    Route {
+      path: "/rare",
+      handler: get(Server::rare),
+      expensive: true,
+      summary: "Rare ordinals and their locations",
+      parameters: RARE_PARAMETERS,
+      returns: Returns::Content("text/html"),
+    },
+    Route {
+      path: "/rare.txt",
+      handler: get(Server::rare_txt),
+      expensive: true,
+      summary: "Rare ordinals and their locations, one per line",
+      parameters: RARE_PARAMETERS,
+      returns: Returns::Content("text/plain"),
+    },
+    Route {
+      path: "/search",
+      handler: get(Server::search_by_query),
+      expensive: true,
+      summary: "Search",
+      parameters: &[Parameter::query("query", Kind::String)],
+      returns: Returns::Search,
+    },
+    Route {
+      path: "/search/:query",
+      handler: get(Server::search_by_path),
+      expensive: true,
+      summary: "Search",
+      parameters: &[Parameter::path("query", Kind::String)],
+      returns: Returns::Search,
+    },
+    Route {
+      path: "/static/*path",
+      handler: get(Server::static_asset),
+      expensive: false,
+      summary: "Static asset",
+      parameters: &[Parameter::path("path", Kind::String)],
+      returns: Returns::Content("*/*"),
+    },
+    Route {
+      path: "/status",
+      handler: get(Server::status),
+      expensive: false,
+      summary: "Server status",
+      parameters: &[],
+      returns: Returns::Content("text/plain"),
+    },
+    Route {
+      path: "/tx/:txid",
+      handler: get(Server::transaction),
+      expensive: false,
+      summary: "Transaction",
+      parameters: &[Parameter::path("txid", Kind::Txid)],
+      returns: Returns::Content("text/html"),
+    },
+  ]
+}
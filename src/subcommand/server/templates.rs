@@ -5,18 +5,22 @@ use {
 };
 
 pub(crate) use {
-  block::BlockHtml, clock::ClockSvg, home::HomeHtml, input::InputHtml, ordinal::OrdinalHtml,
-  output::OutputHtml, range::RangeHtml, rare::RareTxt, transaction::TransactionHtml,
+  block::BlockHtml, blocks::BlocksHtml, clock::ClockSvg, feed::FeedXml, home::HomeHtml,
+  input::InputHtml, ordinal::OrdinalHtml, output::OutputHtml, range::RangeHtml, rare::RareHtml,
+  rare::RareTxt, search::SearchHtml, transaction::TransactionHtml,
 };
 
 mod block;
+mod blocks;
 mod clock;
+mod feed;
 mod home;
 mod input;
 mod ordinal;
 mod output;
 mod range;
 mod rare;
+mod search;
 mod transaction;
 
 #[derive(Boilerplate)]
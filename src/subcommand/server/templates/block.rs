@@ -1,18 +1,47 @@
-use super::*;
+use {
+  super::*,
+  crate::index::{assign_ordinal_ranges, BlockStats},
+};
 
 #[derive(Boilerplate)]
 pub(crate) struct BlockHtml {
   hash: BlockHash,
   block: Block,
   height: Height,
+  stats: Option<BlockStats>,
+  coinbase_outputs: Vec<(OutPoint, Vec<(u64, u64)>)>,
 }
 
 impl BlockHtml {
-  pub(crate) fn new(block: Block, height: Height) -> Self {
+  pub(crate) fn new(block: Block, height: Height, stats: Option<BlockStats>) -> Self {
+    let coinbase_outputs = match (&stats, block.coinbase()) {
+      (Some(stats), Some(coinbase)) => {
+        let start = height.starting_ordinal().n();
+
+        let inputs = (stats.subsidy > 0)
+          .then(|| (start, start + stats.subsidy))
+          .into_iter()
+          .chain(stats.fee_ranges.iter().copied())
+          .collect();
+
+        let txid = coinbase.txid();
+
+        assign_ordinal_ranges(inputs, coinbase.output.iter().map(|output| output.value))
+          .0
+          .into_iter()
+          .enumerate()
+          .map(|(vout, ranges)| (OutPoint::new(txid, vout as u32), ranges))
+          .collect()
+      }
+      _ => Vec::new(),
+    };
+
     Self {
       hash: block.header.block_hash(),
       block,
       height,
+      stats,
+      coinbase_outputs,
     }
   }
 }
@@ -30,7 +59,41 @@ mod tests {
   #[test]
   fn block_html() {
     pretty_assert_eq!(
-      BlockHtml::new( Chain::Mainnet.genesis_block() , Height(0))
+      BlockHtml::new(Chain::Mainnet.genesis_block(), Height(0), None).to_string(),
+      "
+        <h1>Block <span class=monospace>000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f</span></h1>
+        <dl>
+          <dt>height</dt><dd>0</dd>
+          <dt>timestamp</dt><dd>1231006505</dd>
+          <dt>size</dt><dd>285</dd>
+          <dt>weight</dt><dd>1140</dd>
+          <dt>prev blockhash</dt><dd><a href=/block/0000000000000000000000000000000000000000000000000000000000000000 class=monospace>0000000000000000000000000000000000000000000000000000000000000000</a></dd>
+        </dl>
+        <h2>1 Transaction</h2>
+        <ul class=monospace>
+          <li><a href=/tx/4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b</a></li>
+        </ul>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn block_html_with_stats() {
+    pretty_assert_eq!(
+      BlockHtml::new(
+        Chain::Mainnet.genesis_block(),
+        Height(0),
+        Some(BlockStats {
+          subsidy: 50 * COIN_VALUE,
+          fees: 0,
+          lost: 0,
+          rare_ordinals_mined: 1,
+          rare_ordinals_moved: 0,
+          ordinal_ranges_created: 1,
+          fee_ranges: Vec::new(),
+        })
+      )
       .to_string(),
       "
         <h1>Block <span class=monospace>000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f</span></h1>
@@ -40,7 +103,23 @@ mod tests {
           <dt>size</dt><dd>285</dd>
           <dt>weight</dt><dd>1140</dd>
           <dt>prev blockhash</dt><dd><a href=/block/0000000000000000000000000000000000000000000000000000000000000000 class=monospace>0000000000000000000000000000000000000000000000000000000000000000</a></dd>
+          <dt>ordinals</dt><dd><a href=/range/0/5000000000 class=mythic>0–5000000000</a></dd>
+          <dt>subsidy</dt><dd>5000000000</dd>
+          <dt>fees</dt><dd>0</dd>
+          <dt>lost</dt><dd>0</dd>
+          <dt>rare ordinals mined</dt><dd>1</dd>
+          <dt>rare ordinals moved</dt><dd>0</dd>
+          <dt>ordinal ranges created</dt><dd>1</dd>
         </dl>
+        <h2>Coinbase Outputs</h2>
+        <ul class=monospace>
+          <li>
+            <a href=/output/4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0</a>
+            <ul>
+              <li><a href=/range/0/5000000000 class=mythic>0–5000000000</a></li>
+            </ul>
+          </li>
+        </ul>
         <h2>1 Transaction</h2>
         <ul class=monospace>
           <li><a href=/tx/4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b</a></li>
@@ -49,4 +128,19 @@ mod tests {
       .unindent()
     );
   }
+
+  #[test]
+  fn block_html_without_fee_ranges() {
+    assert!(BlockHtml::new(
+      Chain::Mainnet.genesis_block(),
+      Height(0),
+      Some(BlockStats {
+        subsidy: 50 * COIN_VALUE,
+        fees: 100,
+        ..Default::default()
+      })
+    )
+    .to_string()
+    .contains("<p>Fee ranges were not recorded for this block, so coinbase outputs only show subsidy ordinals.</p>"));
+  }
 }
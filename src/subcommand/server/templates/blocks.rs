@@ -0,0 +1,97 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct BlocksHtml {
+  blocks: Vec<(u64, BlockHash)>,
+  newer: Option<u64>,
+  older: Option<u64>,
+}
+
+impl BlocksHtml {
+  pub(crate) const PAGE_SIZE: u64 = 100;
+
+  /// `blocks` are the blocks below height `before`, highest first, and
+  /// `height` is the height of the latest block.
+  pub(crate) fn new(blocks: Vec<(u64, BlockHash)>, before: u64, height: u64) -> Self {
+    Self {
+      newer: (before <= height).then(|| (before + Self::PAGE_SIZE).min(height + 1)),
+      older: blocks
+        .last()
+        .map(|(height, _hash)| *height)
+        .filter(|height| *height > 0),
+      blocks,
+    }
+  }
+}
+
+impl Content for BlocksHtml {
+  fn title(&self) -> String {
+    "Blocks".into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn blocks_html() {
+    pretty_assert_eq!(
+      BlocksHtml::new(
+        vec![
+          (
+            101,
+            "1111111111111111111111111111111111111111111111111111111111111111"
+              .parse()
+              .unwrap()
+          ),
+          (
+            100,
+            "0000000000000000000000000000000000000000000000000000000000000000"
+              .parse()
+              .unwrap()
+          )
+        ],
+        102,
+        200
+      )
+      .to_string(),
+      "
+        <h1>Blocks</h1>
+        <ol start=101 reversed class=blocks>
+          <li><a href=/block/1111111111111111111111111111111111111111111111111111111111111111>1111111111111111111111111111111111111111111111111111111111111111</a></li>
+          <li><a href=/block/0000000000000000000000000000000000000000000000000000000000000000>0000000000000000000000000000000000000000000000000000000000000000</a></li>
+        </ol>
+        <a href=/blocks?before=201>newer</a>
+        <a href=/blocks?before=100>older</a>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn latest_and_earliest_blocks_have_no_further_links() {
+    pretty_assert_eq!(
+      BlocksHtml::new(
+        vec![(
+          0,
+          "0000000000000000000000000000000000000000000000000000000000000000"
+            .parse()
+            .unwrap()
+        )],
+        1,
+        0
+      )
+      .to_string(),
+      "
+        <h1>Blocks</h1>
+        <ol start=0 reversed class=blocks>
+          <li><a href=/block/0000000000000000000000000000000000000000000000000000000000000000>0000000000000000000000000000000000000000000000000000000000000000</a></li>
+        </ol>
+        <a>newer</a>
+        <a>older</a>
+      "
+      .unindent()
+    );
+  }
+}
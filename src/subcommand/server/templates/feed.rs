@@ -0,0 +1,163 @@
+use super::*;
+
+/// An RSS feed. Item GUIDs are derived only from what the item describes, so
+/// readers don't show an item twice when the feed is regenerated. Links are
+/// relative to `base_url`, which should be set, since readers resolve
+/// relative links inconsistently.
+#[derive(Boilerplate)]
+pub(crate) struct FeedXml {
+  title: &'static str,
+  base_url: String,
+  items: Vec<FeedItem>,
+}
+
+struct FeedItem {
+  title: String,
+  link: String,
+  guid: String,
+  pub_date: String,
+}
+
+impl FeedXml {
+  pub(crate) const ITEMS: usize = 100;
+
+  /// Blocks, with their heights, hashes, and times, newest first.
+  pub(crate) fn blocks(base_url: &str, blocks: Vec<(u64, BlockHash, u32)>) -> Self {
+    Self {
+      title: "Blocks",
+      base_url: base_url.into(),
+      items: blocks
+        .into_iter()
+        .map(|(height, hash, time)| FeedItem {
+          title: format!("Block {height}"),
+          link: format!("{base_url}/block/{hash}"),
+          guid: hash.to_string(),
+          pub_date: Self::pub_date(time),
+        })
+        .collect(),
+    }
+  }
+
+  /// Moves of rare ordinals, with the heights and times of the blocks they
+  /// happened in, newest first.
+  pub(crate) fn rare(base_url: &str, moves: Vec<(u64, u32, Ordinal, SatPoint)>) -> Self {
+    Self {
+      title: "Rare Ordinals",
+      base_url: base_url.into(),
+      items: moves
+        .into_iter()
+        .map(|(height, time, ordinal, satpoint)| FeedItem {
+          title: if Index::lost_in_block(satpoint.outpoint).is_some() {
+            format!(
+              "{} ordinal {ordinal} lost in block {height}",
+              ordinal.rarity()
+            )
+          } else {
+            format!(
+              "{} ordinal {ordinal} moved to {satpoint} in block {height}",
+              ordinal.rarity()
+            )
+          },
+          link: format!("{base_url}/ordinal/{ordinal}"),
+          guid: format!("{ordinal}:{satpoint}"),
+          pub_date: Self::pub_date(time),
+        })
+        .collect(),
+    }
+  }
+
+  /// RSS dates are in RFC 2822 format.
+  fn pub_date(time: u32) -> String {
+    Utc.timestamp(time.into(), 0).to_rfc2822()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn blocks_feed() {
+    pretty_assert_eq!(
+      FeedXml::blocks(
+        "https://ordinals.com",
+        vec![(
+          0,
+          "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+            .parse()
+            .unwrap(),
+          1231006505,
+        )]
+      )
+      .to_string(),
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Blocks</title>
+    <link>https://ordinals.com/</link>
+    <description>Blocks</description>
+    <item>
+      <title>Block 0</title>
+      <link>https://ordinals.com/block/000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f</link>
+      <guid isPermaLink="false">000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f</guid>
+      <pubDate>Sat, 03 Jan 2009 18:15:05 +0000</pubDate>
+    </item>
+  </channel>
+</rss>
+"#
+    );
+  }
+
+  #[test]
+  fn rare_feed() {
+    pretty_assert_eq!(
+      FeedXml::rare(
+        "https://ordinals.com",
+        vec![
+          (
+            1,
+            1231469665,
+            Ordinal(50 * COIN_VALUE),
+            SatPoint {
+              outpoint: Index::lost_outpoint(1),
+              offset: 0,
+            },
+          ),
+          (
+            0,
+            1231006505,
+            Ordinal(0),
+            SatPoint {
+              outpoint: "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0"
+                .parse()
+                .unwrap(),
+              offset: 0,
+            },
+          ),
+        ]
+      )
+      .to_string(),
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Rare Ordinals</title>
+    <link>https://ordinals.com/</link>
+    <description>Rare Ordinals</description>
+    <item>
+      <title>uncommon ordinal 5000000000 lost in block 1</title>
+      <link>https://ordinals.com/ordinal/5000000000</link>
+      <guid isPermaLink="false">5000000000:0000000000000000000000000000000000000000000000000000000000000000:1:0</guid>
+      <pubDate>Fri, 09 Jan 2009 02:54:25 +0000</pubDate>
+    </item>
+    <item>
+      <title>mythic ordinal 0 moved to 4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0 in block 0</title>
+      <link>https://ordinals.com/ordinal/0</link>
+      <guid isPermaLink="false">0:4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0</guid>
+      <pubDate>Sat, 03 Jan 2009 18:15:05 +0000</pubDate>
+    </item>
+  </channel>
+</rss>
+"#
+    );
+  }
+}
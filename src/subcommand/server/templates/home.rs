@@ -5,6 +5,7 @@ pub(crate) struct HomeHtml {
   last: u64,
   blocks: Vec<BlockHash>,
   starting_ordinal: Option<Ordinal>,
+  older: Option<u64>,
 }
 
 impl HomeHtml {
@@ -18,6 +19,10 @@ impl HomeHtml {
         .map(|(height, _)| height)
         .cloned()
         .unwrap_or(0),
+      older: blocks
+        .last()
+        .map(|(height, _)| *height)
+        .filter(|height| *height > 0),
       blocks: blocks.into_iter().map(|(_, hash)| hash).collect(),
     }
   }
@@ -63,6 +68,7 @@ mod tests {
   <li><a href=/block/1{64}>1{64}</a></li>
   <li><a href=/block/0{64}>0{64}</a></li>
 </ol>
+<a href=/blocks\\?before=1260000>older</a>
 ",
     );
   }
@@ -5,7 +5,9 @@ pub(crate) struct OutputHtml {
   pub(crate) outpoint: OutPoint,
   pub(crate) list: List,
   pub(crate) chain: Chain,
-  pub(crate) output: TxOut,
+  /// `None` for the sentinel outpoint of ordinals lost in a block, which
+  /// isn't a real transaction output.
+  pub(crate) output: Option<TxOut>,
 }
 
 impl Content for OutputHtml {
@@ -30,10 +32,10 @@ mod tests {
           .unwrap(),
         list: List::Unspent(vec![(0, 1), (1, 3)]),
         chain: Chain::Mainnet,
-        output: TxOut {
+        output: Some(TxOut {
           value: 3,
           script_pubkey: Script::new_p2pkh(&PubkeyHash::all_zeros()),
-        },
+        }),
       }
       .to_string(),
       "
@@ -57,19 +59,19 @@ mod tests {
   fn spent_output() {
     pretty_assert_eq!(
       OutputHtml {
-        outpoint: "0000000000000000000000000000000000000000000000000000000000000000:0"
+        outpoint: "1111111111111111111111111111111111111111111111111111111111111111:0"
           .parse()
           .unwrap(),
         list: List::Spent,
         chain: Chain::Mainnet,
-        output: TxOut {
+        output: Some(TxOut {
           value: 1,
           script_pubkey: script::Builder::new().push_scriptint(0).into_script(),
-        },
+        }),
       }
       .to_string(),
       "
-        <h1>Output <span class=monospace>0000000000000000000000000000000000000000000000000000000000000000:0</span></h1>
+        <h1>Output <span class=monospace>1111111111111111111111111111111111111111111111111111111111111111:0</span></h1>
         <dl>
           <dt>value</dt><dd>1</dd>
           <dt>script pubkey</dt><dd class=data>OP_0</dd>
@@ -79,4 +81,26 @@ mod tests {
       .unindent()
     );
   }
+
+  #[test]
+  fn lost_output() {
+    pretty_assert_eq!(
+      OutputHtml {
+        outpoint: Index::lost_outpoint(1),
+        list: List::Unspent(vec![(5_000_000_000, 10_000_000_000)]),
+        chain: Chain::Mainnet,
+        output: None,
+      }
+      .to_string(),
+      "
+        <h1>Output <span class=monospace>0000000000000000000000000000000000000000000000000000000000000000:1</span></h1>
+        <p>Ordinals lost in <a href=/block/1>block 1</a>.</p>
+        <h2>1 Ordinal Range</h2>
+        <ul class=monospace>
+          <li><a href=/range/5000000000/10000000000 class=uncommon>5000000000–10000000000</a></li>
+        </ul>
+      "
+      .unindent()
+    );
+  }
 }
@@ -4,6 +4,13 @@ use super::*;
 pub(crate) struct RangeHtml {
   pub(crate) start: Ordinal,
   pub(crate) end: Ordinal,
+  pub(crate) outputs: Vec<(OutPoint, Vec<(u64, u64)>)>,
+  pub(crate) rare_ordinals: Vec<(Ordinal, SatPoint)>,
+}
+
+impl RangeHtml {
+  /// Maximum number of outputs and of rare ordinals shown on the page.
+  pub(crate) const LIMIT: usize = 100;
 }
 
 impl Content for RangeHtml {
@@ -22,6 +29,8 @@ mod tests {
       RangeHtml {
         start: Ordinal(0),
         end: Ordinal(1),
+        outputs: Vec::new(),
+        rare_ordinals: Vec::new(),
       }
       .to_string(),
       "
@@ -29,6 +38,7 @@ mod tests {
         <dl>
           <dt>value</dt><dd>1</dd>
           <dt>first</dt><dd><a href=/ordinal/0 class=mythic>0</a></dd>
+          <dt>block</dt><dd><a href=/block/0>0</a></dd>
         </dl>
       "
       .unindent()
@@ -41,6 +51,8 @@ mod tests {
       RangeHtml {
         start: Ordinal(1),
         end: Ordinal(10),
+        outputs: Vec::new(),
+        rare_ordinals: Vec::new(),
       }
       .to_string(),
       "
@@ -48,9 +60,92 @@ mod tests {
         <dl>
           <dt>value</dt><dd>9</dd>
           <dt>first</dt><dd><a href=/ordinal/1 class=common>1</a></dd>
+          <dt>block</dt><dd><a href=/block/0>0</a></dd>
         </dl>
       "
       .unindent()
     );
   }
+
+  #[test]
+  fn outputs_and_rare_ordinals() {
+    pretty_assert_eq!(
+      RangeHtml {
+        start: Ordinal(0),
+        end: Ordinal(100 * COIN_VALUE),
+        outputs: vec![
+          (
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0"
+              .parse()
+              .unwrap(),
+            vec![(0, 50 * COIN_VALUE)],
+          ),
+          (
+            Index::lost_outpoint(1),
+            vec![(50 * COIN_VALUE, 50 * COIN_VALUE + 1)],
+          ),
+        ],
+        rare_ordinals: vec![(
+          Ordinal(50 * COIN_VALUE),
+          SatPoint {
+            outpoint: Index::lost_outpoint(1),
+            offset: 0,
+          },
+        )],
+      }
+      .to_string(),
+      "
+        <h1>Ordinal range 0–10000000000</h1>
+        <dl>
+          <dt>value</dt><dd>10000000000</dd>
+          <dt>first</dt><dd><a href=/ordinal/0 class=mythic>0</a></dd>
+          <dt>blocks</dt><dd><a href=/block/0>0</a>–<a href=/block/1>1</a></dd>
+        </dl>
+        <h2>2 Outputs</h2>
+        <ul class=monospace>
+          <li>
+            <a href=/output/4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0</a>
+            <ul>
+              <li><a href=/range/0/5000000000 class=mythic>0–5000000000</a></li>
+            </ul>
+          </li>
+          <li>
+            lost in <a href=/block/1>block 1</a>
+            <ul>
+              <li><a href=/ordinal/5000000000 class=uncommon>5000000000</a></li>
+            </ul>
+          </li>
+        </ul>
+        <h2>1 Rare Ordinal</h2>
+        <ul class=monospace>
+          <li>
+            <a href=/ordinal/5000000000 class=uncommon>5000000000</a>
+            lost in <a href=/block/1>block 1</a>
+          </li>
+        </ul>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn long_lists_are_truncated() {
+    let html = RangeHtml {
+      start: Ordinal(0),
+      end: Ordinal(1000),
+      outputs: (0..=RangeHtml::LIMIT as u64)
+        .map(|i| {
+          (
+            OutPoint::new(Txid::from_inner([1; 32]), i as u32),
+            vec![(i, i + 1)],
+          )
+        })
+        .collect(),
+      rare_ordinals: Vec::new(),
+    }
+    .to_string();
+
+    assert!(html.contains("<h2>100 Outputs</h2>"));
+    assert!(html.contains("<p>More outputs hold pieces of this range.</p>"));
+  }
 }
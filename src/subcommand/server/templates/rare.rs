@@ -2,3 +2,91 @@ use super::*;
 
 #[derive(Boilerplate)]
 pub(crate) struct RareTxt(pub(crate) Vec<(Ordinal, SatPoint)>);
+
+#[derive(Boilerplate)]
+pub(crate) struct RareHtml {
+  pub(crate) ordinals: Vec<(Ordinal, SatPoint, bool)>,
+  pub(crate) prev: Option<String>,
+  pub(crate) next: Option<String>,
+}
+
+impl Content for RareHtml {
+  fn title(&self) -> String {
+    "Rare Ordinals".into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rare_html() {
+    pretty_assert_eq!(
+      RareHtml {
+        ordinals: vec![
+          (
+            Ordinal(0),
+            SatPoint {
+              outpoint: "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0"
+                .parse()
+                .unwrap(),
+              offset: 0,
+            },
+            false
+          ),
+          (
+            Ordinal(50 * COIN_VALUE),
+            SatPoint {
+              outpoint: Index::lost_outpoint(1),
+              offset: 0,
+            },
+            false
+          ),
+          (
+            Ordinal(100 * COIN_VALUE),
+            SatPoint {
+              outpoint: "0c4eb1fa83a7d6ce0e21e5e616a96e83a7b1658170fb544acf6f5c6a2d4b3f90:0"
+                .parse()
+                .unwrap(),
+              offset: 7,
+            },
+            true
+          ),
+        ],
+        prev: None,
+        next: Some("/rare?page=1".into()),
+      }
+      .to_string(),
+      "
+        <h1>Rare Ordinals</h1>
+        <p>
+          <a href=/rare>all</a>
+          <a href=/rare?rarity=uncommon class=uncommon>uncommon</a>
+          <a href=/rare?rarity=rare class=rare>rare</a>
+          <a href=/rare?rarity=epic class=epic>epic</a>
+          <a href=/rare?rarity=legendary class=legendary>legendary</a>
+          <a href=/rare?rarity=mythic class=mythic>mythic</a>
+        </p>
+        <ul class=monospace>
+          <li>
+            <a href=/ordinal/0 class=mythic>0</a>
+            at <a href=/output/4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0</a>
+          </li>
+          <li>
+            <a href=/ordinal/5000000000 class=uncommon>5000000000</a>
+            lost in <a href=/block/1>block 1</a>
+          </li>
+          <li>
+            <a href=/ordinal/10000000000 class=uncommon>10000000000</a>
+            at <a href=/output/0c4eb1fa83a7d6ce0e21e5e616a96e83a7b1658170fb544acf6f5c6a2d4b3f90:0>0c4eb1fa83a7d6ce0e21e5e616a96e83a7b1658170fb544acf6f5c6a2d4b3f90:0:7</a>
+            (spent in mempool)
+          </li>
+        </ul>
+        <a>prev</a>
+        <a href=/rare?page=1>next</a>
+      "
+      .unindent()
+    );
+  }
+}
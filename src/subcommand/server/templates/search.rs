@@ -0,0 +1,76 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct SearchHtml {
+  pub(crate) query: String,
+  pub(crate) results: Vec<(String, String)>,
+  pub(crate) notes: Vec<String>,
+}
+
+impl Content for SearchHtml {
+  fn title(&self) -> String {
+    format!("Search results for {}", self.query)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn search_html() {
+    pretty_assert_eq!(
+      SearchHtml {
+        query: "0".into(),
+        results: vec![
+          ("/block/0".into(), "block 0".into()),
+          ("/ordinal/0".into(), "ordinal 0".into()),
+        ],
+        notes: Vec::new(),
+      }
+      .to_string(),
+      "
+        <h1>Search results for <span class=monospace>0</span></h1>
+        <ul>
+          <li><a href=/block/0>block 0</a></li>
+          <li><a href=/ordinal/0>ordinal 0</a></li>
+        </ul>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn no_results() {
+    pretty_assert_eq!(
+      SearchHtml {
+        query: "foo<".into(),
+        results: Vec::new(),
+        notes: Vec::new(),
+      }
+      .to_string(),
+      "
+        <h1>Search results for <span class=monospace>foo&lt;</span></h1>
+        <p>No results.</p>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn notes() {
+    pretty_assert_eq!(
+      SearchHtml {
+        query: "foo".into(),
+        results: Vec::new(),
+        notes: vec!["bar<".into()],
+      }
+      .to_string(),
+      "
+        <h1>Search results for <span class=monospace>foo</span></h1>
+        <p>bar&lt;</p>
+      "
+      .unindent()
+    );
+  }
+}
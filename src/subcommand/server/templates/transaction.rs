@@ -4,14 +4,16 @@ use super::*;
 pub(crate) struct TransactionHtml {
   txid: Txid,
   transaction: Transaction,
+  flow: TransactionFlow,
   chain: Chain,
 }
 
 impl TransactionHtml {
-  pub(crate) fn new(transaction: Transaction, chain: Chain) -> Self {
+  pub(crate) fn new(transaction: Transaction, flow: TransactionFlow, chain: Chain) -> Self {
     Self {
       txid: transaction.txid(),
       transaction,
+      flow,
       chain,
     }
   }
@@ -49,7 +51,12 @@ mod tests {
     };
 
     pretty_assert_eq!(
-      TransactionHtml::new(transaction, Chain::Mainnet).to_string(),
+      TransactionHtml::new(
+        transaction.clone(),
+        TransactionFlow::new(&transaction, Vec::new(), vec![None, None]),
+        Chain::Mainnet
+      )
+      .to_string(),
       "
         <h1>Transaction <span class=monospace>9108ec7cbe9f1231dbf6374251b7267fb31cb23f36ed5a1d7344f5635b17dfe9</span></h1>
         <h2>2 Outputs</h2>
@@ -73,6 +80,7 @@ mod tests {
             </dl>
           </li>
         </ul>
+        <p>The index only keeps the ordinal ranges of unspent outputs, so where ordinals went is only shown in full while every input is unspent, as for unconfirmed transactions.</p>
       "
       .unindent()
     );
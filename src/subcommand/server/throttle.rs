@@ -0,0 +1,281 @@
+use {
+  super::*,
+  axum::{extract::ConnectInfo, http::Request, middleware::Next},
+  std::{
+    collections::HashMap,
+    future::Future,
+    net::{IpAddr, SocketAddr},
+  },
+  tokio::sync::Semaphore,
+};
+
+/// Limits on how hard clients may use the server: a per-IP token bucket
+/// refilled at `rate_limit` requests per second, and a cap on how many
+/// expensive queries may run at once. Requests relayed by `trusted_proxies`
+/// count against the client named in `X-Forwarded-For`.
+pub(crate) struct Throttle {
+  buckets: Mutex<Buckets>,
+  expensive_queries: Arc<Semaphore>,
+  rate_limit: Option<NonZeroU32>,
+  trusted_proxies: Vec<IpAddr>,
+}
+
+struct Buckets {
+  by_ip: HashMap<IpAddr, Bucket>,
+  pruned: Instant,
+}
+
+struct Bucket {
+  tokens: f64,
+  updated: Instant,
+}
+
+impl Throttle {
+  pub(crate) const DEFAULT_MAX_EXPENSIVE_QUERIES: usize = 4;
+
+  /// Time allowed for requests to routes that only make a few lookups.
+  pub(crate) const TIMEOUT: Duration = Duration::from_secs(10);
+
+  /// Time allowed for requests to routes that may scan the index or look up
+  /// many outputs.
+  pub(crate) const EXPENSIVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+  pub(crate) fn new(
+    rate_limit: Option<NonZeroU32>,
+    max_expensive_queries: usize,
+    trusted_proxies: Vec<IpAddr>,
+  ) -> Self {
+    Self {
+      buckets: Mutex::new(Buckets {
+        by_ip: HashMap::new(),
+        pruned: Instant::now(),
+      }),
+      expensive_queries: Arc::new(Semaphore::new(max_expensive_queries)),
+      rate_limit,
+      trusted_proxies,
+    }
+  }
+
+  /// The address of the client behind a request from `peer`. While the
+  /// request came from a trusted proxy, the address that proxy appended to
+  /// `forwarded_for` is taken instead, so that clients can't pick their own
+  /// address by sending the header themselves.
+  fn client_ip(&self, peer: IpAddr, forwarded_for: &str) -> IpAddr {
+    let mut client = peer;
+
+    for hop in forwarded_for.rsplit(',') {
+      if !self.trusted_proxies.contains(&client) {
+        break;
+      }
+
+      match hop.trim().parse() {
+        Ok(ip) => client = ip,
+        Err(_) => break,
+      }
+    }
+
+    client
+  }
+
+  /// Take a token from the bucket of `ip`, or return how long until one is
+  /// available. Buckets hold at most one second's worth of tokens.
+  fn take_token(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+    let rate_limit = match self.rate_limit {
+      Some(rate_limit) => f64::from(rate_limit.get()),
+      None => return Ok(()),
+    };
+
+    let mut buckets = self.buckets.lock().unwrap();
+
+    // Buckets untouched for a second have refilled, which is the same as
+    // missing, so forget them, but only once a second to keep this cheap
+    if now.saturating_duration_since(buckets.pruned) >= Duration::from_secs(1) {
+      buckets
+        .by_ip
+        .retain(|_, bucket| now.saturating_duration_since(bucket.updated) < Duration::from_secs(1));
+      buckets.pruned = now;
+    }
+
+    let bucket = buckets.by_ip.entry(ip).or_insert(Bucket {
+      tokens: rate_limit,
+      updated: now,
+    });
+
+    bucket.tokens = (bucket.tokens
+      + now.saturating_duration_since(bucket.updated).as_secs_f64() * rate_limit)
+      .min(rate_limit);
+    bucket.updated = now;
+
+    if bucket.tokens >= 1.0 {
+      bucket.tokens -= 1.0;
+      Ok(())
+    } else {
+      Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate_limit))
+    }
+  }
+}
+
+fn throttle<B>(request: &Request<B>) -> ServerResult<Arc<Throttle>> {
+  request
+    .extensions()
+    .get::<Arc<Throttle>>()
+    .cloned()
+    .ok_or_else(|| ServerError::Internal(anyhow!("throttle extension missing")))
+}
+
+/// Reject requests from clients that have used up their tokens.
+pub(crate) async fn rate_limit<B>(request: Request<B>, next: Next<B>) -> ServerResult<Response> {
+  if let Some(ConnectInfo(address)) = request.extensions().get::<ConnectInfo<SocketAddr>>() {
+    let throttle = throttle(&request)?;
+
+    let forwarded_for = request
+      .headers()
+      .get_all("x-forwarded-for")
+      .iter()
+      .filter_map(|value| value.to_str().ok())
+      .collect::<Vec<&str>>()
+      .join(",");
+
+    throttle
+      .take_token(
+        throttle.client_ip(address.ip(), &forwarded_for),
+        Instant::now(),
+      )
+      .map_err(ServerError::TooManyRequests)?;
+  }
+
+  Ok(next.run(request).await)
+}
+
+/// Run `response` on its own task, so that a handler blocked on the index or
+/// on Bitcoin Core can't hold up the response past `timeout`.
+async fn spawn_with_timeout(
+  response: impl Future<Output = Response> + Send + 'static,
+  timeout: Duration,
+) -> ServerResult<Response> {
+  match tokio::time::timeout(timeout, task::spawn(response)).await {
+    Ok(response) => response.map_err(|err| ServerError::Internal(err.into())),
+    Err(_) => Err(ServerError::Timeout),
+  }
+}
+
+pub(crate) async fn timeout<B: Send + 'static>(
+  request: Request<B>,
+  next: Next<B>,
+) -> ServerResult<Response> {
+  spawn_with_timeout(next.run(request), Throttle::TIMEOUT).await
+}
+
+/// Run an expensive query if fewer than the maximum are already running. The
+/// permit is held until the handler finishes, even if the request times out.
+pub(crate) async fn expensive<B: Send + 'static>(
+  request: Request<B>,
+  next: Next<B>,
+) -> ServerResult<Response> {
+  let permit = throttle(&request)?
+    .expensive_queries
+    .clone()
+    .try_acquire_owned()
+    .map_err(|_| ServerError::TooManyRequests(Duration::from_secs(1)))?;
+
+  spawn_with_timeout(
+    async move {
+      let response = next.run(request).await;
+      drop(permit);
+      response
+    },
+    Throttle::EXPENSIVE_TIMEOUT,
+  )
+  .await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unlimited() {
+    let throttle = Throttle::new(None, 1, Vec::new());
+    let now = Instant::now();
+    for _ in 0..100 {
+      assert_eq!(throttle.take_token([127, 0, 0, 1].into(), now), Ok(()));
+    }
+  }
+
+  #[test]
+  fn bucket_empties_and_refills() {
+    let throttle = Throttle::new(NonZeroU32::new(2), 1, Vec::new());
+    let ip = [127, 0, 0, 1].into();
+    let now = Instant::now();
+
+    assert_eq!(throttle.take_token(ip, now), Ok(()));
+    assert_eq!(throttle.take_token(ip, now), Ok(()));
+    assert_eq!(
+      throttle.take_token(ip, now),
+      Err(Duration::from_millis(500))
+    );
+
+    assert_eq!(
+      throttle.take_token(ip, now + Duration::from_millis(500)),
+      Ok(())
+    );
+  }
+
+  #[test]
+  fn buckets_are_per_ip() {
+    let throttle = Throttle::new(NonZeroU32::new(1), 1, Vec::new());
+    let now = Instant::now();
+
+    assert_eq!(throttle.take_token([127, 0, 0, 1].into(), now), Ok(()));
+    assert!(throttle.take_token([127, 0, 0, 1].into(), now).is_err());
+    assert_eq!(throttle.take_token([127, 0, 0, 2].into(), now), Ok(()));
+  }
+
+  #[test]
+  fn full_buckets_are_forgotten() {
+    let throttle = Throttle::new(NonZeroU32::new(1), 1, Vec::new());
+    let now = Instant::now();
+
+    throttle.take_token([127, 0, 0, 1].into(), now).unwrap();
+    throttle
+      .take_token([127, 0, 0, 2].into(), now + Duration::from_secs(1))
+      .unwrap();
+
+    assert_eq!(throttle.buckets.lock().unwrap().by_ip.len(), 1);
+  }
+
+  #[test]
+  fn buckets_are_pruned_at_most_once_a_second() {
+    let throttle = Throttle::new(NonZeroU32::new(1), 1, Vec::new());
+    let now = Instant::now();
+
+    for (ip, offset) in [(1, 500), (2, 1000), (3, 1600)] {
+      throttle
+        .take_token([127, 0, 0, ip].into(), now + Duration::from_millis(offset))
+        .unwrap();
+    }
+
+    assert_eq!(throttle.buckets.lock().unwrap().by_ip.len(), 3);
+  }
+
+  #[test]
+  fn forwarded_for_is_only_trusted_from_trusted_proxies() {
+    let throttle = Throttle::new(None, 1, vec![[10, 0, 0, 1].into(), [10, 0, 0, 2].into()]);
+
+    let client = [1, 2, 3, 4].into();
+
+    assert_eq!(throttle.client_ip(client, "5.6.7.8"), client);
+    assert_eq!(
+      throttle.client_ip([10, 0, 0, 1].into(), "5.6.7.8, 1.2.3.4"),
+      client
+    );
+    assert_eq!(
+      throttle.client_ip([10, 0, 0, 1].into(), "5.6.7.8, 1.2.3.4, 10.0.0.2"),
+      client
+    );
+    assert_eq!(
+      throttle.client_ip([10, 0, 0, 1].into(), ""),
+      IpAddr::from([10, 0, 0, 1])
+    );
+  }
+}
@@ -1,6 +1,34 @@
 use super::*;
 
-pub(crate) fn run() -> Result {
+#[derive(Serialize)]
+struct Supply {
+  supply: u64,
+  first: u64,
+  last: u64,
+  last_mined_in_block: u64,
+}
+
+impl Output for Supply {
+  fn tsv(&self) -> String {
+    fields_tsv(&[
+      ("supply", self.supply.to_string()),
+      ("first", self.first.to_string()),
+      ("last", self.last.to_string()),
+      ("last_mined_in_block", self.last_mined_in_block.to_string()),
+    ])
+  }
+
+  fn text(&self) -> String {
+    fields_text(&[
+      ("supply", self.supply.to_string()),
+      ("first", self.first.to_string()),
+      ("last", self.last.to_string()),
+      ("last mined in block", self.last_mined_in_block.to_string()),
+    ])
+  }
+}
+
+pub(crate) fn run(options: Options) -> Result {
   let mut last = 0;
 
   loop {
@@ -10,10 +38,10 @@ pub(crate) fn run() -> Result {
     last += 1;
   }
 
-  println!("supply: {}", Ordinal::SUPPLY);
-  println!("first: {}", 0);
-  println!("last: {}", Ordinal::SUPPLY - 1);
-  println!("last mined in block: {}", last);
-
-  Ok(())
+  options.format().print(&Supply {
+    supply: Ordinal::SUPPLY,
+    first: 0,
+    last: Ordinal::SUPPLY - 1,
+    last_mined_in_block: last,
+  })
 }
@@ -6,26 +6,71 @@ pub(crate) struct Traits {
   ordinal: Ordinal,
 }
 
+#[derive(Serialize)]
+struct TraitsOutput {
+  number: u64,
+  decimal: String,
+  degree: String,
+  name: String,
+  height: u64,
+  cycle: u64,
+  epoch: u64,
+  period: u64,
+  offset: u64,
+  rarity: Rarity,
+}
+
+impl TraitsOutput {
+  fn fields(&self) -> [(&str, String); 10] {
+    [
+      ("number", self.number.to_string()),
+      ("decimal", self.decimal.clone()),
+      ("degree", self.degree.clone()),
+      ("name", self.name.clone()),
+      ("height", self.height.to_string()),
+      ("cycle", self.cycle.to_string()),
+      ("epoch", self.epoch.to_string()),
+      ("period", self.period.to_string()),
+      ("offset", self.offset.to_string()),
+      ("rarity", self.rarity.to_string()),
+    ]
+  }
+}
+
+impl Output for TraitsOutput {
+  fn tsv(&self) -> String {
+    fields_tsv(&self.fields())
+  }
+
+  fn text(&self) -> String {
+    fields_text(&self.fields())
+  }
+}
+
 impl Traits {
-  pub(crate) fn run(self) -> Result {
-    print!("{}", self);
-    Ok(())
+  pub(crate) fn run(self, options: Options) -> Result {
+    options.format().print(&self.output())
+  }
+
+  fn output(&self) -> TraitsOutput {
+    TraitsOutput {
+      number: self.ordinal.n(),
+      decimal: self.ordinal.decimal().to_string(),
+      degree: self.ordinal.degree().to_string(),
+      name: self.ordinal.name(),
+      height: self.ordinal.height().n(),
+      cycle: self.ordinal.cycle(),
+      epoch: self.ordinal.epoch().0,
+      period: self.ordinal.period(),
+      offset: self.ordinal.third(),
+      rarity: self.ordinal.rarity(),
+    }
   }
 }
 
 impl Display for Traits {
   fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-    writeln!(f, "number: {}", self.ordinal.n())?;
-    writeln!(f, "decimal: {}", self.ordinal.decimal())?;
-    writeln!(f, "degree: {}", self.ordinal.degree())?;
-    writeln!(f, "name: {}", self.ordinal.name())?;
-    writeln!(f, "height: {}", self.ordinal.height())?;
-    writeln!(f, "cycle: {}", self.ordinal.cycle())?;
-    writeln!(f, "epoch: {}", self.ordinal.epoch())?;
-    writeln!(f, "period: {}", self.ordinal.period())?;
-    writeln!(f, "offset: {}", self.ordinal.third())?;
-    writeln!(f, "rarity: {}", self.ordinal.rarity())?;
-    Ok(())
+    f.write_str(&self.output().text())
   }
 }
 
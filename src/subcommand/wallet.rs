@@ -1,40 +1,311 @@
-use super::*;
+use {
+  super::*,
+  crate::index::assign_ordinal_ranges,
+  backend::Backend,
+  bitcoincore_rpc::Client,
+  std::collections::{BTreeMap, BTreeSet},
+};
 
+mod backend;
+mod bump;
+mod consolidate;
+mod cpfp;
+mod create;
+mod extract;
+mod history;
 mod identify;
+mod keychain;
 mod list;
+mod lock_rare;
+mod receive;
+mod restore;
 mod send;
 mod transaction_builder;
+mod unlock;
 
-fn list_unspent(options: &Options, index: &Index) -> Result<Vec<(OutPoint, Vec<(u64, u64)>)>> {
-  let client = options.bitcoin_rpc_client()?;
-
-  client
-    .list_unspent(None, None, None, None, None)?
-    .iter()
-    .map(|utxo| {
-      let outpoint = OutPoint::new(utxo.txid, utxo.vout);
-      match index.list(outpoint)? {
-        Some(List::Unspent(ordinal_ranges)) => Ok((outpoint, ordinal_ranges)),
-        Some(List::Spent) => bail!("output {outpoint} in wallet but is spent according to index"),
-        None => bail!("ordinals index has not seen {outpoint}"),
-      }
+/// Whether an output was received at an address handed out by `ord wallet
+/// receive`, or is change, or was received at an address not handed out by
+/// ord, which is treated the same as change.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Purpose {
+  Receive,
+  Change,
+}
+
+impl Purpose {
+  fn of(receive: &BTreeSet<OutPoint>, outpoint: OutPoint) -> Self {
+    if receive.contains(&outpoint) {
+      Self::Receive
+    } else {
+      Self::Change
+    }
+  }
+}
+
+impl Display for Purpose {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Receive => write!(f, "receive"),
+      Self::Change => write!(f, "change"),
+    }
+  }
+}
+
+#[derive(Deserialize)]
+struct JsonOutPoint {
+  txid: Txid,
+  vout: u32,
+}
+
+/// Bitcoin Core omits locked outputs from `listunspent`, so they must be
+/// listed separately to be visible to ordinal-aware commands.
+fn list_locked(client: &Client) -> Result<BTreeSet<OutPoint>> {
+  Ok(
+    client
+      .call::<Vec<JsonOutPoint>>("listlockunspent", &[])?
+      .into_iter()
+      .map(|JsonOutPoint { txid, vout }| OutPoint::new(txid, vout))
+      .collect(),
+  )
+}
+
+fn locked_outputs_path(options: &Options) -> Result<PathBuf> {
+  Ok(options.data_dir()?.join("locked-outputs.txt"))
+}
+
+fn load_locked_outputs(options: &Options) -> Result<BTreeSet<OutPoint>> {
+  let path = locked_outputs_path(options)?;
+
+  let contents = match fs::read_to_string(&path) {
+    Ok(contents) => contents,
+    Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(BTreeSet::new()),
+    Err(err) => return Err(err).with_context(|| format!("I/O error reading `{}`", path.display())),
+  };
+
+  contents
+    .lines()
+    .filter(|line| !line.is_empty())
+    .map(|line| {
+      line
+        .parse()
+        .with_context(|| format!("failed to parse outpoint from `{}`: {line}", path.display()))
     })
     .collect()
 }
 
+fn save_locked_outputs(options: &Options, outpoints: &BTreeSet<OutPoint>) -> Result {
+  let path = locked_outputs_path(options)?;
+
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)
+      .with_context(|| format!("failed to create data dir `{}`", parent.display()))?;
+  }
+
+  let mut contents = String::new();
+  for outpoint in outpoints {
+    contents.push_str(&format!("{outpoint}\n"));
+  }
+
+  fs::write(&path, contents).with_context(|| format!("I/O error writing `{}`", path.display()))
+}
+
+/// If `ord wallet lock-rare` has been used, drop locks on outputs that are no
+/// longer unspent, lock outputs of the new transaction that received rare
+/// ordinals, except for those sent to `recipient`, and re-apply any locks that
+/// Bitcoin Core has forgotten.
+fn sync_locks(
+  options: &Options,
+  backend: &Backend,
+  utxos: &BTreeMap<OutPoint, Vec<(u64, u64)>>,
+  transaction: &Transaction,
+  txid: Txid,
+  recipient: Option<&Address>,
+) -> Result {
+  let mut persisted = load_locked_outputs(options)?;
+
+  if persisted.is_empty() {
+    return Ok(());
+  }
+
+  persisted.retain(|outpoint| {
+    utxos.contains_key(outpoint)
+      && !transaction
+        .input
+        .iter()
+        .any(|tx_in| tx_in.previous_output == *outpoint)
+  });
+
+  persisted.extend(
+    rare_outputs(transaction, utxos, recipient)
+      .into_iter()
+      .map(|vout| OutPoint::new(txid, vout)),
+  );
+
+  let locked = backend.list_locked(options)?;
+
+  let unlocked = persisted
+    .difference(&locked)
+    .cloned()
+    .collect::<Vec<OutPoint>>();
+
+  if !unlocked.is_empty() && !backend.lock_unspent(&unlocked)? {
+    bail!("failed to lock outputs containing rare ordinals");
+  }
+
+  save_locked_outputs(options, &persisted)
+}
+
+fn rare_outputs(
+  transaction: &Transaction,
+  utxos: &BTreeMap<OutPoint, Vec<(u64, u64)>>,
+  recipient: Option<&Address>,
+) -> Vec<u32> {
+  let mut offset = 0;
+  let mut rare_offsets = Vec::new();
+  for tx_in in &transaction.input {
+    for (start, end) in &utxos[&tx_in.previous_output] {
+      if Ordinal(*start).rarity() > Rarity::Common {
+        rare_offsets.push(offset);
+      }
+      offset += end - start;
+    }
+  }
+
+  let recipient = recipient.map(Address::script_pubkey);
+
+  let mut start = 0;
+  let mut vouts = Vec::new();
+  for (vout, tx_out) in transaction.output.iter().enumerate() {
+    let end = start + tx_out.value;
+    if Some(&tx_out.script_pubkey) != recipient.as_ref()
+      && rare_offsets
+        .iter()
+        .any(|offset| *offset >= start && *offset < end)
+    {
+      vouts.push(vout as u32);
+    }
+    start = end;
+  }
+
+  vouts
+}
+
+/// The index only knows the ranges of confirmed outputs, but those of an
+/// unconfirmed transaction can be derived from the ranges of its inputs, as
+/// long as they have not been spent by other unconfirmed transactions.
+fn unconfirmed_output_ranges(index: &Index, txid: Txid) -> Result<Option<Vec<Vec<(u64, u64)>>>> {
+  let transaction = match index.transaction(txid)? {
+    Some(transaction) => transaction,
+    None => return Ok(None),
+  };
+
+  let mut inputs = VecDeque::new();
+  for tx_in in &transaction.input {
+    match index.list(tx_in.previous_output)? {
+      Some(List::Unspent(ranges)) => inputs.extend(ranges),
+      _ => return Ok(None),
+    }
+  }
+
+  Ok(Some(
+    assign_ordinal_ranges(inputs, transaction.output.iter().map(|tx_out| tx_out.value)).0,
+  ))
+}
+
 #[derive(Debug, Parser)]
 pub(crate) enum Wallet {
+  Bump(bump::Bump),
+  Consolidate(consolidate::Consolidate),
+  Cpfp(cpfp::Cpfp),
+  Create(create::Create),
+  Extract,
+  History,
   Identify(identify::Identify),
   List,
+  LockRare,
+  Receive,
+  Restore(restore::Restore),
   Send(send::Send),
+  Unlock(unlock::Unlock),
 }
 
 impl Wallet {
   pub(crate) fn run(self, options: Options) -> Result {
     match self {
+      Self::Bump(bump) => bump.run(options),
+      Self::Consolidate(consolidate) => consolidate.run(options),
+      Self::Cpfp(cpfp) => cpfp.run(options),
+      Self::Create(create) => create.run(options),
+      Self::Extract => extract::run(options),
+      Self::History => history::run(options),
       Self::Identify(identify) => identify.run(options),
       Self::List => list::run(options),
+      Self::LockRare => lock_rare::run(options),
+      Self::Receive => receive::run(options),
+      Self::Restore(restore) => restore.run(options),
       Self::Send(send) => send.run(options),
+      Self::Unlock(unlock) => unlock.run(options),
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    bitcoin::{blockdata::witness::Witness, PackedLockTime},
+  };
+
+  #[test]
+  fn rare_outputs_excludes_recipient() {
+    let recipient = "tb1q6en7qjxgw4ev8xwx94pzdry6a6ky7wlfeqzunz"
+      .parse::<Address>()
+      .unwrap();
+
+    let change = "tb1qjsv26lap3ffssj6hfy8mzn0lg5vte6a42j75ww"
+      .parse::<Address>()
+      .unwrap();
+
+    let utxos = [
+      (outpoint(1), vec![(10, 20), (0, 10)]),
+      (outpoint(2), vec![(50 * COIN_VALUE, 50 * COIN_VALUE + 10)]),
+    ]
+    .into_iter()
+    .collect();
+
+    let transaction = Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: [outpoint(1), outpoint(2)]
+        .into_iter()
+        .map(|previous_output| TxIn {
+          previous_output,
+          script_sig: Script::new(),
+          sequence: Sequence::MAX,
+          witness: Witness::new(),
+        })
+        .collect(),
+      output: vec![
+        TxOut {
+          value: 10,
+          script_pubkey: recipient.script_pubkey(),
+        },
+        TxOut {
+          value: 15,
+          script_pubkey: change.script_pubkey(),
+        },
+        TxOut {
+          value: 5,
+          script_pubkey: change.script_pubkey(),
+        },
+      ],
+    };
+
+    assert_eq!(
+      rare_outputs(&transaction, &utxos, Some(&recipient)),
+      vec![1]
+    );
+    assert_eq!(rare_outputs(&transaction, &utxos, None), vec![0, 1]);
+  }
+}
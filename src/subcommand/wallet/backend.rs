@@ -0,0 +1,384 @@
+use {super::*, keychain::Keychain, std::collections::HashSet};
+
+/// Bitcoin Core label of addresses handed out by `ord wallet receive`.
+const RECEIVE_LABEL: &str = "ord-receive";
+
+/// Coinbase outputs can be spent once they have this many confirmations.
+const COINBASE_MATURITY: u64 = 100;
+
+#[derive(Deserialize)]
+struct JsonTransaction {
+  txid: Txid,
+  vout: u32,
+  category: String,
+}
+
+enum Kind {
+  Core(Client),
+  Keychain { client: Client, keychain: Keychain },
+}
+
+/// Wallet commands use either Bitcoin Core's wallet or, once `ord wallet
+/// create` has been run, ord's own keychain, in which case Bitcoin Core is
+/// only used to look up and broadcast transactions.
+pub(crate) struct Backend {
+  kind: Kind,
+  policy: WalletPolicy,
+}
+
+impl Backend {
+  pub(crate) fn new(options: &Options) -> Result<Self> {
+    Ok(Self {
+      kind: match Keychain::load(options)? {
+        Some(keychain) => Kind::Keychain {
+          client: options.bitcoin_rpc_client()?,
+          keychain,
+        },
+        None => Kind::Core(options.bitcoin_rpc_client()?),
+      },
+      policy: options.wallet_policy(),
+    })
+  }
+
+  /// Open the wallet for `command`, which may spend from it, enforcing the
+  /// wallet policy for both kinds of wallet. Keychain wallets have no name,
+  /// so only Bitcoin Core wallets are subject to the wallet name policy.
+  pub(crate) fn for_command(options: &Options, index: &Index, command: &str) -> Result<Self> {
+    let policy = options.wallet_policy();
+
+    let keychain = match Keychain::load(options)? {
+      Some(keychain) => keychain,
+      None => {
+        return Ok(Self {
+          kind: Kind::Core(options.bitcoin_rpc_client_for_wallet_command(command)?),
+          policy,
+        })
+      }
+    };
+
+    let backend = Self {
+      kind: Kind::Keychain {
+        client: options.bitcoin_rpc_client()?,
+        keychain,
+      },
+      policy,
+    };
+
+    if backend.policy.max_balance.is_some() {
+      let total = backend
+        .list_unspent(index)?
+        .iter()
+        .flat_map(|(_outpoint, ranges)| ranges)
+        .map(|(start, end)| end - start)
+        .sum::<u64>();
+
+      backend.policy.check_balance(command, total)?;
+    }
+
+    Ok(backend)
+  }
+
+  pub(crate) fn client(&self) -> &Client {
+    match &self.kind {
+      Kind::Core(client) | Kind::Keychain { client, .. } => client,
+    }
+  }
+
+  /// Unspent outputs in the wallet, including locked outputs, along with
+  /// their ordinal ranges.
+  pub(crate) fn list_unspent(&self, index: &Index) -> Result<Vec<(OutPoint, Vec<(u64, u64)>)>> {
+    let outpoints = match &self.kind {
+      Kind::Core(client) => client
+        .list_unspent(None, None, None, None, None)?
+        .iter()
+        .map(|utxo| OutPoint::new(utxo.txid, utxo.vout))
+        .chain(list_locked(client)?)
+        .collect::<Vec<OutPoint>>(),
+      Kind::Keychain { client, keychain } => {
+        let script_pubkeys = keychain.script_pubkeys()?;
+
+        let mut outpoints = Vec::new();
+        for (outpoint, script_pubkey) in index.watched_outputs()? {
+          // The index only knows about confirmed spends, so outputs already
+          // spent by a transaction in the mempool are skipped.
+          if script_pubkeys.contains_key(&script_pubkey)
+            && client
+              .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+              .is_some()
+          {
+            outpoints.push(outpoint);
+          }
+        }
+        outpoints
+      }
+    };
+
+    outpoints
+      .into_iter()
+      .map(|outpoint| match index.list(outpoint)? {
+        Some(List::Unspent(ordinal_ranges)) => Ok((outpoint, ordinal_ranges)),
+        Some(List::Spent) => bail!("output {outpoint} in wallet but is spent according to index"),
+        None => bail!("ordinals index has not seen {outpoint}"),
+      })
+      .collect()
+  }
+
+  /// Outputs of the wallet's transactions, oldest first, along with their
+  /// `listtransactions` category. The keychain's transactions are those the
+  /// index has seen pay to or spend from its addresses, which, unlike
+  /// Bitcoin Core's, don't include unconfirmed transactions.
+  pub(crate) fn transactions(&self, index: &Index) -> Result<Vec<(OutPoint, String)>> {
+    let keychain = match &self.kind {
+      Kind::Core(client) => return list_transactions(client),
+      Kind::Keychain { keychain, .. } => keychain,
+    };
+
+    let script_pubkeys = keychain.script_pubkeys()?;
+    let tip = index.height()?.n();
+
+    let mut outputs = HashSet::new();
+    let mut transactions = Vec::new();
+
+    for (height, txid) in index.watched_transactions()? {
+      let transaction = index
+        .transaction(txid)?
+        .ok_or_else(|| anyhow!("transaction {txid} not found"))?;
+
+      let sends = transaction
+        .input
+        .iter()
+        .any(|input| outputs.contains(&input.previous_output));
+
+      for (vout, output) in transaction.output.iter().enumerate() {
+        let outpoint = OutPoint::new(txid, vout as u32);
+
+        let category = if script_pubkeys.contains_key(&output.script_pubkey) {
+          outputs.insert(outpoint);
+
+          if !transaction.is_coin_base() {
+            "receive"
+          } else if tip - height + 1 < COINBASE_MATURITY {
+            "immature"
+          } else {
+            "generate"
+          }
+        } else if sends {
+          "send"
+        } else {
+          continue;
+        };
+
+        transactions.push((outpoint, category.into()));
+      }
+    }
+
+    Ok(transactions)
+  }
+
+  /// Outputs locked against spending. Bitcoin Core tracks its own locks,
+  /// while the keychain's only locks are those saved by `ord wallet
+  /// lock-rare`.
+  pub(crate) fn list_locked(&self, options: &Options) -> Result<BTreeSet<OutPoint>> {
+    match &self.kind {
+      Kind::Core(client) => list_locked(client),
+      Kind::Keychain { .. } => load_locked_outputs(options),
+    }
+  }
+
+  pub(crate) fn lock_unspent(&self, outpoints: &[OutPoint]) -> Result<bool> {
+    match &self.kind {
+      Kind::Core(client) => Ok(client.lock_unspent(outpoints)?),
+      Kind::Keychain { .. } => Ok(true),
+    }
+  }
+
+  pub(crate) fn unlock_unspent(&self, outpoints: &[OutPoint]) -> Result<bool> {
+    match &self.kind {
+      Kind::Core(client) => Ok(client.unlock_unspent(outpoints)?),
+      Kind::Keychain { .. } => Ok(true),
+    }
+  }
+
+  /// Get a fresh address for receiving ordinals, which is never used for
+  /// change. Keychain addresses are registered with the index so that outputs
+  /// paying to them are tracked.
+  pub(crate) fn receive_address(&mut self, index: &Index) -> Result<Address> {
+    match &mut self.kind {
+      Kind::Core(client) => Ok(client.get_new_address(Some(RECEIVE_LABEL), None)?),
+      Kind::Keychain { keychain, .. } => {
+        let address = keychain.next_receive_address()?;
+        index.watch(&address.script_pubkey())?;
+        Ok(address)
+      }
+    }
+  }
+
+  /// Those of `outpoints` that pay to addresses handed out by `ord wallet
+  /// receive`.
+  pub(crate) fn receive_outputs(
+    &self,
+    outpoints: impl IntoIterator<Item = OutPoint>,
+  ) -> Result<BTreeSet<OutPoint>> {
+    let script_pubkeys = match &self.kind {
+      Kind::Core(client) => match client
+        .call::<BTreeMap<String, serde_json::Value>>("getaddressesbylabel", &[RECEIVE_LABEL.into()])
+      {
+        Ok(addresses) => addresses
+          .keys()
+          .map(|address| Ok(Address::from_str(address)?.script_pubkey()))
+          .collect::<Result<HashSet<Script>>>()?,
+        Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
+          bitcoincore_rpc::jsonrpc::error::RpcError { code: -11, .. },
+        ))) => return Ok(BTreeSet::new()),
+        Err(err) => return Err(err.into()),
+      },
+      Kind::Keychain { keychain, .. } => keychain.receive_script_pubkeys()?,
+    };
+
+    if script_pubkeys.is_empty() {
+      return Ok(BTreeSet::new());
+    }
+
+    let mut receive = BTreeSet::new();
+    for outpoint in outpoints {
+      if let Some(tx_out) = self
+        .client()
+        .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+      {
+        if script_pubkeys.contains(&Script::from(tx_out.script_pub_key.hex)) {
+          receive.insert(outpoint);
+        }
+      }
+    }
+
+    Ok(receive)
+  }
+
+  /// Get a fresh change address. Keychain addresses are registered with the
+  /// index so that outputs paying to them are tracked.
+  pub(crate) fn change_address(&mut self, index: &Index) -> Result<Address> {
+    match &mut self.kind {
+      Kind::Core(client) => client
+        .call("getrawchangeaddress", &[])
+        .context("could not get change addresses from wallet"),
+      Kind::Keychain { keychain, .. } => {
+        let address = keychain.next_change_address()?;
+        index.watch(&address.script_pubkey())?;
+        Ok(address)
+      }
+    }
+  }
+
+  pub(crate) fn is_mine(&self, address: &Address) -> Result<bool> {
+    #[derive(Deserialize)]
+    struct JsonAddressInfo {
+      ismine: bool,
+    }
+
+    match &self.kind {
+      Kind::Core(client) => Ok(
+        client
+          .call::<JsonAddressInfo>("getaddressinfo", &[address.to_string().into()])?
+          .ismine,
+      ),
+      Kind::Keychain { keychain, .. } => Ok(
+        keychain
+          .script_pubkeys()?
+          .contains_key(&address.script_pubkey()),
+      ),
+    }
+  }
+
+  /// Sign `transaction`, returning it serialized, if it doesn't spend more
+  /// than the wallet policy allows.
+  pub(crate) fn sign(&self, index: &Index, transaction: &Transaction) -> Result<Vec<u8>> {
+    let prevouts = match (&self.kind, self.policy.max_transaction_value) {
+      (Kind::Core(_), None) => Vec::new(),
+      _ => Self::prevouts(index, transaction)?,
+    };
+
+    self
+      .policy
+      .check_transaction_value(prevouts.iter().map(|tx_out| tx_out.value).sum())?;
+
+    match &self.kind {
+      Kind::Core(client) => {
+        let signed = client.sign_raw_transaction_with_wallet(transaction, None, None)?;
+
+        if !signed.complete {
+          bail!("failed to sign transaction: not all inputs are in wallet");
+        }
+
+        Ok(signed.hex)
+      }
+      Kind::Keychain { keychain, .. } => {
+        let mut transaction = transaction.clone();
+
+        keychain.sign(&mut transaction, &prevouts)?;
+
+        Ok(consensus::encode::serialize(&transaction))
+      }
+    }
+  }
+
+  fn prevouts(index: &Index, transaction: &Transaction) -> Result<Vec<TxOut>> {
+    let mut prevouts = Vec::new();
+    for tx_in in &transaction.input {
+      let OutPoint { txid, vout } = tx_in.previous_output;
+
+      let previous = index
+        .transaction(txid)?
+        .ok_or_else(|| anyhow!("transaction {txid} not found"))?;
+
+      prevouts.push(
+        previous
+          .output
+          .get(vout as usize)
+          .ok_or_else(|| anyhow!("transaction {txid} has no output {vout}"))?
+          .clone(),
+      );
+    }
+    Ok(prevouts)
+  }
+
+  pub(crate) fn send(&self, signed: &[u8]) -> Result<Txid> {
+    Ok(self.client().send_raw_transaction(signed)?)
+  }
+}
+
+/// `listtransactions` returns the most recent transactions first when paging
+/// with `skip`, but each page in chronological order, so pages are fetched
+/// until exhausted and then reversed.
+fn list_transactions(client: &Client) -> Result<Vec<(OutPoint, String)>> {
+  const PAGE_SIZE: usize = 1000;
+
+  let mut pages = Vec::new();
+
+  loop {
+    let page = client.call::<Vec<JsonTransaction>>(
+      "listtransactions",
+      &[
+        "*".into(),
+        PAGE_SIZE.into(),
+        (pages.len() * PAGE_SIZE).into(),
+      ],
+    )?;
+
+    let done = page.len() < PAGE_SIZE;
+
+    pages.push(page);
+
+    if done {
+      break;
+    }
+  }
+
+  Ok(
+    pages
+      .into_iter()
+      .rev()
+      .flatten()
+      .map(|entry| (OutPoint::new(entry.txid, entry.vout), entry.category))
+      .collect(),
+  )
+}
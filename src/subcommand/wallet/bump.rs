@@ -0,0 +1,127 @@
+use {super::*, std::num::NonZeroU64, transaction_builder::TransactionBuilder};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Bump {
+  txid: Txid,
+  #[clap(long, help = "Pay a fee of <FEE_RATE> sats per virtual byte.")]
+  fee_rate: NonZeroU64,
+}
+
+impl Bump {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let mut backend = Backend::for_command(&options, &index, "ord wallet bump")?;
+
+    if index.is_transaction_in_active_chain(self.txid)? {
+      bail!("transaction {} has already been confirmed", self.txid);
+    }
+
+    let original = index
+      .transaction(self.txid)?
+      .ok_or_else(|| anyhow!("transaction {} not found", self.txid))?;
+
+    if !original.is_explicitly_rbf() {
+      bail!(
+        "transaction {} does not signal replace-by-fee and cannot be bumped",
+        self.txid
+      );
+    }
+
+    let mut utxos = backend
+      .list_unspent(&index)?
+      .into_iter()
+      .collect::<BTreeMap<OutPoint, Vec<(u64, u64)>>>();
+
+    let mut inputs = Vec::new();
+    for tx_in in &original.input {
+      match index.list(tx_in.previous_output)? {
+        Some(List::Unspent(ranges)) => {
+          utxos.insert(tx_in.previous_output, ranges.clone());
+          inputs.push(ranges);
+        }
+        _ => bail!(
+          "input {} of transaction {} is not unspent according to index",
+          tx_in.previous_output,
+          self.txid
+        ),
+      }
+    }
+
+    let mut recipients = Vec::new();
+    for (vout, tx_out) in original.output.iter().enumerate() {
+      let address = options
+        .chain()
+        .address_from_script(&tx_out.script_pubkey)
+        .with_context(|| format!("output {vout} of transaction {} has no address", self.txid))?;
+
+      if !backend.is_mine(&address)? {
+        recipients.push((vout, address));
+      }
+    }
+
+    let (vout, recipient) = match recipients.as_slice() {
+      [recipient] => recipient.clone(),
+      _ => bail!(
+        "transaction {} does not have exactly one recipient and was not sent by `ord wallet send`",
+        self.txid
+      ),
+    };
+
+    let ordinal = assign_ordinal_ranges(
+      inputs.iter().flatten().copied().collect(),
+      original.output.iter().map(|tx_out| tx_out.value),
+    )
+    .0[vout]
+      .first()
+      .map(|(start, _end)| Ordinal(*start))
+      .ok_or_else(|| anyhow!("recipient output of transaction {} is empty", self.txid))?;
+
+    let original_fee = inputs
+      .iter()
+      .flatten()
+      .map(|(start, end)| end - start)
+      .sum::<u64>()
+      - original
+        .output
+        .iter()
+        .map(|tx_out| tx_out.value)
+        .sum::<u64>();
+
+    let change = vec![
+      backend.change_address(&index)?,
+      backend.change_address(&index)?,
+    ];
+
+    let unsigned_transaction = TransactionBuilder::build_replacement(
+      utxos.clone(),
+      original
+        .input
+        .iter()
+        .map(|tx_in| tx_in.previous_output)
+        .collect(),
+      ordinal,
+      recipient.clone(),
+      change,
+      Amount::from_sat(self.fee_rate.get()),
+      Amount::from_sat(original_fee),
+    )?;
+
+    let signed_tx = backend.sign(&index, &unsigned_transaction)?;
+
+    let txid = backend.send(&signed_tx)?;
+
+    sync_locks(
+      &options,
+      &backend,
+      &utxos,
+      &unsigned_transaction,
+      txid,
+      Some(&recipient),
+    )?;
+
+    println!("{txid}");
+    Ok(())
+  }
+}
@@ -0,0 +1,283 @@
+use {
+  super::*,
+  bitcoin::blockdata::{locktime::PackedLockTime, witness::Witness},
+  transaction_builder::{Error, TransactionBuilder},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Consolidate {
+  #[clap(
+    long,
+    default_value = "1",
+    help = "Pay a fee of <FEE_RATE> sats per virtual byte."
+  )]
+  fee_rate: u64,
+}
+
+impl Consolidate {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let mut backend = Backend::for_command(&options, &index, "ord wallet consolidate")?;
+
+    let locked = backend.list_locked(&options)?;
+
+    let utxos = backend
+      .list_unspent(&index)?
+      .into_iter()
+      .filter(|(outpoint, _ranges)| !locked.contains(outpoint))
+      .collect::<BTreeMap<OutPoint, Vec<(u64, u64)>>>();
+
+    let cardinal = utxos
+      .iter()
+      .filter(|(_outpoint, ranges)| is_cardinal(ranges))
+      .count();
+
+    if cardinal < 2 {
+      bail!("wallet contains fewer than two cardinal outputs to consolidate");
+    }
+
+    let change = backend.change_address(&index)?;
+
+    let unsigned_transaction =
+      build_consolidation(&utxos, change, Amount::from_sat(self.fee_rate))?;
+
+    let signed_tx = backend.sign(&index, &unsigned_transaction)?;
+
+    let txid = backend.send(&signed_tx)?;
+
+    sync_locks(
+      &options,
+      &backend,
+      &utxos,
+      &unsigned_transaction,
+      txid,
+      None,
+    )?;
+
+    println!("{txid}");
+    Ok(())
+  }
+}
+
+fn is_cardinal(ranges: &[(u64, u64)]) -> bool {
+  ranges
+    .iter()
+    .all(|(start, _end)| Ordinal(*start).rarity() == Rarity::Common)
+}
+
+/// Build a transaction spending every cardinal output in `utxos` to a single
+/// output paying to `change`, paying `fee_rate` sats per virtual byte.
+fn build_consolidation(
+  utxos: &BTreeMap<OutPoint, Vec<(u64, u64)>>,
+  change: Address,
+  fee_rate: Amount,
+) -> std::result::Result<Transaction, Error> {
+  let inputs = utxos
+    .iter()
+    .filter(|(_outpoint, ranges)| is_cardinal(ranges))
+    .map(|(outpoint, _ranges)| *outpoint)
+    .collect::<Vec<OutPoint>>();
+
+  let total = inputs
+    .iter()
+    .flat_map(|outpoint| &utxos[outpoint])
+    .map(|(start, end)| Amount::from_sat(end - start))
+    .sum::<Amount>();
+
+  let fee = fee_rate
+    * TransactionBuilder::estimate_vsize_of(inputs.len(), &[(change.clone(), total)])
+      .try_into()
+      .unwrap();
+
+  let dust_limit = change.script_pubkey().dust_value();
+
+  if total < fee + dust_limit {
+    return Err(Error::NotEnoughCardinalUtxos);
+  }
+
+  let transaction = Transaction {
+    version: 1,
+    lock_time: PackedLockTime::ZERO,
+    input: inputs
+      .iter()
+      .map(|outpoint| TxIn {
+        previous_output: *outpoint,
+        script_sig: Script::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      })
+      .collect(),
+    output: vec![TxOut {
+      value: (total - fee).to_sat(),
+      script_pubkey: change.script_pubkey(),
+    }],
+  };
+
+  check_no_rare_ordinals(&transaction, utxos)?;
+
+  Ok(transaction)
+}
+
+/// Consolidation must never spend a rare ordinal, even into one of our own
+/// outputs, so any rare ordinal in the inputs is an error.
+fn check_no_rare_ordinals(
+  transaction: &Transaction,
+  utxos: &BTreeMap<OutPoint, Vec<(u64, u64)>>,
+) -> std::result::Result<(), Error> {
+  let output_value = transaction
+    .output
+    .iter()
+    .map(|tx_out| tx_out.value)
+    .sum::<u64>();
+
+  let mut offset = 0;
+  for tx_in in &transaction.input {
+    for (start, end) in &utxos[&tx_in.previous_output] {
+      if Ordinal(*start).rarity() > Rarity::Common {
+        if offset < output_value {
+          return Err(Error::RareOrdinalLostToRecipient(Ordinal(*start)));
+        } else {
+          return Err(Error::RareOrdinalLostToFee(Ordinal(*start)));
+        }
+      }
+      offset += end - start;
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::Error, super::*};
+
+  #[test]
+  fn cardinal_outputs_are_consolidated() {
+    let utxos = vec![
+      (outpoint(1), vec![(10_000, 15_000)]),
+      (outpoint(2), vec![(20_000, 25_000)]),
+    ]
+    .into_iter()
+    .collect();
+
+    pretty_assert_eq!(
+      build_consolidation(&utxos, change(0), Amount::from_sat(1)),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+        output: vec![TxOut {
+          value: 10_000 - 399,
+          script_pubkey: change(0).script_pubkey(),
+        }],
+      })
+    )
+  }
+
+  #[test]
+  fn rare_outputs_are_not_consolidated() {
+    let utxos = vec![
+      (outpoint(1), vec![(10_000, 15_000)]),
+      (outpoint(2), vec![(0, 5_000)]),
+      (
+        outpoint(3),
+        vec![(20_000, 25_000), (50 * COIN_VALUE, 50 * COIN_VALUE + 1)],
+      ),
+      (outpoint(4), vec![(30_000, 35_000)]),
+    ]
+    .into_iter()
+    .collect();
+
+    pretty_assert_eq!(
+      build_consolidation(&utxos, change(0), Amount::from_sat(1))
+        .unwrap()
+        .input,
+      vec![tx_in(outpoint(1)), tx_in(outpoint(4))],
+    )
+  }
+
+  #[test]
+  fn fee_rate_is_applied() {
+    let utxos = vec![
+      (outpoint(1), vec![(10_000, 15_000)]),
+      (outpoint(2), vec![(20_000, 25_000)]),
+    ]
+    .into_iter()
+    .collect();
+
+    pretty_assert_eq!(
+      build_consolidation(&utxos, change(0), Amount::from_sat(5))
+        .unwrap()
+        .output[0]
+        .value,
+      10_000 - 399 * 5,
+    )
+  }
+
+  #[test]
+  fn not_enough_cardinal_value_to_pay_fee() {
+    let utxos = vec![
+      (outpoint(1), vec![(10_000, 10_300)]),
+      (outpoint(2), vec![(20_000, 20_300)]),
+    ]
+    .into_iter()
+    .collect();
+
+    pretty_assert_eq!(
+      build_consolidation(&utxos, change(0), Amount::from_sat(1)),
+      Err(Error::NotEnoughCardinalUtxos),
+    )
+  }
+
+  #[test]
+  fn rare_ordinals_are_not_lost_to_recipient() {
+    let utxos = vec![
+      (outpoint(1), vec![(10_000, 15_000)]),
+      (outpoint(2), vec![(0, 5_000)]),
+    ]
+    .into_iter()
+    .collect();
+
+    let transaction = Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+      output: vec![TxOut {
+        value: 9_601,
+        script_pubkey: change(0).script_pubkey(),
+      }],
+    };
+
+    pretty_assert_eq!(
+      check_no_rare_ordinals(&transaction, &utxos),
+      Err(Error::RareOrdinalLostToRecipient(Ordinal(0))),
+    )
+  }
+
+  #[test]
+  fn rare_ordinals_are_not_lost_to_fee() {
+    let utxos = vec![
+      (outpoint(1), vec![(10_000, 15_000)]),
+      (outpoint(2), vec![(0, 5_000)]),
+    ]
+    .into_iter()
+    .collect();
+
+    let transaction = Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+      output: vec![TxOut {
+        value: 4_601,
+        script_pubkey: change(0).script_pubkey(),
+      }],
+    };
+
+    pretty_assert_eq!(
+      check_no_rare_ordinals(&transaction, &utxos),
+      Err(Error::RareOrdinalLostToFee(Ordinal(0))),
+    )
+  }
+}
@@ -0,0 +1,292 @@
+use {
+  super::*,
+  bitcoin::blockdata::{locktime::PackedLockTime, witness::Witness},
+  transaction_builder::{Error, TransactionBuilder},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Cpfp {
+  outpoint: OutPoint,
+  #[clap(
+    long,
+    help = "Bring the parent and child transactions to <FEE_RATE> sats per virtual byte."
+  )]
+  fee_rate: u64,
+}
+
+impl Cpfp {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let mut backend = Backend::for_command(&options, &index, "ord wallet cpfp")?;
+
+    let txid = self.outpoint.txid;
+
+    if index.is_transaction_in_active_chain(txid)? {
+      bail!("transaction {txid} has already been confirmed");
+    }
+
+    let parent = index
+      .transaction(txid)?
+      .ok_or_else(|| anyhow!("transaction {txid} not found"))?;
+
+    let ranges = unconfirmed_output_ranges(&index, txid)?
+      .ok_or_else(|| anyhow!("inputs of transaction {txid} are not unspent according to index"))?
+      .into_iter()
+      .nth(self.outpoint.vout as usize)
+      .ok_or_else(|| anyhow!("transaction {txid} has no output {}", self.outpoint.vout))?;
+
+    let mut input_value = 0;
+    for tx_in in &parent.input {
+      let previous = index
+        .transaction(tx_in.previous_output.txid)?
+        .ok_or_else(|| anyhow!("transaction {} not found", tx_in.previous_output.txid))?;
+      input_value += previous.output[tx_in.previous_output.vout as usize].value;
+    }
+
+    let parent_fee =
+      Amount::from_sat(input_value - parent.output.iter().map(|tx_out| tx_out.value).sum::<u64>());
+
+    let mut utxos = backend
+      .list_unspent(&index)?
+      .into_iter()
+      .collect::<BTreeMap<OutPoint, Vec<(u64, u64)>>>();
+
+    utxos.insert(self.outpoint, ranges);
+
+    // The parent's output may hold ordinals sent to the wallet, so it goes to
+    // a receive address, which is never spent to pad or pay for transactions.
+    let destination = backend.receive_address(&index)?;
+
+    let change = backend.change_address(&index)?;
+
+    let unsigned_transaction = build_cpfp(
+      &utxos,
+      self.outpoint,
+      destination,
+      change,
+      parent.vsize(),
+      parent_fee,
+      Amount::from_sat(self.fee_rate),
+    )?;
+
+    let signed_tx = backend.sign(&index, &unsigned_transaction)?;
+
+    let txid = backend.send(&signed_tx)?;
+
+    sync_locks(
+      &options,
+      &backend,
+      &utxos,
+      &unsigned_transaction,
+      txid,
+      None,
+    )?;
+
+    println!("{txid}");
+    Ok(())
+  }
+}
+
+/// Build a child transaction that spends `incoming` in its entirety to
+/// `destination`, so the position of every ordinal within it is unchanged,
+/// along with a cardinal input from `utxos` that pays enough of a fee for
+/// the parent and child together to reach `fee_rate`.
+fn build_cpfp(
+  utxos: &BTreeMap<OutPoint, Vec<(u64, u64)>>,
+  incoming: OutPoint,
+  destination: Address,
+  change: Address,
+  parent_vsize: usize,
+  parent_fee: Amount,
+  fee_rate: Amount,
+) -> std::result::Result<Transaction, Error> {
+  let incoming_value = Amount::from_sat(
+    utxos[&incoming]
+      .iter()
+      .map(|(start, end)| end - start)
+      .sum(),
+  );
+
+  let mut outputs = vec![(destination, incoming_value), (change, Amount::ZERO)];
+
+  let vsize = TransactionBuilder::estimate_vsize_of(2, &outputs);
+
+  let child_fee = fee_rate * vsize.try_into().unwrap();
+
+  let fee = (fee_rate * (parent_vsize + vsize).try_into().unwrap())
+    .checked_sub(parent_fee)
+    .unwrap_or(Amount::ZERO)
+    .max(child_fee);
+
+  let mut cardinal_utxos = utxos.keys().cloned().collect::<BTreeSet<OutPoint>>();
+  cardinal_utxos.remove(&incoming);
+
+  let (cardinal, amount) = TransactionBuilder::select_cardinal_utxo_from(
+    &mut cardinal_utxos,
+    utxos,
+    fee + outputs[1].0.script_pubkey().dust_value(),
+  )?;
+
+  outputs[1].1 = amount - fee;
+
+  let transaction = Transaction {
+    version: 1,
+    lock_time: PackedLockTime::ZERO,
+    input: [incoming, cardinal]
+      .into_iter()
+      .map(|outpoint| TxIn {
+        previous_output: outpoint,
+        script_sig: Script::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      })
+      .collect(),
+    output: outputs
+      .iter()
+      .map(|(address, amount)| TxOut {
+        value: amount.to_sat(),
+        script_pubkey: address.script_pubkey(),
+      })
+      .collect(),
+  };
+
+  assert_eq!(
+    transaction.output[0].value,
+    incoming_value.to_sat(),
+    "invariant: incoming output is kept intact"
+  );
+
+  let total_output_amount = transaction
+    .output
+    .iter()
+    .map(|tx_out| tx_out.value)
+    .sum::<u64>();
+
+  let mut offset = 0;
+  for tx_in in &transaction.input {
+    for (start, end) in &utxos[&tx_in.previous_output] {
+      if Ordinal(*start).rarity() > Rarity::Common && offset >= total_output_amount {
+        return Err(Error::RareOrdinalLostToFee(Ordinal(*start)));
+      }
+      offset += end - start;
+    }
+  }
+
+  Ok(transaction)
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::Error, super::*};
+
+  #[test]
+  fn child_pays_for_parent() {
+    let utxos = vec![
+      (outpoint(1), vec![(0, 10_000)]),
+      (outpoint(2), vec![(20_000, 50_000)]),
+    ]
+    .into_iter()
+    .collect();
+
+    pretty_assert_eq!(
+      build_cpfp(
+        &utxos,
+        outpoint(1),
+        recipient(),
+        change(0),
+        200,
+        Amount::from_sat(100),
+        Amount::from_sat(2),
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+        output: vec![
+          tx_out(10_000, recipient()),
+          tx_out(30_000 - (2 * (200 + 430) - 100), change(0))
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn child_pays_at_least_its_own_fee() {
+    let utxos = vec![
+      (outpoint(1), vec![(0, 10_000)]),
+      (outpoint(2), vec![(20_000, 50_000)]),
+    ]
+    .into_iter()
+    .collect();
+
+    pretty_assert_eq!(
+      build_cpfp(
+        &utxos,
+        outpoint(1),
+        recipient(),
+        change(0),
+        200,
+        Amount::from_sat(5_000),
+        Amount::from_sat(2),
+      )
+      .unwrap()
+      .output[1]
+        .value,
+      30_000 - 2 * 430,
+    )
+  }
+
+  #[test]
+  fn rare_ordinals_are_not_used_as_cardinal_inputs() {
+    let utxos = vec![
+      (outpoint(1), vec![(0, 10_000)]),
+      (
+        outpoint(2),
+        vec![(50 * COIN_VALUE, 50 * COIN_VALUE + 30_000)],
+      ),
+      (outpoint(3), vec![(20_000, 50_000)]),
+    ]
+    .into_iter()
+    .collect();
+
+    pretty_assert_eq!(
+      build_cpfp(
+        &utxos,
+        outpoint(1),
+        recipient(),
+        change(0),
+        200,
+        Amount::from_sat(100),
+        Amount::from_sat(2),
+      )
+      .unwrap()
+      .input,
+      vec![tx_in(outpoint(1)), tx_in(outpoint(3))],
+    )
+  }
+
+  #[test]
+  fn not_enough_cardinal_utxos() {
+    let utxos = vec![
+      (outpoint(1), vec![(0, 10_000)]),
+      (outpoint(2), vec![(20_000, 21_000)]),
+    ]
+    .into_iter()
+    .collect();
+
+    pretty_assert_eq!(
+      build_cpfp(
+        &utxos,
+        outpoint(1),
+        recipient(),
+        change(0),
+        200,
+        Amount::from_sat(100),
+        Amount::from_sat(2),
+      ),
+      Err(Error::NotEnoughCardinalUtxos),
+    )
+  }
+}
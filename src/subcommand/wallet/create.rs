@@ -0,0 +1,25 @@
+use {
+  super::*,
+  keychain::{AddressType, Keychain},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Create {
+  #[clap(
+    long,
+    arg_enum,
+    default_value = "taproot",
+    help = "Derive BIP-86 taproot or BIP-84 segwit addresses of <ADDRESS_TYPE>."
+  )]
+  address_type: AddressType,
+}
+
+impl Create {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let (_keychain, mnemonic) = Keychain::create(&options, self.address_type)?;
+
+    println!("{mnemonic}");
+
+    Ok(())
+  }
+}
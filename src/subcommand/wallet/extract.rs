@@ -0,0 +1,54 @@
+//! Extraction moves rare ordinals out of the large outputs they usually sit
+//! in, typically coinbase outputs, and into their own minimal-postage outputs,
+//! so that they can be sent without dragging along lots of cardinal value.
+//!
+//! The transaction is constructed by `TransactionBuilder::build_extraction`.
+
+use {
+  super::*,
+  transaction_builder::{Error, TransactionBuilder},
+};
+
+pub(crate) fn run(options: Options) -> crate::Result {
+  let index = Index::open(&options)?;
+  index.update()?;
+
+  let mut backend = Backend::for_command(&options, &index, "ord wallet extract")?;
+
+  let utxos = backend
+    .list_unspent(&index)?
+    .into_iter()
+    .collect::<BTreeMap<OutPoint, Vec<(u64, u64)>>>();
+
+  let rare = TransactionBuilder::unextracted_outputs(&utxos)
+    .iter()
+    .flat_map(|outpoint| &utxos[outpoint])
+    .filter(|(start, _end)| Ordinal(*start).rarity() > Rarity::Common)
+    .count();
+
+  if rare == 0 {
+    return Err(Error::NothingToExtract.into());
+  }
+
+  let change = (0..rare * 2 + 2)
+    .map(|_| backend.change_address(&index))
+    .collect::<crate::Result<Vec<Address>>>()?;
+
+  let unsigned_transaction = TransactionBuilder::build_extraction(utxos.clone(), change)?;
+
+  let signed_tx = backend.sign(&index, &unsigned_transaction)?;
+
+  let txid = backend.send(&signed_tx)?;
+
+  sync_locks(
+    &options,
+    &backend,
+    &utxos,
+    &unsigned_transaction,
+    txid,
+    None,
+  )?;
+
+  println!("{txid}");
+  Ok(())
+}
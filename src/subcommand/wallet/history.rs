@@ -0,0 +1,133 @@
+use super::*;
+
+#[derive(Serialize)]
+struct HistoryEntry {
+  output: OutPoint,
+  category: String,
+  ranges: Option<Vec<HistoryRange>>,
+}
+
+#[derive(Serialize)]
+struct HistoryRange {
+  start: u64,
+  size: u64,
+  rarity: Rarity,
+  name: String,
+}
+
+impl Output for HistoryEntry {
+  fn tsv(&self) -> String {
+    match &self.ranges {
+      Some(ranges) => ranges
+        .iter()
+        .map(|range| {
+          format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\n",
+            self.output, self.category, range.start, range.size, range.rarity, range.name
+          )
+        })
+        .collect(),
+      None => format!("{}\t{}\tincomplete\n", self.output, self.category),
+    }
+  }
+}
+
+pub(crate) fn run(options: Options) -> Result {
+  let index = Index::open(&options)?;
+  index.update()?;
+
+  let backend = Backend::for_command(&options, &index, "ord wallet history")?;
+
+  let mut entries = Vec::new();
+  for (output, category) in backend.transactions(&index)? {
+    let ranges = output_ranges(&index, output)?;
+    entries.push(history_entry(output, category, ranges));
+  }
+
+  options.format().print(&entries)
+}
+
+/// The index only keeps the ranges of unspent outputs. Those of other outputs
+/// are assigned from the ranges of their transaction's inputs, which are only
+/// known while every input is unspent, as for unconfirmed transactions.
+/// Otherwise the flow of ranges through the transaction is incomplete, and
+/// `None` is returned.
+fn output_ranges(index: &Index, outpoint: OutPoint) -> Result<Option<Vec<(u64, u64)>>> {
+  if let Some(List::Unspent(ranges)) = index.list(outpoint)? {
+    return Ok(Some(ranges));
+  }
+
+  Ok(
+    unconfirmed_output_ranges(index, outpoint.txid)?
+      .and_then(|outputs| outputs.into_iter().nth(outpoint.vout as usize)),
+  )
+}
+
+fn history_entry(
+  output: OutPoint,
+  category: String,
+  ranges: Option<Vec<(u64, u64)>>,
+) -> HistoryEntry {
+  HistoryEntry {
+    output,
+    category,
+    ranges: ranges.map(|ranges| {
+      ranges
+        .into_iter()
+        .map(|(start, end)| {
+          let ordinal = Ordinal(start);
+          HistoryRange {
+            start,
+            size: end - start,
+            rarity: ordinal.rarity(),
+            name: ordinal.name(),
+          }
+        })
+        .collect()
+    }),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn known_ranges_tsv() {
+    assert_eq!(
+      history_entry(
+        outpoint(1),
+        "receive".into(),
+        Some(vec![(0, 10), (50 * COIN_VALUE, 50 * COIN_VALUE + 5)])
+      )
+      .tsv(),
+      format!(
+        "{}\treceive\t0\t10\tmythic\tnvtdijuwxlp\n{}\treceive\t{}\t5\tuncommon\tnvtcsezkbth\n",
+        outpoint(1),
+        outpoint(1),
+        50 * COIN_VALUE
+      )
+    );
+  }
+
+  #[test]
+  fn incomplete_ranges_tsv() {
+    assert_eq!(
+      history_entry(outpoint(1), "send".into(), None).tsv(),
+      format!("{}\tsend\tincomplete\n", outpoint(1))
+    );
+  }
+
+  #[test]
+  fn incomplete_ranges_json() {
+    assert_eq!(
+      Format::Json
+        .render(&history_entry(outpoint(1), "send".into(), None))
+        .unwrap(),
+      format!(
+        "{{\n  \"output\": \"{}\",\n  \"category\": \"send\",\n  \"ranges\": null\n}}\n",
+        outpoint(1)
+      )
+    );
+  }
+}
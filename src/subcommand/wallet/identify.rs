@@ -9,31 +9,82 @@ pub(crate) struct Identify {
   ordinals: Option<PathBuf>,
 }
 
+#[derive(Serialize)]
+struct RareOrdinal {
+  output: OutPoint,
+  ordinal: Ordinal,
+  offset: u64,
+  rarity: Rarity,
+  purpose: Purpose,
+}
+
+impl Output for RareOrdinal {
+  fn tsv(&self) -> String {
+    format!(
+      "{}\t{}\t{}\t{}\t{}\n",
+      self.output, self.ordinal, self.offset, self.rarity, self.purpose
+    )
+  }
+}
+
+#[derive(Serialize)]
+struct ListedOrdinal {
+  output: OutPoint,
+  ordinal: String,
+  purpose: Purpose,
+}
+
+impl Output for ListedOrdinal {
+  fn tsv(&self) -> String {
+    format!("{}\t{}\t{}\n", self.output, self.ordinal, self.purpose)
+  }
+}
+
 impl Identify {
   pub(crate) fn run(&self, options: Options) -> Result {
     let index = Index::open(&options)?;
     index.update()?;
 
-    let utxos = list_unspent(&options, &index)?;
+    let backend = Backend::new(&options)?;
+
+    let utxos = backend.list_unspent(&index)?;
+
+    let receive = backend.receive_outputs(utxos.iter().map(|(outpoint, _ranges)| *outpoint))?;
 
     if let Some(path) = &self.ordinals {
-      for (output, ordinal) in identify_from_tsv(
-        utxos,
-        &fs::read_to_string(path).with_context(|| "I/O error reading `{path}`")?,
-      )? {
-        println!("{output}\t{ordinal}");
-      }
+      options.format().print(
+        &identify_from_tsv(
+          utxos,
+          &fs::read_to_string(path).with_context(|| "I/O error reading `{path}`")?,
+        )?
+        .into_iter()
+        .map(|(output, ordinal)| ListedOrdinal {
+          output,
+          ordinal: ordinal.into(),
+          purpose: Purpose::of(&receive, output),
+        })
+        .collect::<Vec<ListedOrdinal>>(),
+      )
     } else {
-      for (output, ordinal, offset, rarity) in identify_rare(utxos) {
-        println!("{output}\t{ordinal}\t{offset}\t{rarity}");
-      }
+      options.format().print(
+        &identify_rare(utxos)
+          .into_iter()
+          .map(|(output, ordinal, offset, rarity)| RareOrdinal {
+            output,
+            ordinal,
+            offset,
+            rarity,
+            purpose: Purpose::of(&receive, output),
+          })
+          .collect::<Vec<RareOrdinal>>(),
+      )
     }
-
-    Ok(())
   }
 }
 
-fn identify_rare(utxos: Vec<(OutPoint, Vec<(u64, u64)>)>) -> Vec<(OutPoint, Ordinal, u64, Rarity)> {
+pub(crate) fn identify_rare(
+  utxos: Vec<(OutPoint, Vec<(u64, u64)>)>,
+) -> Vec<(OutPoint, Ordinal, u64, Rarity)> {
   utxos
     .into_iter()
     .flat_map(|(outpoint, ordinal_ranges)| {
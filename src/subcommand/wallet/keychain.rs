@@ -0,0 +1,747 @@
+//! A standalone BIP-86 (taproot) or BIP-84 (segwit) wallet, used instead of
+//! Bitcoin Core's wallet once `ord wallet create` has been run.
+//!
+//! The seed is derived from a BIP-39 mnemonic, which `ord wallet create`
+//! prints so that it can be backed up, and from which `ord wallet restore`
+//! recreates the wallet. The seed is encrypted with a key derived from the
+//! passphrase in `ORD_WALLET_PASSPHRASE` and stored in `wallet.json` in the
+//! data dir, readable only by its owner, along with the account extended
+//! public key, so that addresses can be derived without the passphrase. The
+//! passphrase is only needed to sign.
+//!
+//! Outputs paying to wallet addresses are found using the index, which only
+//! tracks outputs paying to scripts registered with `Index::watch` before the
+//! block containing them was indexed. Addresses are registered as they are
+//! derived, and `ord wallet restore` scans Bitcoin Core's UTXO set for outputs
+//! paying to addresses derived by the restored wallet. A wallet restored by
+//! copying `wallet.json` needs a fresh index to find outputs received before
+//! the index was created.
+
+use {
+  super::*,
+  argon2::Argon2,
+  bip39::Mnemonic,
+  bitcoin::{
+    blockdata::witness::Witness,
+    secp256k1::{
+      rand::{self, RngCore},
+      All, Message, Secp256k1, Verification, XOnlyPublicKey,
+    },
+    util::{
+      bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey},
+      schnorr::TapTweak,
+      sighash::{Prevouts, SighashCache},
+    },
+    EcdsaSig, EcdsaSighashType, SchnorrSig, SchnorrSighashType,
+  },
+  chacha20poly1305::{aead::Aead, ChaCha20Poly1305, Key, KeyInit, Nonce},
+  std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+  },
+};
+
+const PASSPHRASE_ENV_VAR: &str = "ORD_WALLET_PASSPHRASE";
+
+const RECEIVE: u32 = 0;
+const CHANGE: u32 = 1;
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum AddressType {
+  Taproot,
+  Segwit,
+}
+
+impl AddressType {
+  fn account_path(self, chain: Chain) -> DerivationPath {
+    let purpose = match self {
+      Self::Taproot => 86,
+      Self::Segwit => 84,
+    };
+
+    let coin_type = match chain {
+      Chain::Mainnet => 0,
+      Chain::Testnet | Chain::Signet | Chain::Regtest => 1,
+    };
+
+    vec![
+      ChildNumber::from_hardened_idx(purpose).unwrap(),
+      ChildNumber::from_hardened_idx(coin_type).unwrap(),
+      ChildNumber::from_hardened_idx(0).unwrap(),
+    ]
+    .into()
+  }
+
+  fn address<C: Verification>(
+    self,
+    secp: &Secp256k1<C>,
+    public_key: bitcoin::secp256k1::PublicKey,
+    chain: Chain,
+  ) -> Address {
+    match self {
+      Self::Taproot => Address::p2tr(
+        secp,
+        XOnlyPublicKey::from(public_key),
+        None,
+        chain.network(),
+      ),
+      Self::Segwit => Address::p2wpkh(&bitcoin::PublicKey::new(public_key), chain.network())
+        .expect("invariant: public key is compressed"),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Keychain {
+  chain: Chain,
+  address_type: AddressType,
+  account: String,
+  salt: String,
+  nonce: String,
+  encrypted_seed: String,
+  #[serde(default)]
+  receive_addresses: u32,
+  change_addresses: u32,
+  #[serde(skip)]
+  path: PathBuf,
+}
+
+impl Keychain {
+  fn path(options: &Options) -> Result<PathBuf> {
+    Ok(options.data_dir()?.join("wallet.json"))
+  }
+
+  /// Create a new wallet from a random 24-word mnemonic, which is returned so
+  /// that it can be shown to the user.
+  pub(crate) fn create(options: &Options, address_type: AddressType) -> Result<(Self, Mnemonic)> {
+    let mut entropy = [0; 32];
+    rand::thread_rng().fill_bytes(&mut entropy);
+
+    let mnemonic = Mnemonic::from_entropy(&entropy)?;
+
+    Ok((Self::restore(options, address_type, &mnemonic)?, mnemonic))
+  }
+
+  pub(crate) fn restore(
+    options: &Options,
+    address_type: AddressType,
+    mnemonic: &Mnemonic,
+  ) -> Result<Self> {
+    let path = Self::path(options)?;
+
+    if path.exists() {
+      bail!("wallet already exists at `{}`", path.display());
+    }
+
+    let mut keychain = Self::from_seed(
+      &mnemonic.to_seed(""),
+      options.chain(),
+      address_type,
+      &Self::passphrase()?,
+    )?;
+    keychain.path = path;
+    keychain.save()?;
+
+    Ok(keychain)
+  }
+
+  pub(crate) fn load(options: &Options) -> Result<Option<Self>> {
+    let path = Self::path(options)?;
+
+    let json = match fs::read_to_string(&path) {
+      Ok(json) => json,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+      Err(err) => {
+        return Err(err).with_context(|| format!("I/O error reading `{}`", path.display()))
+      }
+    };
+
+    let mut keychain = serde_json::from_str::<Self>(&json)
+      .with_context(|| format!("failed to parse wallet `{}`", path.display()))?;
+
+    if keychain.chain != options.chain() {
+      bail!(
+        "wallet `{}` is for {} but ord is on {}",
+        path.display(),
+        keychain.chain,
+        options.chain()
+      );
+    }
+
+    keychain.path = path;
+
+    Ok(Some(keychain))
+  }
+
+  /// Save the wallet, creating `wallet.json` if necessary. Since it contains
+  /// the encrypted seed, the file is only readable by its owner, and is
+  /// written to a temporary file which then replaces it, so that a crash or
+  /// full disk can't leave it half written.
+  fn save(&self) -> Result {
+    let parent = self.path.parent().unwrap_or_else(|| Path::new("."));
+
+    fs::create_dir_all(parent)
+      .with_context(|| format!("failed to create data dir `{}`", parent.display()))?;
+
+    let json = serde_json::to_string_pretty(self)?;
+
+    let tmp = self.path.with_extension("json.tmp");
+
+    let context = || format!("I/O error writing `{}`", tmp.display());
+
+    let mut open_options = fs::OpenOptions::new();
+    open_options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut open_options, 0o600);
+
+    let mut file = open_options.open(&tmp).with_context(context)?;
+
+    // A temporary file left by an earlier crash keeps its old permissions
+    #[cfg(unix)]
+    file
+      .set_permissions(<fs::Permissions as std::os::unix::fs::PermissionsExt>::from_mode(0o600))
+      .with_context(context)?;
+
+    file.write_all(json.as_bytes()).with_context(context)?;
+    file.sync_all().with_context(context)?;
+
+    fs::rename(&tmp, &self.path).with_context(|| {
+      format!(
+        "I/O error replacing `{}` with `{}`",
+        self.path.display(),
+        tmp.display()
+      )
+    })?;
+
+    // Make the rename itself durable
+    #[cfg(unix)]
+    fs::File::open(parent)
+      .and_then(|dir| dir.sync_all())
+      .with_context(|| format!("I/O error syncing `{}`", parent.display()))?;
+
+    Ok(())
+  }
+
+  fn from_seed(
+    seed: &[u8],
+    chain: Chain,
+    address_type: AddressType,
+    passphrase: &str,
+  ) -> Result<Self> {
+    let secp = Secp256k1::new();
+
+    let account = ExtendedPrivKey::new_master(chain.network(), seed)?
+      .derive_priv(&secp, &address_type.account_path(chain))?;
+
+    let mut salt = [0; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+
+    let mut nonce = [0; 12];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let encrypted_seed = Self::cipher(passphrase, &salt)?
+      .encrypt(Nonce::from_slice(&nonce), seed)
+      .map_err(|_| anyhow!("failed to encrypt seed"))?;
+
+    Ok(Self {
+      chain,
+      address_type,
+      account: ExtendedPubKey::from_priv(&secp, &account).to_string(),
+      salt: hex::encode(salt),
+      nonce: hex::encode(nonce),
+      encrypted_seed: hex::encode(encrypted_seed),
+      receive_addresses: 0,
+      change_addresses: 0,
+      path: PathBuf::new(),
+    })
+  }
+
+  fn passphrase() -> Result<String> {
+    env::var(PASSPHRASE_ENV_VAR)
+      .with_context(|| format!("wallet passphrase must be set with `{PASSPHRASE_ENV_VAR}`"))
+  }
+
+  fn cipher(passphrase: &str, salt: &[u8]) -> Result<ChaCha20Poly1305> {
+    let mut key = [0; 32];
+
+    Argon2::default()
+      .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+      .map_err(|err| anyhow!("failed to derive key from passphrase: {err}"))?;
+
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+  }
+
+  fn decrypt_seed(&self, passphrase: &str) -> Result<Vec<u8>> {
+    Self::cipher(passphrase, &hex::decode(&self.salt)?)?
+      .decrypt(
+        Nonce::from_slice(&hex::decode(&self.nonce)?),
+        hex::decode(&self.encrypted_seed)?.as_slice(),
+      )
+      .map_err(|_| anyhow!("failed to decrypt wallet seed: incorrect passphrase"))
+  }
+
+  fn derive_address<C: Verification>(
+    &self,
+    secp: &Secp256k1<C>,
+    keychain: u32,
+    index: u32,
+  ) -> Result<Address> {
+    let public_key = ExtendedPubKey::from_str(&self.account)?
+      .derive_pub(
+        secp,
+        &[
+          ChildNumber::from_normal_idx(keychain)?,
+          ChildNumber::from_normal_idx(index)?,
+        ],
+      )?
+      .public_key;
+
+    Ok(self.address_type.address(secp, public_key, self.chain))
+  }
+
+  /// Derive the next unused address for receiving ordinals, from the
+  /// external keychain, and save the wallet, so the address is not handed out
+  /// again.
+  pub(crate) fn next_receive_address(&mut self) -> Result<Address> {
+    let address = self.derive_address(
+      &Secp256k1::verification_only(),
+      RECEIVE,
+      self.receive_addresses,
+    )?;
+    self.receive_addresses += 1;
+    self.save()?;
+    Ok(address)
+  }
+
+  /// Derive the next unused change address, from the internal keychain, and
+  /// save the wallet.
+  pub(crate) fn next_change_address(&mut self) -> Result<Address> {
+    let address = self.derive_address(
+      &Secp256k1::verification_only(),
+      CHANGE,
+      self.change_addresses,
+    )?;
+    self.change_addresses += 1;
+    self.save()?;
+    Ok(address)
+  }
+
+  /// The next `count` receive and change addresses after those handed out so
+  /// far, with their keychain and index.
+  pub(crate) fn lookahead(&self, count: u32) -> Result<Vec<(Address, u32, u32)>> {
+    let secp = Secp256k1::verification_only();
+
+    let mut addresses = Vec::new();
+
+    for (keychain, start) in [
+      (RECEIVE, self.receive_addresses),
+      (CHANGE, self.change_addresses),
+    ] {
+      for index in start..start + count {
+        addresses.push((
+          self.derive_address(&secp, keychain, index)?,
+          keychain,
+          index,
+        ));
+      }
+    }
+
+    Ok(addresses)
+  }
+
+  /// Treat address `index` of `keychain`, and every address before it, as
+  /// handed out, and save the wallet.
+  pub(crate) fn mark_used(&mut self, keychain: u32, index: u32) -> Result {
+    let count = if keychain == RECEIVE {
+      &mut self.receive_addresses
+    } else {
+      &mut self.change_addresses
+    };
+
+    *count = (*count).max(index + 1);
+
+    self.save()
+  }
+
+  /// Map the script pubkey of every address derived so far to its keychain
+  /// and index.
+  pub(crate) fn script_pubkeys(&self) -> Result<HashMap<Script, (u32, u32)>> {
+    let secp = Secp256k1::verification_only();
+
+    let mut script_pubkeys = HashMap::new();
+
+    for (keychain, count) in [
+      (RECEIVE, self.receive_addresses),
+      (CHANGE, self.change_addresses),
+    ] {
+      for index in 0..count {
+        script_pubkeys.insert(
+          self.derive_address(&secp, keychain, index)?.script_pubkey(),
+          (keychain, index),
+        );
+      }
+    }
+
+    Ok(script_pubkeys)
+  }
+
+  pub(crate) fn receive_script_pubkeys(&self) -> Result<HashSet<Script>> {
+    Ok(
+      self
+        .script_pubkeys()?
+        .into_iter()
+        .filter(|(_script_pubkey, (keychain, _index))| *keychain == RECEIVE)
+        .map(|(script_pubkey, _path)| script_pubkey)
+        .collect(),
+    )
+  }
+
+  /// Sign every input of `transaction`, whose previous outputs are
+  /// `prevouts`, all of which must pay to addresses derived by this wallet.
+  pub(crate) fn sign(&self, transaction: &mut Transaction, prevouts: &[TxOut]) -> Result {
+    self.sign_with_passphrase(transaction, prevouts, &Self::passphrase()?)
+  }
+
+  fn sign_with_passphrase(
+    &self,
+    transaction: &mut Transaction,
+    prevouts: &[TxOut],
+    passphrase: &str,
+  ) -> Result {
+    let secp = Secp256k1::new();
+
+    let account =
+      ExtendedPrivKey::new_master(self.chain.network(), &self.decrypt_seed(passphrase)?)?
+        .derive_priv(&secp, &self.address_type.account_path(self.chain))?;
+
+    let script_pubkeys = self.script_pubkeys()?;
+
+    let mut witnesses = Vec::new();
+
+    {
+      let mut cache = SighashCache::new(&*transaction);
+
+      for (i, prevout) in prevouts.iter().enumerate() {
+        let (keychain, index) = script_pubkeys.get(&prevout.script_pubkey).ok_or_else(|| {
+          anyhow!(
+            "input {} is not in wallet",
+            transaction.input[i].previous_output
+          )
+        })?;
+
+        let key = account.derive_priv(
+          &secp,
+          &[
+            ChildNumber::from_normal_idx(*keychain)?,
+            ChildNumber::from_normal_idx(*index)?,
+          ],
+        )?;
+
+        witnesses.push(self.witness(&secp, &mut cache, i, prevouts, key)?);
+      }
+    }
+
+    for (tx_in, witness) in transaction.input.iter_mut().zip(witnesses) {
+      tx_in.witness = witness;
+    }
+
+    Ok(())
+  }
+
+  fn witness(
+    &self,
+    secp: &Secp256k1<All>,
+    cache: &mut SighashCache<&Transaction>,
+    input: usize,
+    prevouts: &[TxOut],
+    key: ExtendedPrivKey,
+  ) -> Result<Witness> {
+    match self.address_type {
+      AddressType::Taproot => {
+        let sighash = cache.taproot_key_spend_signature_hash(
+          input,
+          &Prevouts::All(prevouts),
+          SchnorrSighashType::Default,
+        )?;
+
+        let keypair = key.to_keypair(secp).tap_tweak(secp, None).to_inner();
+
+        let sig = secp.sign_schnorr(&Message::from_slice(sighash.as_ref())?, &keypair);
+
+        Ok(Witness::from_vec(vec![SchnorrSig {
+          sig,
+          hash_ty: SchnorrSighashType::Default,
+        }
+        .to_vec()]))
+      }
+      AddressType::Segwit => {
+        let prevout = &prevouts[input];
+
+        let script_code = prevout
+          .script_pubkey
+          .p2wpkh_script_code()
+          .ok_or_else(|| anyhow!("input {input} is not p2wpkh"))?;
+
+        let sighash =
+          cache.segwit_signature_hash(input, &script_code, prevout.value, EcdsaSighashType::All)?;
+
+        let sig = secp.sign_ecdsa(&Message::from_slice(sighash.as_ref())?, &key.private_key);
+
+        Ok(Witness::from_vec(vec![
+          EcdsaSig::sighash_all(sig).to_vec(),
+          key.private_key.public_key(secp).serialize().to_vec(),
+        ]))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    bitcoin::{secp256k1::schnorr, PackedLockTime},
+  };
+
+  // BIP-39 seed of the mnemonic "abandon abandon … about", used by the
+  // BIP-84 and BIP-86 test vectors.
+  const SEED: &str = "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4";
+
+  fn keychain(address_type: AddressType) -> Keychain {
+    Keychain::from_seed(
+      &hex::decode(SEED).unwrap(),
+      Chain::Mainnet,
+      address_type,
+      "foo",
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn bip86_test_vector() {
+    assert_eq!(
+      keychain(AddressType::Taproot)
+        .derive_address(&Secp256k1::verification_only(), 0, 0)
+        .unwrap()
+        .to_string(),
+      "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+    );
+  }
+
+  #[test]
+  fn bip84_test_vector() {
+    assert_eq!(
+      keychain(AddressType::Segwit)
+        .derive_address(&Secp256k1::verification_only(), 0, 0)
+        .unwrap()
+        .to_string(),
+      "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+    );
+  }
+
+  #[test]
+  fn seed_round_trips_with_correct_passphrase() {
+    assert_eq!(
+      hex::encode(keychain(AddressType::Taproot).decrypt_seed("foo").unwrap()),
+      SEED
+    );
+  }
+
+  #[test]
+  fn incorrect_passphrase_is_rejected() {
+    assert_eq!(
+      keychain(AddressType::Taproot)
+        .decrypt_seed("bar")
+        .unwrap_err()
+        .to_string(),
+      "failed to decrypt wallet seed: incorrect passphrase"
+    );
+  }
+
+  #[test]
+  fn receive_and_change_addresses_are_distinct() {
+    let mut keychain = keychain(AddressType::Taproot);
+    let tempdir = TempDir::new().unwrap();
+    keychain.path = tempdir.path().join("wallet.json");
+
+    let receive = keychain.next_receive_address().unwrap();
+    let change = keychain.next_change_address().unwrap();
+
+    assert_eq!(
+      receive.to_string(),
+      "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+    );
+
+    assert_eq!(
+      keychain.receive_script_pubkeys().unwrap(),
+      [receive.script_pubkey()].into_iter().collect()
+    );
+
+    assert!(keychain
+      .script_pubkeys()
+      .unwrap()
+      .contains_key(&change.script_pubkey()));
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn wallet_file_is_only_readable_by_owner() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut keychain = keychain(AddressType::Taproot);
+    let tempdir = TempDir::new().unwrap();
+    keychain.path = tempdir.path().join("wallet.json");
+
+    fs::write(&keychain.path, "").unwrap();
+    fs::set_permissions(&keychain.path, fs::Permissions::from_mode(0o644)).unwrap();
+
+    keychain.save().unwrap();
+
+    assert_eq!(
+      fs::metadata(&keychain.path).unwrap().permissions().mode() & 0o777,
+      0o600
+    );
+  }
+
+  #[test]
+  fn mnemonic_derives_test_vector_seed() {
+    assert_eq!(
+      hex::encode(
+        Mnemonic::parse(
+          "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+        )
+        .unwrap()
+        .to_seed("")
+      ),
+      SEED
+    );
+  }
+
+  #[test]
+  fn lookahead_starts_after_handed_out_addresses() {
+    let mut keychain = keychain(AddressType::Taproot);
+    let tempdir = TempDir::new().unwrap();
+    keychain.path = tempdir.path().join("wallet.json");
+
+    let lookahead = keychain.lookahead(2).unwrap();
+
+    assert_eq!(
+      lookahead
+        .iter()
+        .map(|(_address, keychain, index)| (*keychain, *index))
+        .collect::<Vec<(u32, u32)>>(),
+      [(RECEIVE, 0), (RECEIVE, 1), (CHANGE, 0), (CHANGE, 1)]
+    );
+
+    keychain.mark_used(RECEIVE, 1).unwrap();
+
+    assert_eq!(keychain.receive_addresses, 2);
+    assert_eq!(keychain.change_addresses, 0);
+
+    let next = keychain.lookahead(1).unwrap()[0].0.clone();
+
+    assert_eq!(keychain.next_receive_address().unwrap(), next);
+  }
+
+  #[test]
+  fn change_addresses_are_tracked() {
+    let mut keychain = keychain(AddressType::Taproot);
+    let tempdir = TempDir::new().unwrap();
+    keychain.path = tempdir.path().join("wallet.json");
+
+    let first = keychain.next_change_address().unwrap();
+    let second = keychain.next_change_address().unwrap();
+
+    assert_ne!(first, second);
+
+    assert_eq!(
+      keychain.script_pubkeys().unwrap(),
+      [
+        (first.script_pubkey(), (1, 0)),
+        (second.script_pubkey(), (1, 1))
+      ]
+      .into_iter()
+      .collect()
+    );
+  }
+
+  fn spend(prevouts: &[TxOut]) -> Transaction {
+    Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: (0..prevouts.len())
+        .map(|i| TxIn {
+          previous_output: outpoint(i as u64),
+          script_sig: Script::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::new(),
+        })
+        .collect(),
+      output: vec![TxOut {
+        value: 1_000,
+        script_pubkey: Script::new(),
+      }],
+    }
+  }
+
+  #[test]
+  fn taproot_inputs_are_signed() {
+    let mut keychain = keychain(AddressType::Taproot);
+    let tempdir = TempDir::new().unwrap();
+    keychain.path = tempdir.path().join("wallet.json");
+
+    let prevouts = vec![TxOut {
+      value: 10_000,
+      script_pubkey: keychain.next_change_address().unwrap().script_pubkey(),
+    }];
+
+    let mut transaction = spend(&prevouts);
+
+    keychain
+      .sign_with_passphrase(&mut transaction, &prevouts, "foo")
+      .unwrap();
+
+    let secp = Secp256k1::new();
+
+    let sighash = SighashCache::new(&transaction)
+      .taproot_key_spend_signature_hash(0, &Prevouts::All(&prevouts), SchnorrSighashType::Default)
+      .unwrap();
+
+    let output_key =
+      XOnlyPublicKey::from_slice(&prevouts[0].script_pubkey.as_bytes()[2..]).unwrap();
+
+    let witness = transaction.input[0].witness.to_vec();
+
+    assert_eq!(witness.len(), 1);
+
+    secp
+      .verify_schnorr(
+        &schnorr::Signature::from_slice(&witness[0]).unwrap(),
+        &Message::from_slice(sighash.as_ref()).unwrap(),
+        &output_key,
+      )
+      .unwrap();
+  }
+
+  #[test]
+  fn foreign_inputs_are_not_signed() {
+    let keychain = keychain(AddressType::Taproot);
+
+    let prevouts = vec![TxOut {
+      value: 10_000,
+      script_pubkey: Script::new(),
+    }];
+
+    let mut transaction = spend(&prevouts);
+
+    assert_eq!(
+      keychain
+        .sign_with_passphrase(&mut transaction, &prevouts, "foo")
+        .unwrap_err()
+        .to_string(),
+      format!("input {} is not in wallet", outpoint(0)),
+    );
+  }
+}
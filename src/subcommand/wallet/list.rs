@@ -1,16 +1,47 @@
 use super::*;
 
+#[derive(Serialize)]
+struct UnspentRange {
+  output: OutPoint,
+  start: u64,
+  size: u64,
+  rarity: Rarity,
+  name: String,
+  purpose: Purpose,
+}
+
+impl Output for UnspentRange {
+  fn tsv(&self) -> String {
+    format!(
+      "{}\t{}\t{}\t{}\t{}\t{}\n",
+      self.output, self.start, self.size, self.rarity, self.name, self.purpose
+    )
+  }
+}
+
 pub(crate) fn run(options: Options) -> Result {
   let index = Index::open(&options)?;
   index.update()?;
 
-  let utxos = list_unspent(&options, &index)?;
+  let backend = Backend::new(&options)?;
 
-  for (output, start, size, rarity, name) in list(utxos) {
-    println!("{output}\t{start}\t{size}\t{rarity}\t{name}");
-  }
+  let utxos = backend.list_unspent(&index)?;
 
-  Ok(())
+  let receive = backend.receive_outputs(utxos.iter().map(|(outpoint, _ranges)| *outpoint))?;
+
+  options.format().print(
+    &list(utxos)
+      .into_iter()
+      .map(|(output, start, size, rarity, name)| UnspentRange {
+        output,
+        start,
+        size,
+        rarity,
+        name,
+        purpose: Purpose::of(&receive, output),
+      })
+      .collect::<Vec<UnspentRange>>(),
+  )
 }
 
 fn list(utxos: Vec<(OutPoint, Vec<(u64, u64)>)>) -> Vec<(OutPoint, u64, u64, Rarity, String)> {
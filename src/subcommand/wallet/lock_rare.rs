@@ -0,0 +1,29 @@
+use {super::*, identify::identify_rare};
+
+pub(crate) fn run(options: Options) -> Result {
+  let index = Index::open(&options)?;
+  index.update()?;
+
+  let backend = Backend::for_command(&options, &index, "ord wallet lock-rare")?;
+
+  let rare = identify_rare(backend.list_unspent(&index)?)
+    .into_iter()
+    .map(|(outpoint, _ordinal, _offset, _rarity)| outpoint)
+    .collect::<BTreeSet<OutPoint>>();
+
+  let locked = backend.list_locked(&options)?;
+
+  let unlocked = rare.difference(&locked).cloned().collect::<Vec<OutPoint>>();
+
+  if !unlocked.is_empty() && !backend.lock_unspent(&unlocked)? {
+    bail!("failed to lock outputs");
+  }
+
+  save_locked_outputs(&options, &rare)?;
+
+  for outpoint in rare {
+    println!("{outpoint}");
+  }
+
+  Ok(())
+}
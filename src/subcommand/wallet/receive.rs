@@ -0,0 +1,12 @@
+use super::*;
+
+pub(crate) fn run(options: Options) -> Result {
+  let index = Index::open(&options)?;
+  index.update()?;
+
+  let address = Backend::new(&options)?.receive_address(&index)?;
+
+  println!("{address}");
+
+  Ok(())
+}
@@ -0,0 +1,70 @@
+use {
+  super::*,
+  bip39::Mnemonic,
+  keychain::{AddressType, Keychain},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Restore {
+  #[clap(
+    long,
+    arg_enum,
+    default_value = "taproot",
+    help = "Derive BIP-86 taproot or BIP-84 segwit addresses of <ADDRESS_TYPE>."
+  )]
+  address_type: AddressType,
+  #[clap(
+    long,
+    default_value = "20",
+    help = "Scan the first <GAP> receive and change addresses for existing outputs."
+  )]
+  gap: u32,
+  #[clap(
+    long,
+    env = "ORD_WALLET_MNEMONIC",
+    hide_env_values = true,
+    help = "Restore wallet from BIP-39 <MNEMONIC>."
+  )]
+  mnemonic: Mnemonic,
+}
+
+impl Restore {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = options.bitcoin_rpc_client()?;
+
+    let mut keychain = Keychain::restore(&options, self.address_type, &self.mnemonic)?;
+
+    let addresses = keychain.lookahead(self.gap)?;
+
+    for (address, _keychain, _index) in &addresses {
+      index.watch(&address.script_pubkey())?;
+    }
+
+    let outputs = crate::subcommand::watch::scan(
+      &client,
+      &index,
+      addresses
+        .iter()
+        .map(
+          |(address, _keychain, _index)| serde_json::json!({ "desc": format!("addr({address})") }),
+        )
+        .collect(),
+    )?;
+
+    for (outpoint, script_pubkey) in outputs {
+      if let Some((_address, path_keychain, path_index)) = addresses
+        .iter()
+        .find(|(address, _keychain, _index)| address.script_pubkey() == script_pubkey)
+      {
+        keychain.mark_used(*path_keychain, *path_index)?;
+      }
+
+      println!("{outpoint}");
+    }
+
+    Ok(())
+  }
+}
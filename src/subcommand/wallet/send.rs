@@ -8,48 +8,61 @@ pub(crate) struct Send {
 
 impl Send {
   pub(crate) fn run(self, options: Options) -> Result {
-    let client = options.bitcoin_rpc_client_for_wallet_command("ord wallet send")?;
-
-    if !self.address.is_valid_for_network(options.chain.network()) {
+    if !self.address.is_valid_for_network(options.chain().network()) {
       bail!(
         "Address `{}` is not valid for {}",
         self.address,
-        options.chain
+        options.chain()
       );
     }
 
     let index = Index::open(&options)?;
     index.update()?;
 
-    let utxos = list_unspent(&options, &index)?.into_iter().collect();
+    let mut backend = Backend::for_command(&options, &index, "ord wallet send")?;
 
-    if options.chain == Chain::Mainnet {
-      let balances = client.get_balances()?;
+    let utxos = backend
+      .list_unspent(&index)?
+      .into_iter()
+      .collect::<BTreeMap<OutPoint, Vec<(u64, u64)>>>();
 
-      if balances.mine.trusted + balances.mine.untrusted_pending + balances.mine.immature
-        > Amount::from_sat(1_000_000)
-      {
-        bail!("`ord wallet send` may not be used on mainnet with wallets containing more than 1,000,000 sats");
-      }
-    }
+    let receive = backend.receive_outputs(utxos.keys().cloned())?;
+
+    // Outputs received with `ord wallet receive` may hold ordinals that were
+    // sent to the wallet deliberately, so they are never used to pad or pay
+    // for the transaction, and are only spent if they hold the ordinal being
+    // sent.
+    let spendable = utxos
+      .iter()
+      .filter(|(outpoint, ranges)| {
+        !receive.contains(outpoint)
+          || ranges
+            .iter()
+            .any(|(start, end)| (*start..*end).contains(&self.ordinal.n()))
+      })
+      .map(|(outpoint, ranges)| (*outpoint, ranges.clone()))
+      .collect();
 
     let change = vec![
-      client
-        .call("getrawchangeaddress", &[])
-        .context("could not get change addresses from wallet")?,
-      client
-        .call("getrawchangeaddress", &[])
-        .context("could not get change addresses from wallet")?,
+      backend.change_address(&index)?,
+      backend.change_address(&index)?,
     ];
 
     let unsigned_transaction =
-      TransactionBuilder::build_transaction(utxos, self.ordinal, self.address, change)?;
+      TransactionBuilder::build_transaction(spendable, self.ordinal, self.address.clone(), change)?;
+
+    let signed_tx = backend.sign(&index, &unsigned_transaction)?;
 
-    let signed_tx = client
-      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
-      .hex;
+    let txid = backend.send(&signed_tx)?;
 
-    let txid = client.send_raw_transaction(&signed_tx)?;
+    sync_locks(
+      &options,
+      &backend,
+      &utxos,
+      &unsigned_transaction,
+      txid,
+      Some(&self.address),
+    )?;
 
     println!("{txid}");
     Ok(())
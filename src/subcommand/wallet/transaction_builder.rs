@@ -13,6 +13,19 @@
 //! ordinal to send, the wallets current UTXOs and their ordinal ranges, and
 //! the recipient's address.
 //!
+//! `TransactionBuilder::build_replacement` rebuilds a previously sent
+//! transaction from the same inputs at a higher fee rate, so that it can
+//! replace the original using replace-by-fee, and checks that it pays enough
+//! more than the original for Bitcoin Core to accept it. All constructed
+//! transactions signal replaceability.
+//!
+//! `TransactionBuilder::build_extraction` moves every rare ordinal in the
+//! wallet that isn't already in an output of its own into a new change output,
+//! by applying the same alignment, padding, and postage steps to each rare
+//! ordinal in turn, with a fresh change address as its recipient. Rare
+//! ordinals closer to the previous one than the dust limit can't be separated,
+//! and stay in the same output.
+//!
 //! Internally, `TransactionBuilder` calls multiple methods that implement
 //! transformations responsible for individual concerns, such as ensuring that
 //! the transaction fee is paid, and that outgoing outputs aren't too large.
@@ -36,8 +49,10 @@ use {
 pub(crate) enum Error {
   NotInWallet(Ordinal),
   NotEnoughCardinalUtxos,
+  NothingToExtract,
   RareOrdinalLostToRecipient(Ordinal),
   RareOrdinalLostToFee(Ordinal),
+  ReplacementFeeTooLow { fee: Amount, minimum: Amount },
 }
 
 impl fmt::Display for Error {
@@ -48,6 +63,7 @@ impl fmt::Display for Error {
         f,
         "wallet does not contain enough cardinal UTXOs, please add additional funds to wallet."
       ),
+      Error::NothingToExtract => write!(f, "wallet contains no rare ordinals to extract"),
       Error::RareOrdinalLostToRecipient(ordinal) => write!(
         f,
         "transaction would lose rare ordinal {ordinal} to recipient"
@@ -55,6 +71,12 @@ impl fmt::Display for Error {
       Error::RareOrdinalLostToFee(ordinal) => {
         write!(f, "transaction would lose rare ordinal {ordinal} to fee")
       }
+      Error::ReplacementFeeTooLow { fee, minimum } => write!(
+        f,
+        "replacement would pay a fee of {} sats, but must pay at least {} sats to replace the original transaction, please raise the fee rate",
+        fee.to_sat(),
+        minimum.to_sat()
+      ),
     }
   }
 }
@@ -64,6 +86,8 @@ impl std::error::Error for Error {}
 #[derive(Debug, PartialEq)]
 pub(crate) struct TransactionBuilder {
   change_addresses: BTreeSet<Address>,
+  extracted: Vec<(Ordinal, Address)>,
+  fee_rate: Amount,
   unused_change_addresses: Vec<Address>,
   inputs: Vec<OutPoint>,
   ordinal: Ordinal,
@@ -76,9 +100,11 @@ pub(crate) struct TransactionBuilder {
 type Result<T> = std::result::Result<T, Error>;
 
 impl TransactionBuilder {
-  const MAX_POSTAGE: Amount = Amount::from_sat(2 * 10_000);
-  const TARGET_FEE_RATE: Amount = Amount::from_sat(1);
-  const TARGET_POSTAGE: Amount = Amount::from_sat(10_000);
+  /// Bitcoin Core's default `-incrementalrelayfee`, in sats per virtual byte.
+  pub(crate) const INCREMENTAL_RELAY_FEE_RATE: Amount = Amount::from_sat(1);
+  pub(crate) const MAX_POSTAGE: Amount = Amount::from_sat(2 * 10_000);
+  pub(crate) const TARGET_FEE_RATE: Amount = Amount::from_sat(1);
+  pub(crate) const TARGET_POSTAGE: Amount = Amount::from_sat(10_000);
 
   pub(crate) fn build_transaction(
     ranges: BTreeMap<OutPoint, Vec<(u64, u64)>>,
@@ -86,7 +112,7 @@ impl TransactionBuilder {
     recipient: Address,
     change: Vec<Address>,
   ) -> Result<Transaction> {
-    Self::new(ranges, ordinal, recipient, change)
+    Self::new(ranges, ordinal, recipient, change, Self::TARGET_FEE_RATE)
       .select_ordinal()?
       .align_ordinal()
       .pad_alignment_output()?
@@ -96,14 +122,71 @@ impl TransactionBuilder {
       .build()
   }
 
+  pub(crate) fn build_replacement(
+    ranges: BTreeMap<OutPoint, Vec<(u64, u64)>>,
+    inputs: Vec<OutPoint>,
+    ordinal: Ordinal,
+    recipient: Address,
+    change: Vec<Address>,
+    fee_rate: Amount,
+    original_fee: Amount,
+  ) -> Result<Transaction> {
+    Self::new(ranges, ordinal, recipient, change, fee_rate)
+      .select_inputs(inputs)?
+      .align_ordinal()
+      .pad_alignment_output()?
+      .add_postage()?
+      .strip_excess_postage()
+      .deduct_fee()
+      .check_replacement_fee(original_fee)?
+      .build()
+  }
+
+  pub(crate) fn build_extraction(
+    ranges: BTreeMap<OutPoint, Vec<(u64, u64)>>,
+    change: Vec<Address>,
+  ) -> Result<Transaction> {
+    Self::select_rare_ordinals(ranges, change)?
+      .add_postage()?
+      .strip_excess_postage()
+      .deduct_fee()
+      .build()
+  }
+
+  /// Wallet outputs that contain rare ordinals and haven't been extracted.
+  /// Outputs which begin with a rare ordinal and carry less than the maximum
+  /// postage have already been extracted.
+  pub(crate) fn unextracted_outputs(ranges: &BTreeMap<OutPoint, Vec<(u64, u64)>>) -> Vec<OutPoint> {
+    ranges
+      .iter()
+      .filter(|(_outpoint, ranges)| {
+        let extracted = ranges
+          .first()
+          .map(|(start, _end)| Ordinal(*start).rarity() > Rarity::Common)
+          .unwrap_or(false)
+          && Amount::from_sat(ranges.iter().map(|(start, end)| end - start).sum())
+            < Self::MAX_POSTAGE;
+
+        !extracted
+          && ranges
+            .iter()
+            .any(|(start, _end)| Ordinal(*start).rarity() > Rarity::Common)
+      })
+      .map(|(outpoint, _ranges)| *outpoint)
+      .collect()
+  }
+
   fn new(
     ranges: BTreeMap<OutPoint, Vec<(u64, u64)>>,
     ordinal: Ordinal,
     recipient: Address,
     change: Vec<Address>,
+    fee_rate: Amount,
   ) -> Self {
     Self {
       change_addresses: change.iter().cloned().collect(),
+      extracted: Vec::new(),
+      fee_rate,
       utxos: ranges.keys().cloned().collect(),
       inputs: Vec::new(),
       ordinal,
@@ -136,39 +219,143 @@ impl TransactionBuilder {
     Ok(self)
   }
 
-  fn align_ordinal(mut self) -> Self {
-    assert_eq!(self.outputs.len(), 1, "invariant: only one output");
+  fn select_inputs(mut self, inputs: Vec<OutPoint>) -> Result<Self> {
+    if !inputs.iter().any(|input| {
+      self.ranges[input]
+        .iter()
+        .any(|(start, end)| self.ordinal.0 < *end && self.ordinal.0 >= *start)
+    }) {
+      return Err(Error::NotInWallet(self.ordinal));
+    }
 
-    assert_eq!(
-      self.outputs[0].0, self.recipient,
-      "invariant: first output is recipient"
+    let mut amount = Amount::ZERO;
+    for input in inputs {
+      self.utxos.remove(&input);
+      amount += Amount::from_sat(
+        self.ranges[&input]
+          .iter()
+          .map(|(start, end)| end - start)
+          .sum(),
+      );
+      self.inputs.push(input);
+    }
+
+    self.outputs.push((self.recipient.clone(), amount));
+
+    Ok(self)
+  }
+
+  /// Spend every unextracted output, and align each rare ordinal in them in
+  /// turn, each with its own change address as recipient.
+  fn select_rare_ordinals(
+    ranges: BTreeMap<OutPoint, Vec<(u64, u64)>>,
+    mut change: Vec<Address>,
+  ) -> Result<Self> {
+    let inputs = Self::unextracted_outputs(&ranges);
+
+    let mut rare_ordinals = inputs
+      .iter()
+      .flat_map(|input| &ranges[input])
+      .map(|(start, _end)| Ordinal(*start))
+      .filter(|ordinal| ordinal.rarity() > Rarity::Common)
+      .collect::<Vec<Ordinal>>()
+      .into_iter();
+
+    let ordinal = rare_ordinals.next().ok_or(Error::NothingToExtract)?;
+    let recipient = change.pop().expect("not enough change addresses");
+
+    let mut builder = Self::new(
+      ranges,
+      ordinal,
+      recipient.clone(),
+      change,
+      Self::TARGET_FEE_RATE,
     );
+    builder.change_addresses.insert(recipient);
 
-    let ordinal_offset = self.calculate_ordinal_offset();
-    if ordinal_offset != 0 {
-      self.outputs.insert(
-        0,
-        (
-          self
-            .unused_change_addresses
-            .pop()
-            .expect("not enough change addresses"),
-          Amount::from_sat(ordinal_offset),
-        ),
+    builder = builder
+      .select_inputs(inputs)?
+      .align_ordinal()
+      .pad_alignment_output()?;
+
+    for ordinal in rare_ordinals {
+      builder = builder
+        .strip_excess_postage()
+        .retarget(ordinal)
+        .align_ordinal()
+        .pad_alignment_output()?;
+    }
+
+    Ok(builder)
+  }
+
+  /// Make `ordinal` the ordinal being sent, to a new change address, unless
+  /// it is closer than the dust limit to the start of the current recipient's
+  /// output, in which case it can't be separated and stays there.
+  fn retarget(mut self, ordinal: Ordinal) -> Self {
+    let (vout, offset) = self.locate_ordinal(ordinal);
+
+    if self.outputs[vout].0 == self.recipient
+      && Amount::from_sat(offset) < self.recipient.script_pubkey().dust_value()
+    {
+      return self;
+    }
+
+    self.extracted.push((self.ordinal, self.recipient.clone()));
+    self.ordinal = ordinal;
+    self.recipient = self.next_change_address();
+
+    self
+  }
+
+  /// Split the output containing the ordinal so that the ordinal is at the
+  /// first position of the recipient's output. The part before the ordinal
+  /// keeps the address of the output it was split from, or goes to a new
+  /// change address if that was the recipient.
+  fn align_ordinal(mut self) -> Self {
+    let (vout, offset) = self.locate_ordinal(self.ordinal);
+
+    if offset == 0 {
+      let address = self.outputs[vout].0.clone();
+      if address != self.recipient {
+        self.outputs[vout].0 = self.recipient.clone();
+        self.unused_change_addresses.push(address);
+      }
+    } else {
+      let address = if self.outputs[vout].0 == self.recipient {
+        self.next_change_address()
+      } else {
+        self.outputs[vout].0.clone()
+      };
+      self.outputs[vout] = (
+        self.recipient.clone(),
+        self.outputs[vout].1 - Amount::from_sat(offset),
       );
-      self.outputs.last_mut().expect("no output").1 -= Amount::from_sat(ordinal_offset);
+      self
+        .outputs
+        .insert(vout, (address, Amount::from_sat(offset)));
     }
 
     self
   }
 
+  /// Alignment outputs under the dust limit are padded with a cardinal input
+  /// if they are the first output, and otherwise merged into the preceding
+  /// output, which then carries a little extra postage.
   fn pad_alignment_output(mut self) -> Result<Self> {
-    if self.outputs[0].0 != self.recipient {
+    let vout = self.recipient_vout();
+    if vout > 0 {
       let dust_limit = self.recipient.script_pubkey().dust_value();
-      if self.outputs[0].1 < dust_limit {
-        let (utxo, size) = self.select_cardinal_utxo(dust_limit - self.outputs[0].1)?;
-        self.inputs.insert(0, utxo);
-        self.outputs[0].1 += size;
+      if self.outputs[vout - 1].1 < dust_limit {
+        if vout == 1 {
+          let (utxo, size) = self.select_cardinal_utxo(dust_limit - self.outputs[0].1)?;
+          self.inputs.insert(0, utxo);
+          self.outputs[0].1 += size;
+        } else {
+          let (address, amount) = self.outputs.remove(vout - 1);
+          self.outputs[vout - 2].1 += amount;
+          self.unused_change_addresses.push(address);
+        }
       }
     }
 
@@ -188,37 +375,30 @@ impl TransactionBuilder {
     Ok(self)
   }
 
+  /// Outputs after the recipient's were split from its postage when
+  /// stripping the postage of a previous rare ordinal during extraction, so
+  /// they are merged back into it before stripping, and their change
+  /// addresses are reused.
   fn strip_excess_postage(mut self) -> Self {
-    let ordinal_offset = self.calculate_ordinal_offset();
-    let total_output_amount = self
-      .outputs
-      .iter()
-      .map(|(_address, amount)| *amount)
-      .sum::<Amount>();
+    let vout = self.recipient_vout();
 
-    self
-      .outputs
-      .iter()
-      .position(|(address, _amount)| address == &self.recipient)
-      .expect("couldn't find output that contains the index");
+    for (address, amount) in self.outputs.split_off(vout + 1) {
+      self.outputs[vout].1 += amount;
+      self.unused_change_addresses.push(address);
+    }
 
-    let postage = total_output_amount - Amount::from_sat(ordinal_offset);
+    let postage = self.outputs[vout].1;
     if postage > Self::MAX_POSTAGE {
-      self.outputs.last_mut().expect("no outputs found").1 = Self::TARGET_POSTAGE;
-      self.outputs.push((
-        self
-          .unused_change_addresses
-          .pop()
-          .expect("not enough change addresses"),
-        postage - Self::TARGET_POSTAGE,
-      ));
+      self.outputs[vout].1 = Self::TARGET_POSTAGE;
+      let change = self.next_change_address();
+      self.outputs.push((change, postage - Self::TARGET_POSTAGE));
     }
 
     self
   }
 
   fn deduct_fee(mut self) -> Self {
-    let ordinal_offset = self.calculate_ordinal_offset();
+    let ordinal_offset = self.calculate_ordinal_offset(self.ordinal);
 
     let fee = self.estimate_fee();
 
@@ -243,6 +423,35 @@ impl TransactionBuilder {
     self
   }
 
+  /// Replacements must pay at least the fee of the transaction they replace,
+  /// plus the incremental relay fee for their own size, which grows if a
+  /// cardinal input was added (BIP125 rules 3 and 4).
+  fn check_replacement_fee(self, original_fee: Amount) -> Result<Self> {
+    let input_amount = self
+      .inputs
+      .iter()
+      .flat_map(|outpoint| &self.ranges[outpoint])
+      .map(|(start, end)| Amount::from_sat(end - start))
+      .sum::<Amount>();
+
+    let output_amount = self
+      .outputs
+      .iter()
+      .map(|(_address, amount)| *amount)
+      .sum::<Amount>();
+
+    let fee = input_amount - output_amount;
+
+    let minimum =
+      original_fee + Self::INCREMENTAL_RELAY_FEE_RATE * self.estimate_vsize().try_into().unwrap();
+
+    if fee < minimum {
+      return Err(Error::ReplacementFeeTooLow { fee, minimum });
+    }
+
+    Ok(self)
+  }
+
   /// Estimate the size in virtual bytes of the transaction being built. Since
   /// we don't know the size of the input script sigs and witnesses, assume
   /// they are P2PKH, so that we get a worst case estimate, since it's probably
@@ -250,12 +459,14 @@ impl TransactionBuilder {
   /// underestimate and never get the transaction confirmed, or, even worse, be
   /// under the minimum relay fee and never even get relayed.
   fn estimate_vsize(&self) -> usize {
+    Self::estimate_vsize_of(self.inputs.len(), &self.outputs)
+  }
+
+  pub(crate) fn estimate_vsize_of(inputs: usize, outputs: &[(Address, Amount)]) -> usize {
     Transaction {
       version: 1,
       lock_time: PackedLockTime::ZERO,
-      input: self
-        .inputs
-        .iter()
+      input: (0..inputs)
         .map(|_| TxIn {
           previous_output: OutPoint::null(),
           script_sig: script::Builder::new()
@@ -266,8 +477,7 @@ impl TransactionBuilder {
           witness: Witness::new(),
         })
         .collect(),
-      output: self
-        .outputs
+      output: outputs
         .iter()
         .map(|(address, amount)| TxOut {
           value: amount.to_sat(),
@@ -279,12 +489,10 @@ impl TransactionBuilder {
   }
 
   fn estimate_fee(&self) -> Amount {
-    Self::TARGET_FEE_RATE * self.estimate_vsize().try_into().unwrap()
+    self.fee_rate * self.estimate_vsize().try_into().unwrap()
   }
 
   fn build(self) -> Result<Transaction> {
-    let ordinal = self.ordinal.n();
-    let recipient = self.recipient.script_pubkey();
     let transaction = Transaction {
       version: 1,
       lock_time: PackedLockTime::ZERO,
@@ -294,7 +502,7 @@ impl TransactionBuilder {
         .map(|outpoint| TxIn {
           previous_output: *outpoint,
           script_sig: Script::new(),
-          sequence: Sequence::MAX,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         })
         .collect(),
@@ -308,67 +516,84 @@ impl TransactionBuilder {
         .collect(),
     };
 
-    let outpoint = self
-      .ranges
+    let targets = self
+      .extracted
       .iter()
-      .find(|(_outpoint, ranges)| {
-        ranges
+      .map(|(ordinal, recipient)| (ordinal.n(), recipient.script_pubkey()))
+      .chain(std::iter::once((
+        self.ordinal.n(),
+        self.recipient.script_pubkey(),
+      )))
+      .collect::<Vec<(u64, Script)>>();
+
+    let mut ordinal_offsets = Vec::new();
+    for (ordinal, recipient) in &targets {
+      let ordinal = *ordinal;
+
+      let outpoint = self
+        .ranges
+        .iter()
+        .find(|(_outpoint, ranges)| {
+          ranges
+            .iter()
+            .any(|(start, end)| ordinal >= *start && ordinal < *end)
+        })
+        .expect("invariant: ordinal is contained in utxo ranges");
+
+      assert_eq!(
+        transaction
+          .input
           .iter()
-          .any(|(start, end)| ordinal >= *start && ordinal < *end)
-      })
-      .expect("invariant: ordinal is contained in utxo ranges");
+          .filter(|tx_in| tx_in.previous_output == *outpoint.0)
+          .count(),
+        1,
+        "invariant: inputs spend ordinal"
+      );
 
-    assert_eq!(
-      transaction
+      let mut ordinal_offset = 0;
+      let mut found = false;
+      for (start, end) in transaction
         .input
         .iter()
-        .filter(|tx_in| tx_in.previous_output == *outpoint.0)
-        .count(),
-      1,
-      "invariant: inputs spend ordinal"
-    );
-
-    let mut ordinal_offset = 0;
-    let mut found = false;
-    for (start, end) in transaction
-      .input
-      .iter()
-      .flat_map(|tx_in| &self.ranges[&tx_in.previous_output])
-    {
-      if ordinal >= *start && ordinal < *end {
-        ordinal_offset += ordinal - start;
-        found = true;
-        break;
-      } else {
-        ordinal_offset += end - start;
+        .flat_map(|tx_in| &self.ranges[&tx_in.previous_output])
+      {
+        if ordinal >= *start && ordinal < *end {
+          ordinal_offset += ordinal - start;
+          found = true;
+          break;
+        } else {
+          ordinal_offset += end - start;
+        }
       }
-    }
-    assert!(found, "invariant: ordinal is found in inputs");
-
-    let mut output_end = 0;
-    let mut found = false;
-    for tx_out in &transaction.output {
-      output_end += tx_out.value;
-      if output_end > ordinal_offset {
-        assert_eq!(
-          tx_out.script_pubkey, recipient,
-          "invariant: ordinal is sent to recipient"
-        );
-        found = true;
-        break;
+      assert!(found, "invariant: ordinal is found in inputs");
+
+      let mut output_end = 0;
+      let mut found = false;
+      for tx_out in &transaction.output {
+        output_end += tx_out.value;
+        if output_end > ordinal_offset {
+          assert_eq!(
+            &tx_out.script_pubkey, recipient,
+            "invariant: ordinal is sent to recipient"
+          );
+          found = true;
+          break;
+        }
       }
-    }
-    assert!(found, "invariant: ordinal is found in outputs");
+      assert!(found, "invariant: ordinal is found in outputs");
 
-    assert_eq!(
-      transaction
-        .output
-        .iter()
-        .filter(|tx_out| tx_out.script_pubkey == self.recipient.script_pubkey())
-        .count(),
-      1,
-      "invariant: recipient address appears exactly once in outputs",
-    );
+      assert_eq!(
+        transaction
+          .output
+          .iter()
+          .filter(|tx_out| &tx_out.script_pubkey == recipient)
+          .count(),
+        1,
+        "invariant: recipient address appears exactly once in outputs",
+      );
+
+      ordinal_offsets.push(ordinal_offset);
+    }
 
     assert!(
       self
@@ -385,7 +610,11 @@ impl TransactionBuilder {
 
     let mut offset = 0;
     for output in &transaction.output {
-      if output.script_pubkey == self.recipient.script_pubkey() {
+      if let Some(ordinal_offset) = targets
+        .iter()
+        .position(|(_ordinal, recipient)| &output.script_pubkey == recipient)
+        .map(|target| ordinal_offsets[target])
+      {
         assert!(
           Amount::from_sat(output.value) < Self::MAX_POSTAGE,
           "invariant: excess postage is stripped"
@@ -421,7 +650,7 @@ impl TransactionBuilder {
     }
 
     let fee_rate = fee.to_sat() as f64 / self.estimate_vsize() as f64;
-    let target_fee_rate = Self::TARGET_FEE_RATE.to_sat() as f64;
+    let target_fee_rate = self.fee_rate.to_sat() as f64;
     assert!(
       fee_rate == target_fee_rate,
       "invariant: fee rate is equal to target fee rate: actual fee rate: {} target_fee rate: {}",
@@ -458,9 +687,15 @@ impl TransactionBuilder {
       offset += output.value;
     }
 
+    // Rare ordinals sent to one of our own change addresses aren't lost
+    let external = !self.change_addresses.contains(&self.recipient);
+
     for (rare_ordinal, offset) in &rare_ordinals {
-      if rare_ordinal != &self.ordinal {
-        if offset >= &recipient_range.0 && offset < &recipient_range.1 {
+      if targets
+        .iter()
+        .all(|(ordinal, _recipient)| *ordinal != rare_ordinal.n())
+      {
+        if external && offset >= &recipient_range.0 && offset < &recipient_range.1 {
           return Err(Error::RareOrdinalLostToRecipient(*rare_ordinal));
         } else if offset >= &(total_input_amount - fee.to_sat()) {
           return Err(Error::RareOrdinalLostToFee(*rare_ordinal));
@@ -471,11 +706,11 @@ impl TransactionBuilder {
     Ok(transaction)
   }
 
-  fn calculate_ordinal_offset(&self) -> u64 {
+  fn calculate_ordinal_offset(&self, ordinal: Ordinal) -> u64 {
     let mut ordinal_offset = 0;
     for (start, end) in self.inputs.iter().flat_map(|input| &self.ranges[input]) {
-      if self.ordinal.0 >= *start && self.ordinal.0 < *end {
-        ordinal_offset += self.ordinal.0 - start;
+      if ordinal.0 >= *start && ordinal.0 < *end {
+        ordinal_offset += ordinal.0 - start;
         return ordinal_offset;
       } else {
         ordinal_offset += end - start;
@@ -484,18 +719,53 @@ impl TransactionBuilder {
     panic!("Could not find ordinal in inputs");
   }
 
+  /// The output containing `ordinal`, and the ordinal's offset within it.
+  fn locate_ordinal(&self, ordinal: Ordinal) -> (usize, u64) {
+    let mut offset = self.calculate_ordinal_offset(ordinal);
+    for (vout, (_address, amount)) in self.outputs.iter().enumerate() {
+      if offset < amount.to_sat() {
+        return (vout, offset);
+      }
+      offset -= amount.to_sat();
+    }
+    panic!("Could not find ordinal in outputs");
+  }
+
+  fn recipient_vout(&self) -> usize {
+    self
+      .outputs
+      .iter()
+      .position(|(address, _amount)| address == &self.recipient)
+      .expect("couldn't find output that contains the index")
+  }
+
+  fn next_change_address(&mut self) -> Address {
+    self
+      .unused_change_addresses
+      .pop()
+      .expect("not enough change addresses")
+  }
+
   fn select_cardinal_utxo(&mut self, minimum_amount: Amount) -> Result<(OutPoint, Amount)> {
+    Self::select_cardinal_utxo_from(&mut self.utxos, &self.ranges, minimum_amount)
+  }
+
+  pub(crate) fn select_cardinal_utxo_from(
+    utxos: &mut BTreeSet<OutPoint>,
+    ranges: &BTreeMap<OutPoint, Vec<(u64, u64)>>,
+    minimum_amount: Amount,
+  ) -> Result<(OutPoint, Amount)> {
     let mut found = None;
 
-    for utxo in &self.utxos {
-      if self.ranges[utxo]
+    for utxo in utxos.iter() {
+      if ranges[utxo]
         .iter()
         .any(|(start, _end)| Ordinal(*start).rarity() > Rarity::Common)
       {
         continue;
       }
 
-      let amount = self.ranges[utxo]
+      let amount = ranges[utxo]
         .iter()
         .map(|(start, end)| Amount::from_sat(end - start))
         .sum::<Amount>();
@@ -508,7 +778,7 @@ impl TransactionBuilder {
 
     let (utxo, amount) = found.ok_or(Error::NotEnoughCardinalUtxos)?;
 
-    self.utxos.remove(&utxo);
+    utxos.remove(&utxo);
 
     Ok((utxo, amount))
   }
@@ -518,36 +788,8 @@ impl TransactionBuilder {
 mod tests {
   use {super::Error, super::*};
 
-  fn recipient() -> Address {
-    "tb1q6en7qjxgw4ev8xwx94pzdry6a6ky7wlfeqzunz"
-      .parse()
-      .unwrap()
-  }
-
-  fn change(n: u64) -> Address {
-    match n {
-      0 => "tb1qjsv26lap3ffssj6hfy8mzn0lg5vte6a42j75ww",
-      1 => "tb1qakxxzv9n7706kc3xdcycrtfv8cqv62hnwexc0l",
-      _ => panic!(),
-    }
-    .parse()
-    .unwrap()
-  }
-
-  fn tx_in(previous_output: OutPoint) -> TxIn {
-    TxIn {
-      previous_output,
-      script_sig: Script::new(),
-      sequence: Sequence::MAX,
-      witness: Witness::new(),
-    }
-  }
-
-  fn tx_out(value: u64, address: Address) -> TxOut {
-    TxOut {
-      value,
-      script_pubkey: address.script_pubkey(),
-    }
+  fn changes() -> Vec<Address> {
+    (0..4).map(change).collect()
   }
 
   #[test]
@@ -563,6 +805,7 @@ mod tests {
       Ordinal(51 * COIN_VALUE),
       recipient(),
       vec![change(0), change(1)],
+      TransactionBuilder::TARGET_FEE_RATE,
     )
     .select_ordinal()
     .unwrap();
@@ -596,6 +839,8 @@ mod tests {
       recipient: recipient(),
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      extracted: Vec::new(),
+      fee_rate: TransactionBuilder::TARGET_FEE_RATE,
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -649,6 +894,7 @@ mod tests {
       Ordinal(14_950),
       recipient(),
       vec![change(0), change(1)],
+      TransactionBuilder::TARGET_FEE_RATE,
     )
     .select_ordinal()
     .unwrap()
@@ -748,6 +994,7 @@ mod tests {
       Ordinal(2),
       recipient(),
       vec![change(0), change(1)],
+      TransactionBuilder::TARGET_FEE_RATE,
     )
     .build()
     .unwrap();
@@ -761,6 +1008,7 @@ mod tests {
       Ordinal(2),
       recipient(),
       vec![change(0), change(1)],
+      TransactionBuilder::TARGET_FEE_RATE,
     )
     .build()
     .unwrap();
@@ -774,6 +1022,7 @@ mod tests {
       Ordinal(2),
       recipient(),
       vec![change(0), change(1)],
+      TransactionBuilder::TARGET_FEE_RATE,
     )
     .select_ordinal()
     .unwrap();
@@ -793,6 +1042,7 @@ mod tests {
       Ordinal(2),
       recipient(),
       vec![change(0), change(1)],
+      TransactionBuilder::TARGET_FEE_RATE,
     )
     .select_ordinal()
     .unwrap();
@@ -835,6 +1085,7 @@ mod tests {
       Ordinal(0),
       recipient(),
       vec![change(0), change(1)],
+      TransactionBuilder::TARGET_FEE_RATE,
     )
     .select_ordinal()
     .unwrap()
@@ -895,6 +1146,7 @@ mod tests {
       Ordinal(3_333),
       recipient(),
       vec![change(0), change(1)],
+      TransactionBuilder::TARGET_FEE_RATE,
     )
     .select_ordinal()
     .unwrap()
@@ -919,6 +1171,7 @@ mod tests {
       Ordinal(1),
       recipient(),
       vec![change(0), change(1)],
+      TransactionBuilder::TARGET_FEE_RATE,
     )
     .select_ordinal()
     .unwrap()
@@ -941,6 +1194,7 @@ mod tests {
       Ordinal(3_333),
       recipient(),
       vec![change(0), change(1)],
+      TransactionBuilder::TARGET_FEE_RATE,
     )
     .select_ordinal()
     .unwrap()
@@ -960,6 +1214,7 @@ mod tests {
       Ordinal(0),
       recipient(),
       vec![change(0), change(1)],
+      TransactionBuilder::TARGET_FEE_RATE,
     )
     .select_ordinal()
     .unwrap()
@@ -1007,6 +1262,8 @@ mod tests {
       recipient: recipient(),
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      extracted: Vec::new(),
+      fee_rate: TransactionBuilder::TARGET_FEE_RATE,
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -1033,6 +1290,8 @@ mod tests {
       recipient: recipient(),
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      extracted: Vec::new(),
+      fee_rate: TransactionBuilder::TARGET_FEE_RATE,
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -1073,4 +1332,386 @@ mod tests {
       Err(Error::RareOrdinalLostToFee(Ordinal(0)))
     )
   }
+
+  #[test]
+  fn replacement_pays_higher_fee_from_change() {
+    let utxos = vec![(outpoint(1), vec![(10_000, 40_000)])];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_replacement(
+        utxos.into_iter().collect(),
+        vec![outpoint(1)],
+        Ordinal(10_000),
+        recipient(),
+        vec![change(0), change(1)],
+        Amount::from_sat(2),
+        Amount::from_sat(251),
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![tx_out(10_000, recipient()), tx_out(19_498, change(1))],
+      })
+    )
+  }
+
+  #[test]
+  fn replacement_must_pay_original_fee_and_relay_fee_for_its_size() {
+    let utxos = vec![(outpoint(1), vec![(10_000, 40_000)])];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_replacement(
+        utxos.into_iter().collect(),
+        vec![outpoint(1)],
+        Ordinal(10_000),
+        recipient(),
+        vec![change(0), change(1)],
+        Amount::from_sat(2),
+        Amount::from_sat(252),
+      ),
+      Err(Error::ReplacementFeeTooLow {
+        fee: Amount::from_sat(502),
+        minimum: Amount::from_sat(503),
+      })
+    )
+  }
+
+  #[test]
+  fn replacement_adds_cardinal_input_if_necessary() {
+    let utxos = vec![
+      (outpoint(1), vec![(10_000, 11_000)]),
+      (outpoint(2), vec![(30_000, 40_000)]),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_replacement(
+        utxos.into_iter().collect(),
+        vec![outpoint(1)],
+        Ordinal(10_000),
+        recipient(),
+        vec![change(0), change(1)],
+        Amount::from_sat(5),
+        Amount::from_sat(250),
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+        output: vec![tx_out(9_005, recipient())],
+      })
+    )
+  }
+
+  #[test]
+  fn replacement_inputs_must_contain_ordinal() {
+    let utxos = vec![
+      (outpoint(1), vec![(10_000, 11_000)]),
+      (outpoint(2), vec![(30_000, 40_000)]),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_replacement(
+        utxos.into_iter().collect(),
+        vec![outpoint(2)],
+        Ordinal(10_000),
+        recipient(),
+        vec![change(0), change(1)],
+        Amount::from_sat(5),
+        Amount::from_sat(250),
+      ),
+      Err(Error::NotInWallet(Ordinal(10_000)))
+    )
+  }
+
+  #[test]
+  fn rare_ordinal_at_start_of_output_is_extracted() {
+    let utxos = vec![(outpoint(1), vec![(0, 100_000)])];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![tx_out(10_000, change(3)), tx_out(89_749, change(2))],
+      })
+    )
+  }
+
+  #[test]
+  fn rare_ordinal_inside_output_is_aligned_for_extraction() {
+    let utxos = vec![(
+      outpoint(1),
+      vec![(50 * COIN_VALUE - 5_000, 50 * COIN_VALUE + 100_000)],
+    )];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![
+          tx_out(5_000, change(2)),
+          tx_out(10_000, change(3)),
+          tx_out(89_718, change(1)),
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn extraction_alignment_output_under_dust_limit_is_padded() {
+    let utxos = vec![
+      (
+        outpoint(1),
+        vec![(50 * COIN_VALUE - 100, 50 * COIN_VALUE + 50_000)],
+      ),
+      (outpoint(2), vec![(10_000, 20_000)]),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(2)), tx_in(outpoint(1))],
+        output: vec![
+          tx_out(10_100, change(2)),
+          tx_out(10_000, change(3)),
+          tx_out(39_539, change(1)),
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn rare_ordinals_closer_than_dust_limit_share_output() {
+    let utxos = vec![(
+      outpoint(1),
+      vec![(0, 100), (50 * COIN_VALUE, 50 * COIN_VALUE + 50_000)],
+    )];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![tx_out(10_000, change(3)), tx_out(39_849, change(2))],
+      })
+    )
+  }
+
+  #[test]
+  fn multiple_rare_ordinals_are_extracted() {
+    let utxos = vec![(
+      outpoint(1),
+      vec![(0, 50_000), (50 * COIN_VALUE, 50 * COIN_VALUE + 50_000)],
+    )];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![
+          tx_out(10_000, change(3)),
+          tx_out(40_000, change(2)),
+          tx_out(10_000, change(1)),
+          tx_out(39_687, change(0)),
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn postage_is_cut_short_by_next_rare_ordinal() {
+    let utxos = vec![(
+      outpoint(1),
+      vec![(0, 5_000), (50 * COIN_VALUE, 50 * COIN_VALUE + 50_000)],
+    )];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![
+          tx_out(5_000, change(3)),
+          tx_out(10_000, change(1)),
+          tx_out(39_718, change(2)),
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn extraction_change_under_dust_limit_is_added_to_previous_postage() {
+    let utxos = vec![(
+      outpoint(1),
+      vec![(0, 10_100), (50 * COIN_VALUE, 50 * COIN_VALUE + 50_000)],
+    )];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![
+          tx_out(10_100, change(3)),
+          tx_out(10_000, change(1)),
+          tx_out(39_718, change(2)),
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn extraction_fee_is_deducted_from_postage() {
+    let utxos = vec![(
+      outpoint(1),
+      vec![(0, 30_000), (50 * COIN_VALUE, 50 * COIN_VALUE + 10_100)],
+    )];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![
+          tx_out(10_000, change(3)),
+          tx_out(20_000, change(2)),
+          tx_out(9_818, change(1)),
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn extraction_postage_is_added_when_required() {
+    let utxos = vec![
+      (
+        outpoint(1),
+        vec![(0, 30_000), (50 * COIN_VALUE, 50 * COIN_VALUE + 400)],
+      ),
+      (outpoint(2), vec![(100_000, 110_000)]),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+        output: vec![
+          tx_out(10_000, change(3)),
+          tx_out(20_000, change(2)),
+          tx_out(9_939, change(1)),
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn extracted_outputs_are_not_spent() {
+    let utxos = vec![
+      (outpoint(1), vec![(0, 10_000)]),
+      (
+        outpoint(2),
+        vec![(50 * COIN_VALUE, 50 * COIN_VALUE + 100_000)],
+      ),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(2))],
+        output: vec![tx_out(10_000, change(3)), tx_out(89_749, change(2))],
+      })
+    )
+  }
+
+  #[test]
+  fn nothing_to_extract() {
+    let utxos = vec![
+      (outpoint(1), vec![(0, 10_000)]),
+      (outpoint(2), vec![(10_000, 20_000)]),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Err(Error::NothingToExtract),
+    )
+  }
+
+  #[test]
+  fn not_enough_cardinal_utxos_to_add_extraction_postage() {
+    let utxos = vec![(
+      outpoint(1),
+      vec![(0, 30_000), (50 * COIN_VALUE, 50 * COIN_VALUE + 400)],
+    )];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Err(Error::NotEnoughCardinalUtxos),
+    )
+  }
+
+  #[test]
+  fn not_enough_cardinal_utxos_to_pad_extraction_alignment_output() {
+    let utxos = vec![(
+      outpoint(1),
+      vec![(50 * COIN_VALUE - 100, 50 * COIN_VALUE + 50_000)],
+    )];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_extraction(utxos.into_iter().collect(), changes()),
+      Err(Error::NotEnoughCardinalUtxos),
+    )
+  }
+
+  #[test]
+  #[should_panic(expected = "invariant: ordinal is at first position in recipient output")]
+  fn invariant_extracted_ordinals_are_aligned() {
+    let utxos = vec![(
+      outpoint(1),
+      vec![(0, 50_000), (50 * COIN_VALUE, 50 * COIN_VALUE + 50_000)],
+    )];
+
+    let mut builder =
+      TransactionBuilder::select_rare_ordinals(utxos.into_iter().collect(), changes())
+        .unwrap()
+        .add_postage()
+        .unwrap()
+        .strip_excess_postage()
+        .deduct_fee();
+
+    builder.outputs[1].1 -= Amount::from_sat(1);
+    builder.outputs[2].1 += Amount::from_sat(1);
+
+    builder.build().unwrap();
+  }
+
+  #[test]
+  #[should_panic(expected = "invariant: excess postage is stripped")]
+  fn invariant_extracted_postage_is_stripped() {
+    let utxos = vec![(
+      outpoint(1),
+      vec![(0, 50_000), (50 * COIN_VALUE, 50 * COIN_VALUE + 50_000)],
+    )];
+
+    TransactionBuilder::select_rare_ordinals(utxos.into_iter().collect(), changes())
+      .unwrap()
+      .deduct_fee()
+      .build()
+      .unwrap();
+  }
 }
@@ -0,0 +1,49 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Unlock {
+  #[clap(
+    help = "Unlock <OUTPOINT>. Unlocks all outputs locked by `ord wallet lock-rare` if omitted."
+  )]
+  outpoints: Vec<OutPoint>,
+}
+
+impl Unlock {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let backend = Backend::for_command(&options, &index, "ord wallet unlock")?;
+
+    let mut persisted = load_locked_outputs(&options)?;
+
+    let requested = if self.outpoints.is_empty() {
+      persisted.clone()
+    } else {
+      self.outpoints.into_iter().collect()
+    };
+
+    let locked = backend.list_locked(&options)?;
+
+    let unlock = requested
+      .intersection(&locked)
+      .cloned()
+      .collect::<Vec<OutPoint>>();
+
+    if !unlock.is_empty() && !backend.unlock_unspent(&unlock)? {
+      bail!("failed to unlock outputs");
+    }
+
+    for outpoint in &requested {
+      persisted.remove(outpoint);
+    }
+
+    save_locked_outputs(&options, &persisted)?;
+
+    for outpoint in unlock {
+      println!("{outpoint}");
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,62 @@
+use {super::*, bitcoincore_rpc::Client};
+
+mod add;
+mod list;
+
+#[derive(Deserialize)]
+struct JsonScanResult {
+  unspents: Vec<JsonUnspent>,
+}
+
+#[derive(Deserialize)]
+struct JsonUnspent {
+  txid: Txid,
+  vout: u32,
+  #[serde(rename = "scriptPubKey")]
+  script_pubkey: String,
+  height: u64,
+}
+
+/// Outputs in blocks that were indexed before their scripts were watched are
+/// not tracked by the index, so existing outputs are found by scanning
+/// Bitcoin Core's UTXO set for `scan_objects`, and tracked with
+/// `Index::watch_output`. Returns the outputs found.
+pub(crate) fn scan(
+  client: &Client,
+  index: &Index,
+  scan_objects: Vec<serde_json::Value>,
+) -> Result<Vec<(OutPoint, Script)>> {
+  let scan = client.call::<JsonScanResult>(
+    "scantxoutset",
+    &["start".into(), serde_json::Value::Array(scan_objects)],
+  )?;
+
+  let mut outputs = Vec::new();
+
+  for unspent in scan.unspents {
+    let outpoint = OutPoint::new(unspent.txid, unspent.vout);
+    let script_pubkey = Script::from(hex::decode(unspent.script_pubkey)?);
+
+    if let Some(List::Unspent(_)) = index.list(outpoint)? {
+      index.watch_output(outpoint, &script_pubkey, unspent.height)?;
+      outputs.push((outpoint, script_pubkey));
+    }
+  }
+
+  Ok(outputs)
+}
+
+#[derive(Debug, Parser)]
+pub(crate) enum Watch {
+  Add(add::Add),
+  List,
+}
+
+impl Watch {
+  pub(crate) fn run(self, options: Options) -> Result {
+    match self {
+      Self::Add(add) => add.run(options),
+      Self::List => list::run(options),
+    }
+  }
+}
@@ -0,0 +1,69 @@
+use super::*;
+
+#[derive(Deserialize)]
+struct JsonDescriptorInfo {
+  descriptor: String,
+  isrange: bool,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Add {
+  #[clap(help = "Watch <ADDRESS_OR_DESCRIPTOR>, an address or an output descriptor.")]
+  address_or_descriptor: String,
+  #[clap(
+    long,
+    default_value = "1000",
+    help = "Watch the first <RANGE> addresses of ranged descriptors."
+  )]
+  range: u32,
+}
+
+impl Add {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = options.bitcoin_rpc_client()?;
+
+    let descriptor = match Address::from_str(&self.address_or_descriptor) {
+      Ok(address) => {
+        if !address.is_valid_for_network(options.chain().network()) {
+          bail!("Address `{address}` is not valid for {}", options.chain());
+        }
+        format!("addr({address})")
+      }
+      Err(_) => self.address_or_descriptor,
+    };
+
+    let info = client
+      .call::<JsonDescriptorInfo>("getdescriptorinfo", &[descriptor.into()])
+      .context("invalid descriptor")?;
+
+    let range = serde_json::json!([0, self.range.saturating_sub(1)]);
+
+    let addresses = client.call::<Vec<Address>>(
+      "deriveaddresses",
+      &if info.isrange {
+        vec![info.descriptor.clone().into(), range.clone()]
+      } else {
+        vec![info.descriptor.clone().into()]
+      },
+    )?;
+
+    for address in &addresses {
+      index.watch(&address.script_pubkey())?;
+    }
+
+    super::scan(
+      &client,
+      &index,
+      vec![serde_json::json!({ "desc": info.descriptor, "range": range })],
+    )?;
+
+    for address in addresses {
+      println!("{address}");
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,95 @@
+use super::*;
+
+#[derive(Serialize)]
+struct WatchedRange {
+  output: OutPoint,
+  address: String,
+  start: u64,
+  size: u64,
+  rarity: Rarity,
+  name: String,
+}
+
+impl Output for WatchedRange {
+  fn tsv(&self) -> String {
+    format!(
+      "{}\t{}\t{}\t{}\t{}\t{}\n",
+      self.output, self.address, self.start, self.size, self.rarity, self.name
+    )
+  }
+}
+
+pub(crate) fn run(options: Options) -> Result {
+  let index = Index::open(&options)?;
+  index.update()?;
+
+  let mut outputs = Vec::new();
+  for (outpoint, script_pubkey) in index.watched_outputs()? {
+    match index.list(outpoint)? {
+      Some(List::Unspent(ranges)) => outputs.push((outpoint, script_pubkey, ranges)),
+      _ => bail!("watched output {outpoint} is not unspent according to index"),
+    }
+  }
+
+  options
+    .format()
+    .print(&watched_ranges(options.chain(), outputs))
+}
+
+fn watched_ranges(
+  chain: Chain,
+  outputs: Vec<(OutPoint, Script, Vec<(u64, u64)>)>,
+) -> Vec<WatchedRange> {
+  outputs
+    .into_iter()
+    .flat_map(|(output, script_pubkey, ranges)| {
+      let address = chain
+        .address_from_script(&script_pubkey)
+        .map(|address| address.to_string())
+        .unwrap_or_else(|_| script_pubkey.to_string());
+
+      ranges.into_iter().map(move |(start, end)| {
+        let ordinal = Ordinal(start);
+        WatchedRange {
+          output,
+          address: address.clone(),
+          start,
+          size: end - start,
+          rarity: ordinal.rarity(),
+          name: ordinal.name(),
+        }
+      })
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn ranges_are_listed_with_address() {
+    let address = "tb1qjsv26lap3ffssj6hfy8mzn0lg5vte6a42j75ww"
+      .parse::<Address>()
+      .unwrap();
+
+    assert_eq!(
+      Format::Tsv
+        .render(&watched_ranges(
+          Chain::Signet,
+          vec![(
+            outpoint(1),
+            address.script_pubkey(),
+            vec![(50 * COIN_VALUE, 50 * COIN_VALUE + 10), (10, 20)]
+          )]
+        ))
+        .unwrap(),
+      format!(
+        "{}\t{address}\t{}\t10\tuncommon\tnvtcsezkbth\n{}\t{address}\t10\t10\tcommon\tnvtdijuwxlf\n",
+        outpoint(1),
+        50 * COIN_VALUE,
+        outpoint(1),
+      )
+    );
+  }
+}
@@ -3,6 +3,8 @@ pub(crate) use {
   test_bitcoincore_rpc::TransactionTemplate, unindent::Unindent,
 };
 
+use bitcoin::Witness;
+
 macro_rules! assert_regex_match {
   ($string:expr, $pattern:expr $(,)?) => {
     let regex = Regex::new(&format!("^(?s){}$", $pattern)).unwrap();
@@ -26,3 +28,37 @@ pub(crate) fn outpoint(n: u64) -> OutPoint {
 
   format!("{}:{}", hex.repeat(64), n).parse().unwrap()
 }
+
+pub(crate) fn recipient() -> Address {
+  "tb1q6en7qjxgw4ev8xwx94pzdry6a6ky7wlfeqzunz"
+    .parse()
+    .unwrap()
+}
+
+pub(crate) fn change(n: u64) -> Address {
+  match n {
+    0 => "tb1qjsv26lap3ffssj6hfy8mzn0lg5vte6a42j75ww",
+    1 => "tb1qakxxzv9n7706kc3xdcycrtfv8cqv62hnwexc0l",
+    2 => "tb1qqvpsxqcrqvpsxqcrqvpsxqcrqvpsxqcr7mrzn4",
+    3 => "tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw",
+    _ => panic!(),
+  }
+  .parse()
+  .unwrap()
+}
+
+pub(crate) fn tx_in(previous_output: OutPoint) -> TxIn {
+  TxIn {
+    previous_output,
+    script_sig: Script::new(),
+    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+    witness: Witness::new(),
+  }
+}
+
+pub(crate) fn tx_out(value: u64, address: Address) -> TxOut {
+  TxOut {
+    value,
+    script_pubkey: address.script_pubkey(),
+  }
+}
@@ -0,0 +1,166 @@
+use {super::*, crate::index::assign_ordinal_ranges};
+
+/// The ordinal ranges carried by a transaction's inputs and where they end
+/// up, as far as the index knows. The index only keeps the ranges of unspent
+/// outputs, so the flow is only `complete` while every input is unspent, as
+/// for unconfirmed transactions. Otherwise only the ranges of unspent outputs
+/// are known.
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct TransactionFlow {
+  pub(crate) txid: Txid,
+  pub(crate) complete: bool,
+  pub(crate) inputs: Vec<InputFlow>,
+  pub(crate) outputs: Vec<OutputFlow>,
+  pub(crate) fee_ranges: Option<Vec<(u64, u64)>>,
+  pub(crate) rare_ordinals: Vec<Ordinal>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct InputFlow {
+  pub(crate) outpoint: OutPoint,
+  pub(crate) ranges: Option<Vec<(u64, u64)>>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct OutputFlow {
+  pub(crate) outpoint: OutPoint,
+  pub(crate) value: u64,
+  pub(crate) ranges: Option<Vec<(u64, u64)>>,
+}
+
+impl TransactionFlow {
+  /// `input_ranges` and `output_ranges` hold the ranges the index has for
+  /// each input's previous output and each output, or `None` where they are
+  /// spent or unknown. When the ranges of every input are known, output and
+  /// fee ranges are assigned from them first-in-first-out, as the updater
+  /// does, so unconfirmed transactions can be shown too.
+  pub(crate) fn new(
+    transaction: &Transaction,
+    input_ranges: Vec<Option<Vec<(u64, u64)>>>,
+    output_ranges: Vec<Option<Vec<(u64, u64)>>>,
+  ) -> Self {
+    let txid = transaction.txid();
+
+    let assigned = if !input_ranges.is_empty() && input_ranges.iter().all(Option::is_some) {
+      let (outputs, fee_ranges) = assign_ordinal_ranges(
+        input_ranges.iter().flatten().flatten().copied().collect(),
+        transaction.output.iter().map(|output| output.value),
+      );
+      Some((outputs.into_iter().map(Some).collect(), fee_ranges))
+    } else {
+      None
+    };
+
+    let (output_ranges, fee_ranges) = match assigned {
+      Some((output_ranges, fee_ranges)) => (output_ranges, Some(fee_ranges)),
+      None => (output_ranges, None),
+    };
+
+    let rare_ordinals = output_ranges
+      .iter()
+      .flatten()
+      .flatten()
+      .chain(fee_ranges.iter().flatten())
+      .map(|(start, _end)| Ordinal(*start))
+      .filter(|ordinal| !ordinal.is_common())
+      .collect();
+
+    Self {
+      txid,
+      complete: fee_ranges.is_some(),
+      inputs: transaction
+        .input
+        .iter()
+        .zip(input_ranges)
+        .map(|(input, ranges)| InputFlow {
+          outpoint: input.previous_output,
+          ranges,
+        })
+        .collect(),
+      outputs: transaction
+        .output
+        .iter()
+        .zip(output_ranges)
+        .enumerate()
+        .map(|(vout, (output, ranges))| OutputFlow {
+          outpoint: OutPoint::new(txid, vout as u32),
+          value: output.value,
+          ranges,
+        })
+        .collect(),
+      fee_ranges,
+      rare_ordinals,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::PackedLockTime};
+
+  fn transaction(inputs: usize, values: &[u64]) -> Transaction {
+    Transaction {
+      version: 0,
+      lock_time: PackedLockTime(0),
+      input: (0..inputs)
+        .map(|vout| TxIn {
+          previous_output: OutPoint::new(Txid::all_zeros(), vout as u32),
+          script_sig: Script::new(),
+          sequence: Sequence::MAX,
+          witness: bitcoin::Witness::new(),
+        })
+        .collect(),
+      output: values
+        .iter()
+        .map(|value| TxOut {
+          value: *value,
+          script_pubkey: Script::new(),
+        })
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn ranges_are_assigned_when_inputs_are_known() {
+    let transaction = transaction(2, &[5, 10]);
+    let flow = TransactionFlow::new(
+      &transaction,
+      vec![
+        Some(vec![(50 * COIN_VALUE, 50 * COIN_VALUE + 10)]),
+        Some(vec![(10, 20)]),
+      ],
+      vec![None, None],
+    );
+
+    assert_eq!(
+      flow
+        .outputs
+        .iter()
+        .map(|output| output.ranges.clone())
+        .collect::<Vec<_>>(),
+      vec![
+        Some(vec![(50 * COIN_VALUE, 50 * COIN_VALUE + 5)]),
+        Some(vec![(50 * COIN_VALUE + 5, 50 * COIN_VALUE + 10), (10, 15)]),
+      ]
+    );
+
+    assert!(flow.complete);
+    assert_eq!(flow.fee_ranges, Some(vec![(15, 20)]));
+    assert_eq!(flow.rare_ordinals, vec![Ordinal(50 * COIN_VALUE)]);
+  }
+
+  #[test]
+  fn output_ranges_are_used_when_inputs_are_unknown() {
+    let transaction = transaction(2, &[5]);
+    let flow = TransactionFlow::new(
+      &transaction,
+      vec![Some(vec![(0, 5)]), None],
+      vec![Some(vec![(0, 5)])],
+    );
+
+    assert!(!flow.complete);
+    assert_eq!(flow.outputs[0].ranges, Some(vec![(0, 5)]));
+    assert_eq!(flow.fee_ranges, None);
+    assert_eq!(flow.rare_ordinals, vec![Ordinal(0)]);
+  }
+}
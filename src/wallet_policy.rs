@@ -0,0 +1,160 @@
+use super::*;
+
+/// Limits on what wallet commands may do, so that bugs in ord can't lose
+/// more than a user is willing to risk. Defaults depend on the chain, and
+/// may be overridden with flags or disabled entirely with
+/// `--i-understand-the-risks`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WalletPolicy {
+  pub(crate) allowed_wallet_names: Vec<String>,
+  pub(crate) chain: Chain,
+  pub(crate) max_balance: Option<u64>,
+  pub(crate) max_transaction_value: Option<u64>,
+}
+
+impl WalletPolicy {
+  pub(crate) fn permissive(chain: Chain) -> Self {
+    Self {
+      allowed_wallet_names: Vec::new(),
+      chain,
+      max_balance: None,
+      max_transaction_value: None,
+    }
+  }
+
+  /// Check that Bitcoin Core wallet `name` matches one of the allowed
+  /// names, where a trailing `*` matches any suffix.
+  pub(crate) fn check_wallet_name(&self, command: &str, name: &str) -> Result {
+    if self.allowed_wallet_names.is_empty()
+      || self
+        .allowed_wallet_names
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+          Some(prefix) => name.starts_with(prefix),
+          None => name == pattern,
+        })
+    {
+      return Ok(());
+    }
+
+    let allowed = self
+      .allowed_wallet_names
+      .iter()
+      .map(|pattern| match pattern.strip_suffix('*') {
+        Some(prefix) => format!("whose name starts with `{prefix}`"),
+        None => format!("named `{pattern}`"),
+      })
+      .collect::<Vec<String>>()
+      .join(" or ");
+
+    bail!(
+      "`{command}` may only be used on {} with a wallet {allowed}",
+      self.chain
+    );
+  }
+
+  pub(crate) fn check_balance(&self, command: &str, balance: u64) -> Result {
+    match self.max_balance {
+      Some(max_balance) if balance > max_balance => bail!(
+        "`{command}` may not be used on {} with wallets containing more than {} sats",
+        self.chain,
+        separate_thousands(max_balance)
+      ),
+      _ => Ok(()),
+    }
+  }
+
+  pub(crate) fn check_transaction_value(&self, value: u64) -> Result {
+    match self.max_transaction_value {
+      Some(max_transaction_value) if value > max_transaction_value => bail!(
+        "transaction spends {} sats, more than the {} limit of {} sats",
+        separate_thousands(value),
+        self.chain,
+        separate_thousands(max_transaction_value)
+      ),
+      _ => Ok(()),
+    }
+  }
+}
+
+fn separate_thousands(n: u64) -> String {
+  let digits = n.to_string();
+
+  let mut separated = String::new();
+  for (i, c) in digits.chars().enumerate() {
+    if i > 0 && (digits.len() - i) % 3 == 0 {
+      separated.push(',');
+    }
+    separated.push(c);
+  }
+
+  separated
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn mainnet() -> WalletPolicy {
+    WalletPolicy {
+      allowed_wallet_names: vec!["ord".into(), "ord-*".into()],
+      chain: Chain::Mainnet,
+      max_balance: Some(1_000_000),
+      max_transaction_value: Some(500_000),
+    }
+  }
+
+  #[test]
+  fn thousands_are_separated() {
+    assert_eq!(separate_thousands(0), "0");
+    assert_eq!(separate_thousands(999), "999");
+    assert_eq!(separate_thousands(1000), "1,000");
+    assert_eq!(separate_thousands(1_000_000), "1,000,000");
+    assert_eq!(separate_thousands(12_345_678), "12,345,678");
+  }
+
+  #[test]
+  fn wallet_names() {
+    let policy = mainnet();
+    policy.check_wallet_name("foo", "ord").unwrap();
+    policy.check_wallet_name("foo", "ord-bar").unwrap();
+    assert_eq!(
+      policy.check_wallet_name("foo", "ordinals").unwrap_err().to_string(),
+      "`foo` may only be used on mainnet with a wallet named `ord` or whose name starts with `ord-`"
+    );
+  }
+
+  #[test]
+  fn balance() {
+    let policy = mainnet();
+    policy.check_balance("foo", 1_000_000).unwrap();
+    assert_eq!(
+      policy
+        .check_balance("foo", 1_000_001)
+        .unwrap_err()
+        .to_string(),
+      "`foo` may not be used on mainnet with wallets containing more than 1,000,000 sats"
+    );
+  }
+
+  #[test]
+  fn transaction_value() {
+    let policy = mainnet();
+    policy.check_transaction_value(500_000).unwrap();
+    assert_eq!(
+      policy
+        .check_transaction_value(500_001)
+        .unwrap_err()
+        .to_string(),
+      "transaction spends 500,001 sats, more than the mainnet limit of 500,000 sats"
+    );
+  }
+
+  #[test]
+  fn permissive_allows_everything() {
+    let policy = WalletPolicy::permissive(Chain::Mainnet);
+    policy.check_wallet_name("foo", "bar").unwrap();
+    policy.check_balance("foo", u64::MAX).unwrap();
+    policy.check_transaction_value(u64::MAX).unwrap();
+  }
+}
@@ -66,6 +66,9 @@ pub trait Api {
     blockhash: Option<BlockHash>,
   ) -> Result<Value, jsonrpc_core::Error>;
 
+  #[rpc(name = "getrawmempool")]
+  fn get_raw_mempool(&self) -> Result<Vec<Txid>, jsonrpc_core::Error>;
+
   #[rpc(name = "listunspent")]
   fn list_unspent(
     &self,
@@ -78,4 +81,61 @@ pub trait Api {
 
   #[rpc(name = "getrawchangeaddress")]
   fn get_raw_change_address(&self) -> Result<bitcoin::Address, jsonrpc_core::Error>;
+
+  #[rpc(name = "lockunspent")]
+  fn lock_unspent(
+    &self,
+    unlock: bool,
+    outputs: Option<Vec<Value>>,
+  ) -> Result<bool, jsonrpc_core::Error>;
+
+  #[rpc(name = "listlockunspent")]
+  fn list_lock_unspent(&self) -> Result<Vec<Value>, jsonrpc_core::Error>;
+
+  #[rpc(name = "listtransactions")]
+  fn list_transactions(
+    &self,
+    label: Option<String>,
+    count: Option<usize>,
+    skip: Option<usize>,
+    include_watchonly: Option<bool>,
+  ) -> Result<Vec<Value>, jsonrpc_core::Error>;
+
+  #[rpc(name = "getaddressinfo")]
+  fn get_address_info(&self, address: bitcoin::Address) -> Result<Value, jsonrpc_core::Error>;
+
+  #[rpc(name = "getnewaddress")]
+  fn get_new_address(
+    &self,
+    label: Option<String>,
+    address_type: Option<()>,
+  ) -> Result<bitcoin::Address, jsonrpc_core::Error>;
+
+  #[rpc(name = "getaddressesbylabel")]
+  fn get_addresses_by_label(&self, label: String) -> Result<Value, jsonrpc_core::Error>;
+
+  #[rpc(name = "gettxout")]
+  fn get_tx_out(
+    &self,
+    txid: Txid,
+    vout: u32,
+    include_mempool: Option<bool>,
+  ) -> Result<Value, jsonrpc_core::Error>;
+
+  #[rpc(name = "getdescriptorinfo")]
+  fn get_descriptor_info(&self, descriptor: String) -> Result<Value, jsonrpc_core::Error>;
+
+  #[rpc(name = "deriveaddresses")]
+  fn derive_addresses(
+    &self,
+    descriptor: String,
+    range: Option<Value>,
+  ) -> Result<Vec<bitcoin::Address>, jsonrpc_core::Error>;
+
+  #[rpc(name = "scantxoutset")]
+  fn scan_tx_out_set(
+    &self,
+    action: String,
+    scan_objects: Vec<Value>,
+  ) -> Result<Value, jsonrpc_core::Error>;
 }
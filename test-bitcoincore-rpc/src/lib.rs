@@ -20,9 +20,8 @@ use {
   jsonrpc_http_server::{CloseHandle, ServerBuilder},
   server::Server,
   state::State,
-  std::collections::BTreeMap,
   std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     sync::{Arc, Mutex, MutexGuard},
     thread,
     time::Duration,
@@ -97,14 +96,21 @@ impl Handle {
   pub fn mine_blocks(&self, num: u64) -> Vec<Block> {
     let mut bitcoin_rpc_data = self.state.lock().unwrap();
     (0..num)
-      .map(|_| bitcoin_rpc_data.push_block(50 * COIN_VALUE))
+      .map(|_| bitcoin_rpc_data.push_block(50 * COIN_VALUE, Script::new()))
+      .collect()
+  }
+
+  pub fn mine_blocks_with_script_pubkey(&self, num: u64, script_pubkey: Script) -> Vec<Block> {
+    let mut bitcoin_rpc_data = self.state.lock().unwrap();
+    (0..num)
+      .map(|_| bitcoin_rpc_data.push_block(50 * COIN_VALUE, script_pubkey.clone()))
       .collect()
   }
 
   pub fn mine_blocks_with_subsidy(&self, num: u64, subsidy: u64) -> Vec<Block> {
     let mut bitcoin_rpc_data = self.state.lock().unwrap();
     (0..num)
-      .map(|_| bitcoin_rpc_data.push_block(subsidy))
+      .map(|_| bitcoin_rpc_data.push_block(subsidy, Script::new()))
       .collect()
   }
 
@@ -124,6 +130,10 @@ impl Handle {
   pub fn mempool(&self) -> Vec<Transaction> {
     self.state.lock().unwrap().mempool().to_vec()
   }
+
+  pub fn locked(&self) -> BTreeSet<OutPoint> {
+    self.state().locked.clone()
+  }
 }
 
 impl Drop for Handle {
@@ -18,6 +18,36 @@ impl Server {
   fn not_found() -> jsonrpc_core::Error {
     jsonrpc_core::Error::new(jsonrpc_core::types::error::ErrorCode::ServerError(-8))
   }
+
+  fn invalid_parameter(message: &str) -> jsonrpc_core::Error {
+    jsonrpc_core::Error {
+      code: jsonrpc_core::types::error::ErrorCode::ServerError(-8),
+      message: message.into(),
+      data: None,
+    }
+  }
+
+  /// Only `addr(ADDRESS)` descriptors, with or without a checksum, are
+  /// supported.
+  fn descriptor_address(descriptor: &str) -> Result<bitcoin::Address, jsonrpc_core::Error> {
+    let descriptor = descriptor
+      .split_once('#')
+      .map(|(descriptor, _checksum)| descriptor)
+      .unwrap_or(descriptor);
+
+    descriptor
+      .strip_prefix("addr(")
+      .and_then(|rest| rest.strip_suffix(')'))
+      .and_then(|address| address.parse().ok())
+      .ok_or_else(|| Self::invalid_parameter("descriptor"))
+  }
+
+  fn outpoint_from_value(value: &Value) -> OutPoint {
+    OutPoint::new(
+      value["txid"].as_str().unwrap().parse().unwrap(),
+      value["vout"].as_u64().unwrap().try_into().unwrap(),
+    )
+  }
 }
 
 impl Api for Server {
@@ -224,7 +254,18 @@ impl Api for Server {
 
   fn send_raw_transaction(&self, tx: String) -> Result<String, jsonrpc_core::Error> {
     let tx: Transaction = deserialize(&hex::decode(tx).unwrap()).unwrap();
-    self.state.lock().unwrap().mempool.push(tx.clone());
+
+    let mut state = self.state();
+
+    state.mempool.retain(|mempool_tx| {
+      !mempool_tx.input.iter().any(|mempool_tx_in| {
+        tx.input
+          .iter()
+          .any(|tx_in| tx_in.previous_output == mempool_tx_in.previous_output)
+      })
+    });
+
+    state.mempool.push(tx.clone());
 
     Ok(tx.txid().to_string())
   }
@@ -270,7 +311,12 @@ impl Api for Server {
   ) -> Result<Value, jsonrpc_core::Error> {
     assert_eq!(blockhash, None, "Blockhash param is unsupported");
     if verbose {
-      match self.state().transactions.get(&txid) {
+      let state = self.state();
+      let height = state
+        .hashes
+        .iter()
+        .position(|hash| state.blocks[hash].txdata.iter().any(|tx| tx.txid() == txid));
+      match state.transactions.get(&txid) {
         Some(_) => Ok(
           serde_json::to_value(GetRawTransactionResult {
             in_active_chain: None,
@@ -283,8 +329,8 @@ impl Api for Server {
             locktime: 0,
             vin: Vec::new(),
             vout: Vec::new(),
-            blockhash: None,
-            confirmations: Some(1),
+            blockhash: height.map(|height| state.hashes[height]),
+            confirmations: height.map(|height| (state.hashes.len() - height) as u32),
             time: None,
             blocktime: None,
           })
@@ -293,13 +339,22 @@ impl Api for Server {
         None => Err(Self::not_found()),
       }
     } else {
-      match self.state().transactions.get(&txid) {
+      let state = self.state();
+      match state
+        .transactions
+        .get(&txid)
+        .or_else(|| state.mempool.iter().find(|tx| tx.txid() == txid))
+      {
         Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
         None => Err(Self::not_found()),
       }
     }
   }
 
+  fn get_raw_mempool(&self) -> Result<Vec<Txid>, jsonrpc_core::Error> {
+    Ok(self.state().mempool.iter().map(|tx| tx.txid()).collect())
+  }
+
   fn list_unspent(
     &self,
     minconf: Option<usize>,
@@ -313,15 +368,27 @@ impl Api for Server {
     assert_eq!(address, None, "address param not supported");
     assert_eq!(include_unsafe, None, "include_unsafe param not supported");
     assert_eq!(query_options, None, "query_options param not supported");
+    let state = self.state();
+
+    let spent = state
+      .transactions
+      .values()
+      .chain(state.mempool.iter())
+      .flat_map(|tx| tx.input.iter().map(|tx_in| tx_in.previous_output))
+      .collect::<BTreeSet<OutPoint>>();
+
     Ok(
-      self
-        .state()
+      state
         .transactions
         .iter()
         .flat_map(|(txid, tx)| {
           tx.output
             .iter()
             .enumerate()
+            .filter(|(vout, _tx_out)| {
+              let outpoint = OutPoint::new(*txid, *vout as u32);
+              !spent.contains(&outpoint) && !state.locked.contains(&outpoint)
+            })
             .map(|(vout, tx_out)| ListUnspentResultEntry {
               txid: *txid,
               vout: vout as u32,
@@ -343,10 +410,346 @@ impl Api for Server {
   }
 
   fn get_raw_change_address(&self) -> Result<bitcoin::Address, jsonrpc_core::Error> {
-    Ok(
-      "tb1qjsv26lap3ffssj6hfy8mzn0lg5vte6a42j75ww"
+    let mut state = self.state();
+
+    let address: bitcoin::Address = match state.change_addresses.len() {
+      0 => "tb1qjsv26lap3ffssj6hfy8mzn0lg5vte6a42j75ww"
+        .parse()
+        .unwrap(),
+      1 => "tb1qakxxzv9n7706kc3xdcycrtfv8cqv62hnwexc0l"
         .parse()
         .unwrap(),
+      n => bitcoin::Address::p2wsh(
+        &bitcoin::blockdata::script::Builder::new()
+          .push_int(n.try_into().unwrap())
+          .into_script(),
+        self.network,
+      ),
+    };
+
+    state.change_addresses.push(address.clone());
+
+    Ok(address)
+  }
+
+  fn lock_unspent(
+    &self,
+    unlock: bool,
+    outputs: Option<Vec<Value>>,
+  ) -> Result<bool, jsonrpc_core::Error> {
+    let mut state = self.state();
+
+    let outputs: Vec<OutPoint> = match outputs {
+      Some(outputs) => outputs.iter().map(Self::outpoint_from_value).collect(),
+      None => {
+        assert!(unlock, "outputs param is required when locking");
+        state.locked.iter().cloned().collect()
+      }
+    };
+
+    for outpoint in &outputs {
+      if !state.transactions.contains_key(&outpoint.txid)
+        && !state.mempool.iter().any(|tx| tx.txid() == outpoint.txid)
+      {
+        return Err(Self::invalid_parameter(
+          "Invalid parameter, unknown transaction",
+        ));
+      }
+
+      if unlock && !state.locked.contains(outpoint) {
+        return Err(Self::invalid_parameter(
+          "Invalid parameter, expected locked output",
+        ));
+      }
+
+      if !unlock && state.locked.contains(outpoint) {
+        return Err(Self::invalid_parameter(
+          "Invalid parameter, output already locked",
+        ));
+      }
+    }
+
+    for outpoint in outputs {
+      if unlock {
+        state.locked.remove(&outpoint);
+      } else {
+        state.locked.insert(outpoint);
+      }
+    }
+
+    Ok(true)
+  }
+
+  fn list_lock_unspent(&self) -> Result<Vec<Value>, jsonrpc_core::Error> {
+    Ok(
+      self
+        .state()
+        .locked
+        .iter()
+        .map(|outpoint| {
+          serde_json::json!({
+            "txid": outpoint.txid,
+            "vout": outpoint.vout,
+          })
+        })
+        .collect(),
     )
   }
+
+  fn list_transactions(
+    &self,
+    label: Option<String>,
+    count: Option<usize>,
+    skip: Option<usize>,
+    include_watchonly: Option<bool>,
+  ) -> Result<Vec<Value>, jsonrpc_core::Error> {
+    assert_eq!(label.as_deref(), Some("*"), "label param not supported");
+    assert_eq!(
+      include_watchonly, None,
+      "include_watchonly param not supported"
+    );
+
+    let state = self.state();
+
+    let tip = state.hashes.len() - 1;
+
+    let mut entries = Vec::new();
+
+    for (height, hash) in state.hashes.iter().enumerate().skip(1) {
+      let confirmations = tip - height + 1;
+      for (i, tx) in state.blocks[hash].txdata.iter().enumerate() {
+        for vout in 0..tx.output.len() {
+          let category = if i > 0 {
+            "receive"
+          } else if confirmations < 100 {
+            "immature"
+          } else {
+            "generate"
+          };
+
+          entries.push(serde_json::json!({
+            "txid": tx.txid(),
+            "vout": vout,
+            "category": category,
+            "confirmations": confirmations,
+          }));
+        }
+      }
+    }
+
+    for tx in &state.mempool {
+      for vout in 0..tx.output.len() {
+        entries.push(serde_json::json!({
+          "txid": tx.txid(),
+          "vout": vout,
+          "category": "receive",
+          "confirmations": 0,
+        }));
+      }
+    }
+
+    let end = entries.len().saturating_sub(skip.unwrap_or(0));
+    let start = end.saturating_sub(count.unwrap_or(10));
+
+    Ok(entries[start..end].to_vec())
+  }
+
+  fn get_address_info(&self, address: bitcoin::Address) -> Result<Value, jsonrpc_core::Error> {
+    let state = self.state();
+    Ok(serde_json::json!({
+      "address": address,
+      "ismine": state.change_addresses.contains(&address)
+        || state.receive_addresses.contains(&address),
+    }))
+  }
+
+  fn get_new_address(
+    &self,
+    label: Option<String>,
+    address_type: Option<()>,
+  ) -> Result<bitcoin::Address, jsonrpc_core::Error> {
+    assert_eq!(
+      label.as_deref(),
+      Some("ord-receive"),
+      "label param not supported"
+    );
+    assert_eq!(address_type, None, "address_type param not supported");
+
+    let mut state = self.state();
+
+    let address = bitcoin::Address::p2wsh(
+      &bitcoin::blockdata::script::Builder::new()
+        .push_slice(b"receive")
+        .push_int(state.receive_addresses.len().try_into().unwrap())
+        .into_script(),
+      self.network,
+    );
+
+    state.receive_addresses.push(address.clone());
+
+    Ok(address)
+  }
+
+  fn get_addresses_by_label(&self, label: String) -> Result<Value, jsonrpc_core::Error> {
+    assert_eq!(label, "ord-receive", "label param not supported");
+
+    let state = self.state();
+
+    if state.receive_addresses.is_empty() {
+      return Err(jsonrpc_core::Error {
+        code: jsonrpc_core::types::error::ErrorCode::ServerError(-11),
+        message: format!("No addresses with label {label}"),
+        data: None,
+      });
+    }
+
+    Ok(Value::Object(
+      state
+        .receive_addresses
+        .iter()
+        .map(|address| {
+          (
+            address.to_string(),
+            serde_json::json!({ "purpose": "receive" }),
+          )
+        })
+        .collect(),
+    ))
+  }
+
+  fn get_tx_out(
+    &self,
+    txid: Txid,
+    vout: u32,
+    include_mempool: Option<bool>,
+  ) -> Result<Value, jsonrpc_core::Error> {
+    let state = self.state();
+
+    let include_mempool = include_mempool.unwrap_or(true);
+
+    let mempool = if include_mempool {
+      state.mempool.as_slice()
+    } else {
+      &[]
+    };
+
+    let outpoint = OutPoint::new(txid, vout);
+
+    if state.transactions.values().chain(mempool).any(|tx| {
+      tx.input
+        .iter()
+        .any(|tx_in| tx_in.previous_output == outpoint)
+    }) {
+      return Ok(Value::Null);
+    }
+
+    let (tx, confirmations) = match state.transactions.get(&txid) {
+      Some(tx) => (tx, 1),
+      None => match mempool.iter().find(|tx| tx.txid() == txid) {
+        Some(tx) => (tx, 0),
+        None => return Ok(Value::Null),
+      },
+    };
+
+    let tx_out = match tx.output.get(vout as usize) {
+      Some(tx_out) => tx_out,
+      None => return Ok(Value::Null),
+    };
+
+    Ok(serde_json::json!({
+      "bestblock": state.hashes.last().unwrap(),
+      "confirmations": confirmations,
+      "value": Amount::from_sat(tx_out.value).to_btc(),
+      "scriptPubKey": {
+        "asm": "",
+        "hex": hex::encode(tx_out.script_pubkey.as_bytes()),
+      },
+      "coinbase": tx.is_coin_base(),
+    }))
+  }
+
+  fn get_descriptor_info(&self, descriptor: String) -> Result<Value, jsonrpc_core::Error> {
+    let address = Self::descriptor_address(&descriptor)?;
+
+    Ok(serde_json::json!({
+      "descriptor": format!("addr({address})#00000000"),
+      "checksum": "00000000",
+      "isrange": false,
+      "issolvable": false,
+      "hasprivatekeys": false,
+    }))
+  }
+
+  fn derive_addresses(
+    &self,
+    descriptor: String,
+    range: Option<Value>,
+  ) -> Result<Vec<bitcoin::Address>, jsonrpc_core::Error> {
+    assert_eq!(range, None, "range param not supported");
+    Ok(vec![Self::descriptor_address(&descriptor)?])
+  }
+
+  fn scan_tx_out_set(
+    &self,
+    action: String,
+    scan_objects: Vec<Value>,
+  ) -> Result<Value, jsonrpc_core::Error> {
+    assert_eq!(action, "start", "action param not supported");
+
+    let state = self.state();
+
+    let mut unspents = Vec::new();
+    for scan_object in scan_objects {
+      let descriptor = scan_object["desc"]
+        .as_str()
+        .ok_or_else(|| Self::invalid_parameter("scanobjects"))?;
+
+      let script_pubkey = Self::descriptor_address(descriptor)?.script_pubkey();
+
+      for (txid, tx) in &state.transactions {
+        for (vout, tx_out) in tx.output.iter().enumerate() {
+          let outpoint = OutPoint::new(*txid, vout.try_into().unwrap());
+
+          if tx_out.script_pubkey != script_pubkey
+            || state.transactions.values().any(|tx| {
+              tx.input
+                .iter()
+                .any(|tx_in| tx_in.previous_output == outpoint)
+            })
+          {
+            continue;
+          }
+
+          let height = state
+            .hashes
+            .iter()
+            .position(|hash| {
+              state.blocks[hash]
+                .txdata
+                .iter()
+                .any(|tx| tx.txid() == *txid)
+            })
+            .unwrap();
+
+          unspents.push(serde_json::json!({
+            "txid": txid,
+            "vout": vout,
+            "scriptPubKey": hex::encode(script_pubkey.as_bytes()),
+            "desc": descriptor,
+            "amount": Amount::from_sat(tx_out.value).to_btc(),
+            "height": height,
+          }));
+        }
+      }
+    }
+
+    Ok(serde_json::json!({
+      "success": true,
+      "txouts": state.transactions.len(),
+      "height": state.hashes.len() - 1,
+      "bestblock": state.hashes.last().unwrap(),
+      "unspents": unspents,
+      "total_amount": 0.0,
+    }))
+  }
 }
@@ -2,10 +2,13 @@ use super::*;
 
 pub(crate) struct State {
   pub(crate) blocks: BTreeMap<BlockHash, Block>,
+  pub(crate) change_addresses: Vec<bitcoin::Address>,
   pub(crate) hashes: Vec<BlockHash>,
+  pub(crate) locked: BTreeSet<OutPoint>,
   pub(crate) mempool: Vec<Transaction>,
   pub(crate) network: Network,
   pub(crate) nonce: u32,
+  pub(crate) receive_addresses: Vec<bitcoin::Address>,
   pub(crate) transactions: BTreeMap<Txid, Transaction>,
   pub(crate) wallet_name: String,
 }
@@ -22,16 +25,19 @@ impl State {
 
     Self {
       blocks,
+      change_addresses: Vec::new(),
       hashes,
+      locked: BTreeSet::new(),
       mempool: Vec::new(),
       network,
       nonce: 0,
+      receive_addresses: Vec::new(),
       transactions: BTreeMap::new(),
       wallet_name: wallet_name.to_string(),
     }
   }
 
-  pub(crate) fn push_block(&mut self, subsidy: u64) -> Block {
+  pub(crate) fn push_block(&mut self, subsidy: u64, script_pubkey: Script) -> Block {
     let coinbase = Transaction {
       version: 0,
       lock_time: PackedLockTime(0),
@@ -60,7 +66,7 @@ impl State {
                 - tx.output.iter().map(|txout| txout.value).sum::<u64>()
             })
             .sum::<u64>(),
-        script_pubkey: Script::new(),
+        script_pubkey,
       }],
     };
 
@@ -29,6 +29,7 @@ impl<const N: usize> ToArgs for [&str; N] {
 
 pub(crate) struct CommandBuilder {
   args: Vec<String>,
+  env: Vec<(String, String)>,
   expected_exit_code: i32,
   expected_stderr: Expected,
   expected_stdout: Expected,
@@ -40,6 +41,7 @@ impl CommandBuilder {
   pub(crate) fn new(args: impl ToArgs) -> Self {
     Self {
       args: args.to_args(),
+      env: Vec::new(),
       expected_exit_code: 0,
       expected_stderr: Expected::String(String::new()),
       expected_stdout: Expected::String(String::new()),
@@ -53,6 +55,15 @@ impl CommandBuilder {
     self
   }
 
+  pub(crate) fn env(mut self, key: &str, value: &str) -> Self {
+    self.env.push((key.into(), value.into()));
+    self
+  }
+
+  pub(crate) fn temp_dir(self, tempdir: TempDir) -> Self {
+    Self { tempdir, ..self }
+  }
+
   pub(crate) fn rpc_server(self, rpc_server: &test_bitcoincore_rpc::Handle) -> Self {
     Self {
       rpc_server_url: Some(rpc_server.url()),
@@ -114,6 +125,7 @@ impl CommandBuilder {
       .stdout(Stdio::piped())
       .stderr(Stdio::piped())
       .env("HOME", self.tempdir.path())
+      .envs(self.env.iter().cloned())
       .current_dir(&self.tempdir)
       .args(&self.args);
 
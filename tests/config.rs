@@ -0,0 +1,76 @@
+use super::*;
+
+#[test]
+fn config_file_in_data_dir_is_loaded() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  CommandBuilder::new("--data-dir . find 0")
+    .write("ord.yaml", "chain: signet\n")
+    .rpc_server(&rpc_server)
+    .stdout_regex(".*:0:0\n")
+    .run();
+}
+
+#[test]
+fn config_flag_is_respected() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  CommandBuilder::new("--config foo.yaml find 0")
+    .write("foo.yaml", "chain: signet\n")
+    .rpc_server(&rpc_server)
+    .stdout_regex(".*:0:0\n")
+    .run();
+}
+
+#[test]
+fn toml_config_file_is_loaded() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  CommandBuilder::new("--config foo.toml find 0")
+    .write("foo.toml", "chain = \"signet\"\n")
+    .rpc_server(&rpc_server)
+    .stdout_regex(".*:0:0\n")
+    .run();
+}
+
+#[test]
+fn environment_overrides_config_file() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  CommandBuilder::new("--data-dir . find 0")
+    .write("ord.yaml", "chain: regtest\n")
+    .env("ORD_CHAIN", "signet")
+    .rpc_server(&rpc_server)
+    .stdout_regex(".*:0:0\n")
+    .run();
+}
+
+#[test]
+fn command_line_overrides_environment() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  CommandBuilder::new("--chain signet find 0")
+    .env("ORD_CHAIN", "regtest")
+    .rpc_server(&rpc_server)
+    .stdout_regex(".*:0:0\n")
+    .run();
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+  CommandBuilder::new("--data-dir . epochs")
+    .write("ord.yaml", "foo: bar\n")
+    .stderr_regex(
+      "error: failed to parse config file `./ord.yaml`\nbecause: unknown field `foo`.*\n",
+    )
+    .expected_exit_code(1)
+    .run();
+}
+
+#[test]
+fn missing_config_file_is_an_error() {
+  CommandBuilder::new("--config foo.yaml epochs")
+    .stderr_regex("error: failed to open config file `foo.yaml`\nbecause: .*\n")
+    .expected_exit_code(1)
+    .run();
+}
@@ -44,3 +44,13 @@ fn empty() {
     )
     .run();
 }
+
+#[test]
+fn json() {
+  let stdout = CommandBuilder::new("--format json epochs")
+    .stdout_regex(r"(?s)\[\n  0,\n  1050000000000000,\n.*\]\n")
+    .run()
+    .stdout;
+
+  assert_eq!(stdout.lines().count(), 36);
+}
@@ -18,3 +18,28 @@ fn unmined_ordinal() {
     .expected_exit_code(1)
     .run();
 }
+
+#[test]
+fn json() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  CommandBuilder::new("--format json find 0")
+    .rpc_server(&rpc_server)
+    .expected_stdout(
+      r#"{
+  "satpoint": "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0"
+}
+"#,
+    )
+    .run();
+}
+
+#[test]
+fn lost_ordinal() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks_with_subsidy(1, 0);
+  CommandBuilder::new("find 5000000000")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: ordinal was lost in block 1\n")
+    .expected_exit_code(1)
+    .run();
+}
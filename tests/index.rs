@@ -27,3 +27,29 @@ fn custom_index_size() {
     1 << 20
   );
 }
+
+#[test]
+fn rpc_user_and_pass_are_used_instead_of_cookie_file() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new(format!(
+    "--rpc-url {} --cookie-file missing --rpc-user foo --rpc-pass bar find 0",
+    rpc_server.url()
+  ))
+  .expected_stdout("4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0\n")
+  .run();
+}
+
+#[test]
+fn rpc_user_and_pass_may_be_given_in_environment() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new(format!(
+    "--rpc-url {} --cookie-file missing find 0",
+    rpc_server.url()
+  ))
+  .env("ORD_RPC_USER", "foo")
+  .env("ORD_RPC_PASS", "bar")
+  .expected_stdout("4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0\n")
+  .run();
+}
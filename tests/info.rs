@@ -11,6 +11,7 @@ fn basic() {
         utxos indexed\t1
         outputs traversed\t1
         ordinal ranges\t1
+        lost ordinals\t0
         tree height\t\\d+
         free pages\t\\d+
         stored\t.*
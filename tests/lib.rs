@@ -2,7 +2,7 @@
 
 use {
   self::{command_builder::CommandBuilder, expected::Expected},
-  bitcoin::{blockdata::constants::COIN_VALUE, Network, OutPoint},
+  bitcoin::{blockdata::constants::COIN_VALUE, Address, Network, OutPoint},
   executable_path::executable_path,
   pretty_assertions::assert_eq as pretty_assert_eq,
   regex::Regex,
@@ -19,6 +19,7 @@ use {
 };
 
 mod command_builder;
+mod config;
 mod epochs;
 mod expected;
 mod find;
@@ -32,3 +33,4 @@ mod supply;
 mod traits;
 mod version;
 mod wallet;
+mod watch;
@@ -18,3 +18,33 @@ fn output_not_found() {
     .expected_stderr("error: output not found\n")
     .run();
 }
+
+#[test]
+fn json() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  CommandBuilder::new(
+    "--format json list 4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0",
+  )
+  .rpc_server(&rpc_server)
+  .expected_stdout(
+    r#"[
+  {
+    "start": 0,
+    "end": 5000000000
+  }
+]
+"#,
+  )
+  .run();
+}
+
+#[test]
+fn tsv() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  CommandBuilder::new(
+    "--format tsv list 4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0",
+  )
+  .rpc_server(&rpc_server)
+  .expected_stdout("0\t5000000000\n")
+  .run();
+}
@@ -55,3 +55,17 @@ fn block_with_no_subsidy_range() {
     .expected_stdout("[,)\n")
     .run();
 }
+
+#[test]
+fn json() {
+  CommandBuilder::new("--format json range 1")
+    .expected_stdout("{\n  \"start\": 5000000000,\n  \"end\": 10000000000\n}\n")
+    .run();
+}
+
+#[test]
+fn tsv_names() {
+  CommandBuilder::new("--format tsv range --name 0")
+    .expected_stdout("nvtdijuwxlp\tnvtcsezkbth\n")
+    .run();
+}
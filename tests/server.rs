@@ -33,3 +33,108 @@ fn run() {
 
   child.kill().unwrap();
 }
+
+#[test]
+fn read_only_server_requires_existing_index() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new("server --read-only --http-port 0")
+    .rpc_server(&rpc_server)
+    .stderr_regex("error: index `.*index.redb` does not exist, run `ord index` to create it\n")
+    .expected_exit_code(1)
+    .run();
+}
+
+#[test]
+fn read_only_server_does_not_update_index() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new("index").rpc_server(&rpc_server).run();
+
+  rpc_server.mine_blocks(1);
+
+  let port = TcpListener::bind("127.0.0.1:0")
+    .unwrap()
+    .local_addr()
+    .unwrap()
+    .port();
+
+  let builder = CommandBuilder::new(format!("server --read-only --http-port {}", port))
+    .rpc_server(&rpc_server)
+    .temp_dir(output.tempdir);
+
+  let mut child = builder.command().spawn().unwrap();
+
+  for attempt in 0.. {
+    if let Ok(response) = reqwest::blocking::get(format!("http://localhost:{port}/height")) {
+      if response.status() == 200 {
+        assert_eq!(response.text().unwrap(), "1");
+        break;
+      }
+    }
+
+    if attempt == 100 {
+      panic!("Server did not respond to height request");
+    }
+
+    thread::sleep(Duration::from_millis(50));
+  }
+
+  child.kill().unwrap();
+}
+
+#[test]
+fn read_only_server_sees_blocks_indexed_by_index_watch() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  let data_dir = TempDir::new().unwrap();
+  let data_dir = data_dir.path().display();
+
+  CommandBuilder::new(format!("--data-dir {data_dir} index"))
+    .rpc_server(&rpc_server)
+    .run();
+
+  let port = TcpListener::bind("127.0.0.1:0")
+    .unwrap()
+    .local_addr()
+    .unwrap()
+    .port();
+
+  let index_builder =
+    CommandBuilder::new(format!("--data-dir {data_dir} index --watch")).rpc_server(&rpc_server);
+
+  let server_builder = CommandBuilder::new(format!(
+    "--data-dir {data_dir} server --read-only --http-port {port}"
+  ))
+  .rpc_server(&rpc_server);
+
+  let mut index = index_builder.command().spawn().unwrap();
+  let mut server = server_builder.command().spawn().unwrap();
+
+  let wait_for_height = |height: &str| {
+    for attempt in 0.. {
+      if let Ok(response) = reqwest::blocking::get(format!("http://localhost:{port}/height")) {
+        if response.status() == 200 && response.text().unwrap() == height {
+          break;
+        }
+      }
+
+      if attempt == 100 {
+        panic!("Server did not reach height {height}");
+      }
+
+      thread::sleep(Duration::from_millis(50));
+    }
+  };
+
+  wait_for_height("1");
+
+  rpc_server.mine_blocks(1);
+
+  wait_for_height("2");
+
+  server.kill().unwrap();
+  index.kill().unwrap();
+}
@@ -14,3 +14,33 @@ fn genesis() {
     )
     .run();
 }
+
+#[test]
+fn json() {
+  CommandBuilder::new("--format json supply")
+    .expected_stdout(
+      r#"{
+  "supply": 2099999997690000,
+  "first": 0,
+  "last": 2099999997689999,
+  "last_mined_in_block": 6929999
+}
+"#,
+    )
+    .run();
+}
+
+#[test]
+fn tsv() {
+  CommandBuilder::new("--format tsv supply")
+    .expected_stdout(
+      "
+        supply\t2099999997690000
+        first\t0
+        last\t2099999997689999
+        last_mined_in_block\t6929999
+      "
+      .unindent(),
+    )
+    .run();
+}
@@ -19,3 +19,43 @@ rarity: mythic
     )
     .run();
 }
+
+#[test]
+fn json() {
+  CommandBuilder::new("--format json traits 0")
+    .expected_stdout(
+      r#"{
+  "number": 0,
+  "decimal": "0.0",
+  "degree": "0°0′0″0‴",
+  "name": "nvtdijuwxlp",
+  "height": 0,
+  "cycle": 0,
+  "epoch": 0,
+  "period": 0,
+  "offset": 0,
+  "rarity": "mythic"
+}
+"#,
+    )
+    .run();
+}
+
+#[test]
+fn tsv() {
+  CommandBuilder::new("--format tsv traits 0")
+    .expected_stdout(
+      "number\t0
+decimal\t0.0
+degree\t0°0′0″0‴
+name\tnvtdijuwxlp
+height\t0
+cycle\t0
+epoch\t0
+period\t0
+offset\t0
+rarity\tmythic
+",
+    )
+    .run();
+}
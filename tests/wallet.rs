@@ -1,4 +1,13 @@
-use super::*;
+use {
+  super::*,
+  bitcoin::{
+    secp256k1::{schnorr, Message, Secp256k1, XOnlyPublicKey},
+    util::sighash::{Prevouts, SighashCache},
+    SchnorrSighashType, Transaction, TxOut,
+  },
+  std::collections::BTreeSet,
+  test_bitcoincore_rpc::TransactionTemplate,
+};
 
 #[test]
 fn identify() {
@@ -8,7 +17,7 @@ fn identify() {
   CommandBuilder::new("wallet identify")
     .rpc_server(&rpc_server)
     .expected_stdout(format!(
-      "{}\t{}\t0\tuncommon\n",
+      "{}\t{}\t0\tuncommon\tchange\n",
       OutPoint::new(second_coinbase, 0),
       50 * COIN_VALUE,
     ))
@@ -24,7 +33,7 @@ fn identify_from_tsv_success() {
     .write("foo.tsv", "nvtcsezkbtg")
     .rpc_server(&rpc_server)
     .expected_stdout(format!(
-      "{}\tnvtcsezkbtg\n",
+      "{}\tnvtcsezkbtg\tchange\n",
       OutPoint::new(second_coinbase, 0),
     ))
     .run();
@@ -63,7 +72,33 @@ fn list() {
   CommandBuilder::new("wallet list")
     .rpc_server(&rpc_server)
     .expected_stdout(format!(
-      "{}\t{}\t{}\tuncommon\tnvtcsezkbth\n",
+      "{}\t{}\t{}\tuncommon\tnvtcsezkbth\tchange\n",
+      OutPoint::new(second_coinbase, 0),
+      50 * COIN_VALUE,
+      50 * COIN_VALUE,
+    ))
+    .run();
+}
+
+#[test]
+fn list_json() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  let second_coinbase = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new("--format json wallet list")
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!(
+      r#"[
+  {{
+    "output": "{}",
+    "start": {},
+    "size": {},
+    "rarity": "uncommon",
+    "name": "nvtcsezkbth",
+    "purpose": "change"
+  }}
+]
+"#,
       OutPoint::new(second_coinbase, 0),
       50 * COIN_VALUE,
       50 * COIN_VALUE,
@@ -159,3 +194,674 @@ fn send_on_mainnnet_refuses_to_work_with_wallet_with_high_balance() {
     .expected_exit_code(1)
     .run();
 }
+
+#[test]
+fn send_on_mainnnet_works_with_high_balance_if_user_understands_the_risks() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Bitcoin, "foo");
+  rpc_server.mine_blocks_with_subsidy(1, 1_000_001);
+
+  CommandBuilder::new(
+    "--i-understand-the-risks wallet send 5000000000 bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh",
+  )
+  .rpc_server(&rpc_server)
+  .stdout_regex(r".*")
+  .run();
+
+  assert_eq!(rpc_server.mempool().len(), 1);
+}
+
+#[test]
+fn send_respects_allowed_wallet_names() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "foo");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "--chain signet --allowed-wallet-name bar-* wallet send 5000000000 tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw",
+  )
+  .rpc_server(&rpc_server)
+  .expected_stderr("error: `ord wallet send` may only be used on signet with a wallet whose name starts with `bar-`\n")
+  .expected_exit_code(1)
+  .run();
+}
+
+#[test]
+fn send_respects_max_transaction_value() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "--chain signet --max-transaction-value 1000000 wallet send 5000000000 tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw",
+  )
+  .rpc_server(&rpc_server)
+  .expected_stderr(
+    "error: transaction spends 5,000,000,000 sats, more than the signet limit of 1,000,000 sats\n",
+  )
+  .expected_exit_code(1)
+  .run();
+
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn lock_rare() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  let outpoint = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+
+  CommandBuilder::new("wallet lock-rare")
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{outpoint}\n"))
+    .run();
+
+  assert_eq!(
+    rpc_server.locked(),
+    [outpoint].into_iter().collect::<BTreeSet<OutPoint>>()
+  );
+}
+
+#[test]
+fn locked_outputs_are_still_identified() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  let outpoint = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+
+  let output = CommandBuilder::new("wallet lock-rare")
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{outpoint}\n"))
+    .run();
+
+  CommandBuilder::new("wallet identify")
+    .temp_dir(output.tempdir)
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!(
+      "{outpoint}\t{}\t0\tuncommon\tchange\n",
+      50 * COIN_VALUE
+    ))
+    .run();
+}
+
+#[test]
+fn unlock_all() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  let outpoint = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+
+  let output = CommandBuilder::new("wallet lock-rare")
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{outpoint}\n"))
+    .run();
+
+  CommandBuilder::new("wallet unlock")
+    .temp_dir(output.tempdir)
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{outpoint}\n"))
+    .run();
+
+  assert!(rpc_server.locked().is_empty());
+}
+
+#[test]
+fn unlock_outpoint() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  let outpoint = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+
+  CommandBuilder::new("wallet lock-rare")
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{outpoint}\n"))
+    .run();
+
+  CommandBuilder::new(format!("wallet unlock {outpoint}"))
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{outpoint}\n"))
+    .run();
+
+  assert!(rpc_server.locked().is_empty());
+}
+
+#[test]
+fn send_locks_rare_change_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  rpc_server.mine_blocks(2);
+  rpc_server.broadcast_tx(TransactionTemplate {
+    input_slots: &[(1, 0, 0), (2, 0, 0)],
+    output_count: 1,
+    fee: 0,
+  });
+  let coinbase = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+
+  let output = CommandBuilder::new("--chain signet wallet lock-rare")
+    .rpc_server(&rpc_server)
+    .stdout_regex(r".*")
+    .run();
+
+  let output = CommandBuilder::new(format!(
+    "--chain signet wallet send {} tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw",
+    100 * COIN_VALUE
+  ))
+  .temp_dir(output.tempdir)
+  .rpc_server(&rpc_server)
+  .stdout_regex(r".*")
+  .run();
+
+  let txid = rpc_server.mempool()[0].txid();
+  assert_eq!(format!("{}\n", txid), output.stdout);
+
+  assert_eq!(
+    rpc_server.locked(),
+    [coinbase, OutPoint::new(txid, 0)]
+      .into_iter()
+      .collect::<BTreeSet<OutPoint>>()
+  );
+}
+
+#[test]
+fn extract() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let coinbase = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+
+  let output = CommandBuilder::new("--chain signet wallet extract")
+    .rpc_server(&rpc_server)
+    .stdout_regex(r".*")
+    .run();
+
+  let transaction = &rpc_server.mempool()[0];
+  assert_eq!(format!("{}\n", transaction.txid()), output.stdout);
+  assert_eq!(transaction.input.len(), 1);
+  assert_eq!(transaction.input[0].previous_output, coinbase);
+  assert_eq!(transaction.output.len(), 2);
+  assert_eq!(transaction.output[0].value, 10_000);
+}
+
+#[test]
+fn extract_without_rare_ordinals() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  CommandBuilder::new("--chain signet wallet extract")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: wallet contains no rare ordinals to extract\n")
+    .expected_exit_code(1)
+    .run();
+}
+
+#[test]
+fn consolidate() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  rpc_server.mine_blocks(1);
+  let txid = rpc_server.broadcast_tx(TransactionTemplate {
+    input_slots: &[(1, 0, 0)],
+    output_count: 5,
+    fee: 0,
+  });
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new("--chain signet wallet consolidate --fee-rate 2")
+    .rpc_server(&rpc_server)
+    .stdout_regex(r".*")
+    .run();
+
+  let transaction = &rpc_server.mempool()[0];
+  assert_eq!(format!("{}\n", transaction.txid()), output.stdout);
+  assert_eq!(
+    transaction
+      .input
+      .iter()
+      .map(|tx_in| tx_in.previous_output)
+      .collect::<Vec<OutPoint>>(),
+    (1..5)
+      .map(|vout| OutPoint::new(txid, vout))
+      .collect::<Vec<OutPoint>>()
+  );
+  assert_eq!(transaction.output.len(), 1);
+}
+
+#[test]
+fn consolidate_requires_two_cardinal_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain signet wallet consolidate")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: wallet contains fewer than two cardinal outputs to consolidate\n")
+    .expected_exit_code(1)
+    .run();
+}
+
+#[test]
+fn history() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let coinbase = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+  let txid = rpc_server.broadcast_tx(TransactionTemplate {
+    input_slots: &[(1, 0, 0)],
+    output_count: 2,
+    fee: 0,
+  });
+
+  CommandBuilder::new("--chain signet wallet history")
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!(
+      "{coinbase}\timmature\t{}\t{}\tuncommon\tnvtcsezkbth\n\
+       {}\treceive\t{}\t{}\tuncommon\tnvtcsezkbth\n\
+       {}\treceive\t{}\t{}\tcommon\tnvtckcoqqxd\n",
+      50 * COIN_VALUE,
+      50 * COIN_VALUE,
+      OutPoint::new(txid, 0),
+      50 * COIN_VALUE,
+      25 * COIN_VALUE,
+      OutPoint::new(txid, 1),
+      75 * COIN_VALUE,
+      25 * COIN_VALUE,
+    ))
+    .run();
+}
+
+#[test]
+fn history_marks_spent_outputs_incomplete() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let coinbase = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+  let txid = rpc_server.broadcast_tx(TransactionTemplate {
+    input_slots: &[(1, 0, 0)],
+    output_count: 1,
+    fee: 0,
+  });
+  let second_coinbase = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+
+  CommandBuilder::new("--chain signet wallet history")
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!(
+      "{coinbase}\timmature\tincomplete\n\
+       {second_coinbase}\timmature\t{}\t{}\tuncommon\tnvtccadxgaz\n\
+       {}\treceive\t{}\t{}\tuncommon\tnvtcsezkbth\n",
+      100 * COIN_VALUE,
+      50 * COIN_VALUE,
+      OutPoint::new(txid, 0),
+      50 * COIN_VALUE,
+      50 * COIN_VALUE,
+    ))
+    .run();
+}
+
+#[test]
+fn bump() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let coinbase = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+
+  let output = CommandBuilder::new(format!(
+    "--chain signet wallet send {} tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw",
+    50 * COIN_VALUE
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex(r".*")
+  .run();
+
+  let original = rpc_server.mempool()[0].clone();
+  assert!(original.is_explicitly_rbf());
+  assert_eq!(format!("{}\n", original.txid()), output.stdout);
+
+  let output = CommandBuilder::new(format!(
+    "--chain signet wallet bump {} --fee-rate 2",
+    original.txid()
+  ))
+  .temp_dir(output.tempdir)
+  .rpc_server(&rpc_server)
+  .stdout_regex(r".*")
+  .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 1);
+
+  let replacement = &mempool[0];
+  assert_eq!(format!("{}\n", replacement.txid()), output.stdout);
+  assert!(replacement.is_explicitly_rbf());
+  assert_eq!(replacement.input.len(), 1);
+  assert_eq!(replacement.input[0].previous_output, coinbase);
+  assert_eq!(replacement.output[0], original.output[0]);
+  assert_eq!(
+    replacement.output[1].value,
+    50 * COIN_VALUE - 10_000 - 2 * 263
+  );
+}
+
+#[test]
+fn bump_fee_rate_must_be_positive() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  CommandBuilder::new(format!(
+    "--chain signet wallet bump {} --fee-rate 0",
+    "0".repeat(64)
+  ))
+  .rpc_server(&rpc_server)
+  .stderr_regex("error: Invalid value \"0\" for '--fee-rate <FEE_RATE>'.*")
+  .expected_exit_code(2)
+  .run();
+}
+
+#[test]
+fn bump_confirmed_transaction() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!(
+    "--chain signet wallet send {} tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw",
+    50 * COIN_VALUE
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex(r".*")
+  .run();
+
+  let txid = rpc_server.mempool()[0].txid();
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!("--chain signet wallet bump {txid} --fee-rate 2"))
+    .temp_dir(output.tempdir)
+    .rpc_server(&rpc_server)
+    .expected_stderr(format!(
+      "error: transaction {txid} has already been confirmed\n"
+    ))
+    .expected_exit_code(1)
+    .run();
+}
+
+#[test]
+fn cpfp() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  rpc_server.mine_blocks(2);
+  let split = rpc_server.broadcast_tx(TransactionTemplate {
+    input_slots: &[(2, 0, 0)],
+    output_count: 2,
+    fee: 0,
+  });
+  rpc_server.mine_blocks(1);
+
+  let parent = rpc_server.broadcast_tx(TransactionTemplate {
+    input_slots: &[(1, 0, 0)],
+    output_count: 1,
+    fee: 0,
+  });
+
+  let output = CommandBuilder::new(format!(
+    "--chain signet wallet cpfp {}:0 --fee-rate 2",
+    parent
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex(r".*")
+  .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 2);
+
+  let child = &mempool[1];
+  assert_eq!(format!("{}\n", child.txid()), output.stdout);
+  assert_eq!(
+    child
+      .input
+      .iter()
+      .map(|tx_in| tx_in.previous_output)
+      .collect::<Vec<OutPoint>>(),
+    [OutPoint::new(parent, 0), OutPoint::new(split, 1)]
+  );
+  assert_eq!(child.output[0].value, 50 * COIN_VALUE);
+  assert_eq!(
+    child.output[1].value,
+    25 * COIN_VALUE - 2 * (mempool[0].vsize() as u64 + 430)
+  );
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain signet wallet list")
+    .rpc_server(&rpc_server)
+    .temp_dir(output.tempdir)
+    .stdout_regex(format!(
+      ".*{}:0\t[0-9]+\t[0-9]+\t[a-z]+\t[a-z]+\treceive\n.*",
+      child.txid()
+    ))
+    .run();
+}
+
+/// Check that every input of `transaction`, spending `prevouts`, has a valid
+/// taproot key path signature.
+fn assert_taproot_signatures_valid(transaction: &Transaction, prevouts: &[TxOut]) {
+  let secp = Secp256k1::verification_only();
+
+  for (input, tx_in) in transaction.input.iter().enumerate() {
+    let sighash = SighashCache::new(transaction)
+      .taproot_key_spend_signature_hash(
+        input,
+        &Prevouts::All(prevouts),
+        SchnorrSighashType::Default,
+      )
+      .unwrap();
+
+    secp
+      .verify_schnorr(
+        &schnorr::Signature::from_slice(&tx_in.witness.to_vec()[0]).unwrap(),
+        &Message::from_slice(sighash.as_ref()).unwrap(),
+        &XOnlyPublicKey::from_slice(&prevouts[input].script_pubkey.as_bytes()[2..]).unwrap(),
+      )
+      .unwrap();
+  }
+}
+
+const MNEMONIC: &str = "([a-z]+ ){23}[a-z]+\n";
+
+/// Create a signet keychain wallet with `passphrase` and mine a block paying
+/// its coinbase to a receive address, returning the wallet's data directory,
+/// its mnemonic and the coinbase transaction.
+fn create_and_receive(
+  rpc_server: &test_bitcoincore_rpc::Handle,
+  passphrase: &str,
+) -> (TempDir, String, Transaction) {
+  let output = CommandBuilder::new("--chain signet wallet create")
+    .env("ORD_WALLET_PASSPHRASE", passphrase)
+    .rpc_server(rpc_server)
+    .stdout_regex(MNEMONIC)
+    .run();
+
+  let mnemonic = output.stdout.trim().to_string();
+
+  let output = CommandBuilder::new("--chain signet wallet receive")
+    .rpc_server(rpc_server)
+    .temp_dir(output.tempdir)
+    .stdout_regex("tb1p.*\n")
+    .run();
+
+  let address = output.stdout.trim().parse::<Address>().unwrap();
+
+  let coinbase =
+    rpc_server.mine_blocks_with_script_pubkey(1, address.script_pubkey())[0].txdata[0].clone();
+
+  (output.tempdir, mnemonic, coinbase)
+}
+
+/// Check that the wallet in `tempdir` holds only the output of `coinbase`,
+/// and send its first ordinal, checking that the transaction is signed.
+fn list_and_send(
+  rpc_server: &test_bitcoincore_rpc::Handle,
+  tempdir: TempDir,
+  passphrase: &str,
+  coinbase: &Transaction,
+) -> Output {
+  let outpoint = OutPoint::new(coinbase.txid(), 0);
+
+  let output = CommandBuilder::new("--chain signet wallet list")
+    .rpc_server(rpc_server)
+    .temp_dir(tempdir)
+    .expected_stdout(format!(
+      "{outpoint}\t{}\t{}\tuncommon\tnvtcsezkbth\treceive\n",
+      50 * COIN_VALUE,
+      50 * COIN_VALUE
+    ))
+    .run();
+
+  let output = CommandBuilder::new(format!(
+    "--chain signet wallet send {} tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw",
+    50 * COIN_VALUE
+  ))
+  .env("ORD_WALLET_PASSPHRASE", passphrase)
+  .rpc_server(rpc_server)
+  .temp_dir(output.tempdir)
+  .stdout_regex(".*")
+  .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 1);
+  assert_eq!(format!("{}\n", mempool[0].txid()), output.stdout);
+  assert_eq!(mempool[0].input[0].previous_output, outpoint);
+
+  assert_taproot_signatures_valid(&mempool[0], &[coinbase.output[0].clone()]);
+
+  output
+}
+
+#[test]
+fn create() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  let (tempdir, _mnemonic, coinbase) = create_and_receive(&rpc_server, "foo");
+
+  list_and_send(&rpc_server, tempdir, "foo", &coinbase);
+}
+
+#[test]
+fn restore() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  let (_tempdir, mnemonic, coinbase) = create_and_receive(&rpc_server, "foo");
+
+  let output = CommandBuilder::new("--chain signet wallet restore")
+    .env("ORD_WALLET_PASSPHRASE", "bar")
+    .env("ORD_WALLET_MNEMONIC", &mnemonic)
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{}\n", OutPoint::new(coinbase.txid(), 0)))
+    .run();
+
+  list_and_send(&rpc_server, output.tempdir, "bar", &coinbase);
+}
+
+#[test]
+fn restore_rejects_invalid_mnemonic() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  CommandBuilder::new("--chain signet wallet restore --mnemonic foo")
+    .env("ORD_WALLET_PASSPHRASE", "foo")
+    .rpc_server(&rpc_server)
+    .stderr_regex("error: Invalid value \"foo\" for '--mnemonic <MNEMONIC>'.*")
+    .expected_exit_code(2)
+    .run();
+}
+
+#[test]
+fn create_refuses_to_overwrite_wallet() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  let output = CommandBuilder::new("wallet create")
+    .env("ORD_WALLET_PASSPHRASE", "foo")
+    .rpc_server(&rpc_server)
+    .stdout_regex(MNEMONIC)
+    .run();
+
+  CommandBuilder::new("wallet create")
+    .env("ORD_WALLET_PASSPHRASE", "foo")
+    .rpc_server(&rpc_server)
+    .temp_dir(output.tempdir)
+    .stderr_regex("error: wallet already exists at `.*wallet.json`\n")
+    .expected_exit_code(1)
+    .run();
+}
+
+#[test]
+fn create_requires_passphrase() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new("wallet create")
+    .rpc_server(&rpc_server)
+    .expected_stderr(
+      "error: wallet passphrase must be set with `ORD_WALLET_PASSPHRASE`\nbecause: environment variable not found\n",
+    )
+    .expected_exit_code(1)
+    .run();
+}
+
+#[test]
+fn receive() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  let output = CommandBuilder::new("wallet receive")
+    .rpc_server(&rpc_server)
+    .stdout_regex("bc1q.*\n")
+    .run();
+
+  let address = output.stdout.trim().parse::<Address>().unwrap();
+
+  let received = OutPoint::new(
+    rpc_server.mine_blocks_with_script_pubkey(1, address.script_pubkey())[0].txdata[0].txid(),
+    0,
+  );
+
+  let change = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+
+  let stdout = CommandBuilder::new("wallet list")
+    .rpc_server(&rpc_server)
+    .temp_dir(output.tempdir)
+    .stdout_regex(".*")
+    .run()
+    .stdout;
+
+  let mut lines = stdout.lines().collect::<Vec<&str>>();
+  lines.sort();
+
+  let mut expected = vec![
+    format!(
+      "{received}\t{}\t{}\tuncommon\tnvtcsezkbth\treceive",
+      50 * COIN_VALUE,
+      50 * COIN_VALUE
+    ),
+    format!(
+      "{change}\t{}\t{}\tuncommon\tnvtccadxgaz\tchange",
+      100 * COIN_VALUE,
+      50 * COIN_VALUE
+    ),
+  ];
+  expected.sort();
+
+  assert_eq!(lines, expected);
+}
+
+#[test]
+fn keychain_history() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  let (tempdir, _mnemonic, coinbase) = create_and_receive(&rpc_server, "foo");
+
+  let coinbase = OutPoint::new(coinbase.txid(), 0);
+
+  let output = CommandBuilder::new("--chain signet wallet history")
+    .rpc_server(&rpc_server)
+    .temp_dir(tempdir)
+    .expected_stdout(format!(
+      "{coinbase}\timmature\t{}\t{}\tuncommon\tnvtcsezkbth\n",
+      50 * COIN_VALUE,
+      50 * COIN_VALUE
+    ))
+    .run();
+
+  let output = CommandBuilder::new(format!(
+    "--chain signet wallet send {} tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw",
+    50 * COIN_VALUE
+  ))
+  .env("ORD_WALLET_PASSPHRASE", "foo")
+  .rpc_server(&rpc_server)
+  .temp_dir(output.tempdir)
+  .stdout_regex(".*")
+  .run();
+
+  let send = OutPoint::new(output.stdout.trim().parse().unwrap(), 0);
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain signet wallet history")
+    .rpc_server(&rpc_server)
+    .temp_dir(output.tempdir)
+    .stdout_regex(format!(
+      "{coinbase}\timmature\tincomplete\n{send}\tsend\t{}\t[0-9]+\tuncommon\tnvtcsezkbth\n.*",
+      50 * COIN_VALUE,
+    ))
+    .run();
+}
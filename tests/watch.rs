@@ -0,0 +1,86 @@
+use super::*;
+
+const ADDRESS: &str = "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu";
+
+#[test]
+fn add_and_list() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  let script_pubkey = ADDRESS.parse::<Address>().unwrap().script_pubkey();
+
+  let existing = OutPoint::new(
+    rpc_server.mine_blocks_with_script_pubkey(1, script_pubkey.clone())[0].txdata[0].txid(),
+    0,
+  );
+
+  let output = CommandBuilder::new(format!("watch add {ADDRESS}"))
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{ADDRESS}\n"))
+    .run();
+
+  let new = OutPoint::new(
+    rpc_server.mine_blocks_with_script_pubkey(1, script_pubkey)[0].txdata[0].txid(),
+    0,
+  );
+
+  rpc_server.mine_blocks(1);
+
+  let stdout = CommandBuilder::new("watch list")
+    .rpc_server(&rpc_server)
+    .temp_dir(output.tempdir)
+    .stdout_regex(".*")
+    .run()
+    .stdout;
+
+  let mut lines = stdout.lines().collect::<Vec<&str>>();
+  lines.sort();
+
+  let mut expected = vec![
+    format!(
+      "{existing}\t{ADDRESS}\t{}\t{}\tuncommon\tnvtcsezkbth",
+      50 * COIN_VALUE,
+      50 * COIN_VALUE
+    ),
+    format!(
+      "{new}\t{ADDRESS}\t{}\t{}\tuncommon\tnvtccadxgaz",
+      100 * COIN_VALUE,
+      50 * COIN_VALUE
+    ),
+  ];
+  expected.sort();
+
+  assert_eq!(lines, expected);
+}
+
+#[test]
+fn add_rejects_address_for_other_network() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new("watch add tb1qjsv26lap3ffssj6hfy8mzn0lg5vte6a42j75ww")
+    .rpc_server(&rpc_server)
+    .expected_stderr(
+      "error: Address `tb1qjsv26lap3ffssj6hfy8mzn0lg5vte6a42j75ww` is not valid for mainnet\n",
+    )
+    .expected_exit_code(1)
+    .run();
+}
+
+#[test]
+fn list_is_empty_without_watched_scripts() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new("watch list")
+    .rpc_server(&rpc_server)
+    .expected_stdout("")
+    .run();
+}
+
+#[test]
+fn list_json_is_empty_array_without_watched_scripts() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new("--format json watch list")
+    .rpc_server(&rpc_server)
+    .expected_stdout("[]\n")
+    .run();
+}